@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::errors::AttachError;
+use crate::types::Receipt;
+
+/// Copies `source` into `dir` under a name derived from its SHA-256, so identical files are stored once.
+pub fn store_receipt(dir: &Path, source: &Path) -> Result<Receipt, AttachError> {
+    let contents =
+        fs::read(source).map_err(|e| AttachError::Io(format!("Failed to read {}: {}", source.display(), e)))?;
+    let sha256: String = Sha256::digest(&contents).iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let file_name = match source.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}.{}", sha256, ext.to_lowercase()),
+        None => sha256.clone(),
+    };
+    let path = dir.join(file_name);
+
+    if !path.exists() {
+        fs::create_dir_all(dir)
+            .map_err(|e| AttachError::Io(format!("Failed to create {}: {}", dir.display(), e)))?;
+        fs::write(&path, &contents)
+            .map_err(|e| AttachError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+    }
+
+    let original_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(Receipt { path, sha256, original_name })
+}
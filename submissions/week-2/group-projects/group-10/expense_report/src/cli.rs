@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+use crate::errors::{AddError, AttachError, EditError, ImportError, RemoveError, WorkflowError};
+use crate::manager::ExpenseManager;
+use crate::policy;
+use crate::reports;
+use crate::storage::ExpenseStore;
+use crate::types::{Expense, ExpenseStatus, NewExpense, WorkflowAction};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_INVALID_INPUT: i32 = 4;
+/// The expense is in a state (or the approver has a role) that does not allow the request.
+pub const EXIT_NOT_ALLOWED: i32 = 5;
+pub const EXIT_STORAGE: i32 = 6;
+
+pub const USAGE: &str = "\
+Usage: expense_report [COMMAND]
+
+Runs the interactive menu when no command is given.
+
+Commands:
+  add --name <NAME> --category <CATEGORY> --amount <AMOUNT> --employee <EMPLOYEE>
+      [--date <YYYY-MM-DD>] [--merchant <MERCHANT>] [--notes <TEXT>]
+  list [--status <STATUS>] [--format table|csv|json]
+  edit <ID> --editor <NAME> [--name <NAME>] [--category <CATEGORY>] [--amount <AMOUNT>]
+  remove <ID>
+  approve <ID> --approver <NAME> [--comment <TEXT>]
+  reject <ID> --approver <NAME> [--comment <TEXT>]
+  reimburse <ID> --approver <NAME> [--comment <TEXT>]
+  override <ID> --approver <NAME> --reason <TEXT>
+  attach <ID> <RECEIPT_FILE>
+  import <CSV_FILE> --employee <EMPLOYEE>
+  help
+
+Statuses: submitted, manager-approved, finance-approved, approved (either), reimbursed, rejected
+
+Exit codes: 0 ok, 2 usage, 3 not found, 4 invalid input (including imports with rejected rows),
+5 not allowed (including approving an expense with unresolved policy flags), 6 storage or file error";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Add(NewExpense),
+    List { statuses: Vec<ExpenseStatus>, format: OutputFormat },
+    Edit { id: u32, editor: String, name: Option<String>, category: Option<String>, amount: Option<String> },
+    Remove { id: u32 },
+    Review { id: u32, action: WorkflowAction, approver: String, comment: String },
+    Override { id: u32, approver: String, reason: String },
+    Attach { id: u32, path: PathBuf },
+    Import { path: PathBuf, employee: String },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CliError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl CliError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    fn usage(message: impl Into<String>) -> Self {
+        Self::new(EXIT_USAGE, message)
+    }
+}
+
+/// Parses the arguments after the program name.
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let (command, rest) = args.split_first().ok_or_else(|| CliError::usage("No command given"))?;
+    let (positional, mut flags) = split_flags(rest)?;
+
+    let parsed = match command.as_str() {
+        "add" => {
+            no_positional(&positional)?;
+            let incurred_on = match flags.remove("date") {
+                Some(date) => Some(
+                    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                        .map_err(|_| CliError::new(EXIT_INVALID_INPUT, format!("Invalid date '{}'", date)))?,
+                ),
+                None => None,
+            };
+            Command::Add(NewExpense {
+                name: required(&mut flags, "name")?,
+                category: required(&mut flags, "category")?,
+                amount: required(&mut flags, "amount")?,
+                employee: required(&mut flags, "employee")?,
+                incurred_on,
+                merchant: flags.remove("merchant").unwrap_or_default(),
+                notes: flags.remove("notes").unwrap_or_default(),
+            })
+        }
+        "list" => {
+            no_positional(&positional)?;
+            let statuses = match flags.remove("status") {
+                Some(status) => parse_status_filter(&status)?,
+                None => Vec::new(),
+            };
+            let format = match flags.remove("format").as_deref() {
+                None | Some("table") => OutputFormat::Table,
+                Some("csv") => OutputFormat::Csv,
+                Some("json") => OutputFormat::Json,
+                Some(other) => return Err(CliError::usage(format!("Unknown format '{}'", other))),
+            };
+            Command::List { statuses, format }
+        }
+        "edit" => Command::Edit {
+            id: single_id(&positional)?,
+            editor: required(&mut flags, "editor")?,
+            name: flags.remove("name"),
+            category: flags.remove("category"),
+            amount: flags.remove("amount"),
+        },
+        "remove" => Command::Remove { id: single_id(&positional)? },
+        "approve" | "reject" | "reimburse" => {
+            let action = match command.as_str() {
+                "approve" => WorkflowAction::Approve,
+                "reject" => WorkflowAction::Reject,
+                _ => WorkflowAction::Reimburse,
+            };
+            Command::Review {
+                id: single_id(&positional)?,
+                action,
+                approver: required(&mut flags, "approver")?,
+                comment: flags.remove("comment").unwrap_or_default(),
+            }
+        }
+        "override" => Command::Override {
+            id: single_id(&positional)?,
+            approver: required(&mut flags, "approver")?,
+            reason: required(&mut flags, "reason")?,
+        },
+        "attach" => match positional.as_slice() {
+            [id, path] => Command::Attach {
+                id: id.parse().map_err(|_| CliError::usage(format!("Invalid ID '{}'", id)))?,
+                path: PathBuf::from(path),
+            },
+            _ => return Err(CliError::usage("attach needs an expense ID and a receipt file")),
+        },
+        "import" => match positional.as_slice() {
+            [path] => Command::Import { path: PathBuf::from(path), employee: required(&mut flags, "employee")? },
+            _ => return Err(CliError::usage("import needs exactly one CSV file")),
+        },
+        "help" | "--help" | "-h" => Command::Help,
+        other => return Err(CliError::usage(format!("Unknown command '{}'", other))),
+    };
+
+    if let Some(flag) = flags.keys().next() {
+        return Err(CliError::usage(format!("Unexpected option --{} for '{}'", flag, command)));
+    }
+    Ok(parsed)
+}
+
+/// Runs one command and returns what should be printed on success.
+pub fn run<S: ExpenseStore>(manager: &mut ExpenseManager<S>, command: Command) -> Result<String, CliError> {
+    match command {
+        Command::Add(details) => {
+            let id = manager.add_expense_with(details).map_err(add_error)?;
+            Ok(format!("{}", id))
+        }
+        Command::List { statuses, format } => {
+            let expenses: Vec<&Expense> = manager
+                .view_expenses()
+                .into_iter()
+                .filter(|e| statuses.is_empty() || statuses.contains(&e.status))
+                .collect();
+            render_expenses(&expenses, format)
+        }
+        Command::Edit { id, editor, name, category, amount } => {
+            if name.is_none() && category.is_none() && amount.is_none() {
+                return Err(CliError::usage("Nothing to edit: pass --name, --category or --amount"));
+            }
+            manager.edit_expense(id, &editor, name, amount, category, true).map_err(edit_error)?;
+            Ok(format!("Expense {} updated", id))
+        }
+        Command::Remove { id } => {
+            manager.remove_expense(id).map_err(remove_error)?;
+            Ok(format!("Expense {} removed", id))
+        }
+        Command::Review { id, action, approver, comment } => {
+            let result = match action {
+                WorkflowAction::Approve => manager.approve(id, &approver, comment),
+                WorkflowAction::Reject => manager.reject(id, &approver, comment),
+                WorkflowAction::Reimburse => manager.reimburse(id, &approver, comment),
+            };
+            let status = result.map_err(|e| workflow_error(e, &approver))?;
+            Ok(format!("Expense {} is now: {}", id, status))
+        }
+        Command::Override { id, approver, reason } => {
+            manager.override_policy(id, &approver, reason).map_err(|e| workflow_error(e, &approver))?;
+            Ok(format!("Policy override recorded for expense {}", id))
+        }
+        Command::Attach { id, path } => {
+            let receipt = manager.attach_receipt(id, &path).map_err(attach_error)?;
+            Ok(format!("Receipt stored at {} (sha256 {})", receipt.path.display(), receipt.sha256))
+        }
+        Command::Import { path, employee } => {
+            let file = File::open(&path)
+                .map_err(|e| CliError::new(EXIT_STORAGE, format!("Failed to open {}: {}", path.display(), e)))?;
+            let report = manager.import_csv(file, &employee).map_err(import_error)?;
+            if report.errors.is_empty() {
+                Ok(report.summary())
+            } else {
+                Err(CliError::new(EXIT_INVALID_INPUT, report.summary()))
+            }
+        }
+        Command::Help => Ok(USAGE.to_string()),
+    }
+}
+
+fn render_expenses(expenses: &[&Expense], format: OutputFormat) -> Result<String, CliError> {
+    if format == OutputFormat::Json {
+        return serde_json::to_string_pretty(expenses)
+            .map_err(|e| CliError::new(EXIT_STORAGE, format!("Failed to serialize expenses: {}", e)));
+    }
+
+    let headers: Vec<String> = ["ID", "Date", "Name", "Merchant", "Category", "Amount", "Employee", "Status", "Flags"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    let rows: Vec<Vec<String>> = expenses
+        .iter()
+        .map(|e| {
+            vec![
+                e.id.to_string(),
+                e.incurred_on.to_string(),
+                e.name.clone(),
+                e.merchant.clone(),
+                e.category.clone(),
+                e.amount.to_string(),
+                e.employee.clone(),
+                e.status.to_string(),
+                match (&e.policy_override, e.flags.is_empty()) {
+                    (_, true) => String::new(),
+                    (Some(_), false) => format!("{} (overridden)", policy::describe(&e.flags)),
+                    (None, false) => policy::describe(&e.flags),
+                },
+            ]
+        })
+        .collect();
+
+    Ok(match format {
+        OutputFormat::Csv => reports::render_csv(&headers, &rows),
+        _ => reports::render_table(&headers, &rows),
+    })
+}
+
+fn split_flags(args: &[String]) -> Result<(Vec<String>, HashMap<String, String>), CliError> {
+    let mut positional = Vec::new();
+    let mut flags = HashMap::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            positional.push(arg.clone());
+            continue;
+        };
+        let (key, value) = match flag.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => {
+                let value = iter.next().ok_or_else(|| CliError::usage(format!("Missing value for --{}", flag)))?;
+                (flag.to_string(), value.clone())
+            }
+        };
+        if flags.insert(key.clone(), value).is_some() {
+            return Err(CliError::usage(format!("--{} given more than once", key)));
+        }
+    }
+
+    Ok((positional, flags))
+}
+
+fn required(flags: &mut HashMap<String, String>, name: &str) -> Result<String, CliError> {
+    match flags.remove(name) {
+        Some(value) if !value.trim().is_empty() => Ok(value),
+        _ => Err(CliError::usage(format!("Missing required option --{}", name))),
+    }
+}
+
+fn no_positional(positional: &[String]) -> Result<(), CliError> {
+    match positional.first() {
+        Some(arg) => Err(CliError::usage(format!("Unexpected argument '{}'", arg))),
+        None => Ok(()),
+    }
+}
+
+fn single_id(positional: &[String]) -> Result<u32, CliError> {
+    match positional {
+        [id] => id.parse().map_err(|_| CliError::usage(format!("Invalid ID '{}'", id))),
+        [] => Err(CliError::usage("Missing expense ID")),
+        [_, extra, ..] => Err(CliError::usage(format!("Unexpected argument '{}'", extra))),
+    }
+}
+
+fn parse_status_filter(status: &str) -> Result<Vec<ExpenseStatus>, CliError> {
+    let statuses = match status.to_lowercase().as_str() {
+        "submitted" => vec![ExpenseStatus::Submitted],
+        "manager-approved" => vec![ExpenseStatus::ManagerApproved],
+        "finance-approved" => vec![ExpenseStatus::FinanceApproved],
+        "approved" => vec![ExpenseStatus::ManagerApproved, ExpenseStatus::FinanceApproved],
+        "reimbursed" => vec![ExpenseStatus::Reimbursed],
+        "rejected" => vec![ExpenseStatus::Rejected],
+        other => return Err(CliError::usage(format!("Unknown status '{}'", other))),
+    };
+    Ok(statuses)
+}
+
+fn add_error(e: AddError) -> CliError {
+    let code = match e {
+        AddError::InvalidAmount(_) => EXIT_INVALID_INPUT,
+        AddError::Storage(_) => EXIT_STORAGE,
+    };
+    CliError::new(code, e.to_string())
+}
+
+fn edit_error(e: EditError) -> CliError {
+    let code = match e {
+        EditError::NotFound => EXIT_NOT_FOUND,
+        EditError::InvalidAmount(_) => EXIT_INVALID_INPUT,
+        EditError::NotEditable | EditError::Cancelled => EXIT_NOT_ALLOWED,
+        EditError::Storage(_) => EXIT_STORAGE,
+    };
+    CliError::new(code, e.to_string())
+}
+
+fn remove_error(e: RemoveError) -> CliError {
+    let code = match e {
+        RemoveError::NotFound => EXIT_NOT_FOUND,
+        RemoveError::CannotRemovePending => EXIT_NOT_ALLOWED,
+        RemoveError::Storage(_) => EXIT_STORAGE,
+    };
+    CliError::new(code, e.to_string())
+}
+
+/// Messages about the approver name them, since the error itself does not carry the name.
+fn workflow_error(e: WorkflowError, approver: &str) -> CliError {
+    let code = match e {
+        WorkflowError::NotFound | WorkflowError::UnknownApprover => EXIT_NOT_FOUND,
+        WorkflowError::WrongRole { .. }
+        | WorkflowError::IllegalTransition { .. }
+        | WorkflowError::AlreadyApproved
+        | WorkflowError::PolicyViolation(_)
+        | WorkflowError::NothingToOverride => EXIT_NOT_ALLOWED,
+        WorkflowError::MissingReason | WorkflowError::Currency(_) => EXIT_INVALID_INPUT,
+        WorkflowError::Storage(_) => EXIT_STORAGE,
+    };
+    let message = match e {
+        WorkflowError::UnknownApprover | WorkflowError::WrongRole { .. } | WorkflowError::AlreadyApproved => {
+            format!("'{}': {}", approver, e)
+        }
+        _ => e.to_string(),
+    };
+    CliError::new(code, message)
+}
+
+fn attach_error(e: AttachError) -> CliError {
+    let code = match e {
+        AttachError::NotFound => EXIT_NOT_FOUND,
+        AttachError::Io(_) | AttachError::Storage(_) => EXIT_STORAGE,
+    };
+    CliError::new(code, e.to_string())
+}
+
+fn import_error(e: ImportError) -> CliError {
+    let code = match e {
+        ImportError::MissingColumn(_) | ImportError::Csv(_) => EXIT_INVALID_INPUT,
+        ImportError::Storage(_) => EXIT_STORAGE,
+    };
+    CliError::new(code, e.to_string())
+}
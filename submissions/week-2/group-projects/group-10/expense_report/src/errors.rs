@@ -1,13 +1,216 @@
+use std::fmt;
+
+use crate::money::Currency;
+use crate::policy::{self, PolicyFlag};
+use crate::types::{ApproverRole, ExpenseStatus, WorkflowAction};
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(String),
+    Corrupt(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(message) | StorageError::Corrupt(message) => f.write_str(message),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MoneyError {
+    InvalidFormat,
+    Negative,
+    NonFinite,
+    TooManyDecimals,
+    Overflow,
+    InvalidCurrency(String),
+    CurrencyMismatch(Currency, Currency),
+    MissingRate(Currency),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::InvalidFormat => write!(f, "expected an amount like 12.50 or 12.50 USD"),
+            MoneyError::Negative => write!(f, "amount cannot be negative"),
+            MoneyError::NonFinite => write!(f, "amount must be a finite number"),
+            MoneyError::TooManyDecimals => write!(f, "too many decimal places for the currency"),
+            MoneyError::Overflow => write!(f, "amount is too large"),
+            MoneyError::InvalidCurrency(code) => write!(f, "'{}' is not a three-letter currency code", code),
+            MoneyError::CurrencyMismatch(a, b) => write!(f, "cannot combine {} with {}", a, b),
+            MoneyError::MissingRate(currency) => write!(f, "no exchange rate configured for {}", currency),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AddError {
+    InvalidAmount(MoneyError),
+    Storage(StorageError),
+}
+
+impl fmt::Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddError::InvalidAmount(e) => write!(f, "Invalid amount: {}", e),
+            AddError::Storage(e) => write!(f, "Failed to save expense: {}", e),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum RemoveError {
     NotFound,
     CannotRemovePending,
+    Storage(StorageError),
+}
+
+impl fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoveError::NotFound => f.write_str("Expense not found"),
+            RemoveError::CannotRemovePending => {
+                f.write_str("Cannot remove expenses still in review. Reimburse or reject the expense first")
+            }
+            RemoveError::Storage(e) => write!(f, "Failed to save changes: {}", e),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum EditError {
     NotFound,
-    InvalidAmount,
-    InvalidStatus,
+    InvalidAmount(MoneyError),
+    NotEditable,
     Cancelled,
+    Storage(StorageError),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::NotFound => f.write_str("Expense not found"),
+            EditError::InvalidAmount(e) => write!(f, "Invalid amount: {}", e),
+            EditError::NotEditable => f.write_str("Only submitted expenses that have not been reviewed can be edited"),
+            EditError::Cancelled => f.write_str("Edit cancelled"),
+            EditError::Storage(e) => write!(f, "Failed to save changes: {}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WorkflowError {
+    NotFound,
+    UnknownApprover,
+    WrongRole { required: ApproverRole, actual: ApproverRole },
+    IllegalTransition { from: ExpenseStatus, action: WorkflowAction },
+    AlreadyApproved,
+    /// The expense breaks policy rules and nobody has recorded an override yet.
+    PolicyViolation(Vec<PolicyFlag>),
+    NothingToOverride,
+    MissingReason,
+    Currency(MoneyError),
+    Storage(StorageError),
+}
+
+impl fmt::Display for WorkflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkflowError::NotFound => f.write_str("Expense not found"),
+            WorkflowError::UnknownApprover => f.write_str("Not a registered approver"),
+            WorkflowError::WrongRole { required, actual } => {
+                write!(f, "This step needs a {:?} approver, not a {:?} one", required, actual)
+            }
+            WorkflowError::IllegalTransition { from, action } => {
+                write!(f, "Cannot {:?} an expense that is {}", action, from)
+            }
+            WorkflowError::AlreadyApproved => {
+                f.write_str("This approver already approved this stage. A second approver is required")
+            }
+            WorkflowError::PolicyViolation(flags) => {
+                write!(f, "Expense breaks policy ({}). Record an override reason first", policy::describe(flags))
+            }
+            WorkflowError::NothingToOverride => {
+                f.write_str("Only pending expenses with policy flags can be overridden")
+            }
+            WorkflowError::MissingReason => f.write_str("An override needs a reason"),
+            WorkflowError::Currency(e) => write!(f, "Cannot check the approval threshold: {}", e),
+            WorkflowError::Storage(e) => write!(f, "Failed to save changes: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PolicyError {
+    Parse(String),
+    InvalidCap { category: String, error: MoneyError },
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::Parse(reason) => write!(f, "invalid policy file: {}", reason),
+            PolicyError::InvalidCap { category, error } => write!(f, "invalid cap for {}: {}", category, error),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AttachError {
+    NotFound,
+    Io(String),
+    Storage(StorageError),
+}
+
+impl fmt::Display for AttachError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachError::NotFound => f.write_str("Expense not found"),
+            AttachError::Io(message) => f.write_str(message),
+            AttachError::Storage(e) => write!(f, "Failed to save changes: {}", e),
+        }
+    }
+}
+
+/// Problems that stop a whole import.
+#[derive(Debug)]
+pub enum ImportError {
+    MissingColumn(String),
+    Csv(String),
+    Storage(StorageError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::MissingColumn(column) => write!(f, "The CSV file has no '{}' column", column),
+            ImportError::Csv(message) => write!(f, "Cannot read CSV: {}", message),
+            ImportError::Storage(e) => write!(f, "Failed to save imported expenses: {}", e),
+        }
+    }
+}
+
+/// Problems with a single import row; the row is skipped and the import carries on.
+#[derive(Debug, PartialEq)]
+pub enum RowError {
+    Malformed(String),
+    MissingField(&'static str),
+    InvalidDate(String),
+    InvalidAmount(MoneyError),
+    /// A refund or deposit, which is not an expense.
+    Credit(String),
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowError::Malformed(reason) => write!(f, "malformed row: {}", reason),
+            RowError::MissingField(field) => write!(f, "missing {}", field),
+            RowError::InvalidDate(date) => write!(f, "unrecognised date '{}'", date),
+            RowError::InvalidAmount(e) => write!(f, "invalid amount: {}", e),
+            RowError::Credit(amount) => write!(f, "{} is a credit, not an expense", amount),
+        }
+    }
 }
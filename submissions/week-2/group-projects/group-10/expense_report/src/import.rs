@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use chrono::NaiveDate;
+
+use crate::errors::{ImportError, RowError};
+use crate::money::{Currency, Money};
+use crate::types::{Expense, NewExpense};
+
+/// Category given to imported rows that do not have one.
+pub const DEFAULT_CATEGORY: &str = "Uncategorized";
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y"];
+
+/// Accepted header names for each column, compared case-insensitively.
+const DATE_HEADERS: [&str; 3] = ["date", "transaction date", "posted date"];
+const MERCHANT_HEADERS: [&str; 4] = ["merchant", "description", "payee", "name"];
+/// A signed amount column, where money spent is negative.
+const AMOUNT_HEADERS: [&str; 1] = ["amount"];
+/// A debit column, where money spent is positive.
+const DEBIT_HEADERS: [&str; 1] = ["debit"];
+const CURRENCY_HEADERS: [&str; 1] = ["currency"];
+const CATEGORY_HEADERS: [&str; 1] = ["category"];
+const NOTES_HEADERS: [&str; 3] = ["notes", "memo", "reference"];
+
+/// A validated row from a bank export, ready to be submitted.
+#[derive(Debug, Clone)]
+pub struct BankRow {
+    /// Line in the source file, counting the header as line 1.
+    pub line: u64,
+    pub details: NewExpense,
+    pub amount: Money,
+}
+
+impl BankRow {
+    pub fn duplicate_key(&self) -> DuplicateKey {
+        DuplicateKey::new(&self.details.merchant, self.details.incurred_on.unwrap_or_default(), self.amount)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RowIssue {
+    pub line: u64,
+    pub error: RowError,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub imported: Vec<u32>,
+    /// Lines skipped because an expense with the same merchant, date and amount already exists.
+    pub duplicates: Vec<u64>,
+    /// Lines skipped because they are refunds or deposits rather than spend.
+    pub credits: Vec<u64>,
+    pub errors: Vec<RowIssue>,
+}
+
+impl ImportReport {
+    /// One summary line followed by a line per skipped row.
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "Imported {} expense(s), skipped {} duplicate(s), {} credit(s), {} row(s) with errors",
+            self.imported.len(),
+            self.duplicates.len(),
+            self.credits.len(),
+            self.errors.len()
+        )];
+        lines.extend(self.duplicates.iter().map(|line| format!("line {}: duplicate of an existing expense", line)));
+        lines.extend(self.credits.iter().map(|line| format!("line {}: credit, not an expense", line)));
+        lines.extend(self.errors.iter().map(|issue| format!("line {}: {}", issue.line, issue.error)));
+        lines.join("\n")
+    }
+}
+
+/// Two expenses are treated as the same charge when merchant, date and amount all match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DuplicateKey {
+    merchant: String,
+    date: NaiveDate,
+    amount: Money,
+}
+
+impl DuplicateKey {
+    pub fn new(merchant: &str, date: NaiveDate, amount: Money) -> Self {
+        Self { merchant: merchant.trim().to_lowercase(), date, amount }
+    }
+
+    pub fn of(expense: &Expense) -> Self {
+        Self::new(&expense.merchant, expense.incurred_on, expense.amount)
+    }
+}
+
+/// Reads a bank export. Needs date, merchant (or description/payee) and amount (or debit)
+/// columns; currency, category and notes (or memo) are optional.
+///
+/// An `Amount` column is signed the way banks export it: spend is negative and refunds or
+/// deposits are positive. A `Debit` column lists spend as positive numbers, and a negative
+/// debit is a reversal. Either way only spend is imported; credits come back as
+/// `RowError::Credit`. Thousands separators are ignored. Rows that fail validation are
+/// returned as errors rather than stopping the import.
+pub fn parse_bank_csv<R: Read>(
+    reader: R,
+    default_currency: Currency,
+    employee: &str,
+) -> Result<Vec<Result<BankRow, RowIssue>>, ImportError> {
+    let mut csv = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(reader);
+
+    let headers: HashMap<String, usize> = csv
+        .headers()
+        .map_err(|e| ImportError::Csv(e.to_string()))?
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_lowercase(), i))
+        .collect();
+    let column = |names: &[&str]| names.iter().find_map(|name| headers.get(*name).copied());
+    let require = |names: &[&str]| column(names).ok_or_else(|| ImportError::MissingColumn(names[0].to_string()));
+
+    let date_col = require(&DATE_HEADERS)?;
+    let merchant_col = require(&MERCHANT_HEADERS)?;
+    // A signed amount column is preferred when an export has both
+    let (amount_col, spend_is_negative) = match (column(&AMOUNT_HEADERS), column(&DEBIT_HEADERS)) {
+        (Some(col), _) => (col, true),
+        (None, Some(col)) => (col, false),
+        (None, None) => return Err(ImportError::MissingColumn(AMOUNT_HEADERS[0].to_string())),
+    };
+    let currency_col = column(&CURRENCY_HEADERS);
+    let category_col = column(&CATEGORY_HEADERS);
+    let notes_col = column(&NOTES_HEADERS);
+
+    let mut rows = Vec::new();
+    for (index, record) in csv.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(index as u64 + 2);
+                rows.push(Err(RowIssue { line, error: RowError::Malformed(e.to_string()) }));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(index as u64 + 2);
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("").to_string();
+
+        let parsed = parse_row(
+            &field(Some(date_col)),
+            &field(Some(merchant_col)),
+            &field(Some(amount_col)),
+            &field(currency_col),
+            default_currency,
+            spend_is_negative,
+        );
+        rows.push(match parsed {
+            Ok((incurred_on, merchant, amount)) => {
+                let category = field(category_col);
+                Ok(BankRow {
+                    line,
+                    details: NewExpense {
+                        name: merchant.clone(),
+                        category: if category.is_empty() { DEFAULT_CATEGORY.to_string() } else { category },
+                        amount: amount.to_string(),
+                        employee: employee.to_string(),
+                        incurred_on: Some(incurred_on),
+                        merchant,
+                        notes: field(notes_col),
+                    },
+                    amount,
+                })
+            }
+            Err(error) => Err(RowIssue { line, error }),
+        });
+    }
+
+    Ok(rows)
+}
+
+fn parse_row(
+    date: &str,
+    merchant: &str,
+    amount: &str,
+    currency: &str,
+    default_currency: Currency,
+    spend_is_negative: bool,
+) -> Result<(NaiveDate, String, Money), RowError> {
+    if date.is_empty() {
+        return Err(RowError::MissingField("date"));
+    }
+    let incurred_on = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| RowError::InvalidDate(date.to_string()))?;
+
+    if merchant.is_empty() {
+        return Err(RowError::MissingField("merchant"));
+    }
+
+    if amount.is_empty() {
+        return Err(RowError::MissingField("amount"));
+    }
+    let cleaned = amount.replace(',', "");
+    let (negative, magnitude) = match cleaned.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, cleaned.as_str()),
+    };
+    let money = if currency.is_empty() {
+        Money::parse(magnitude, default_currency)
+    } else {
+        Money::parse(&format!("{} {}", magnitude, currency), default_currency)
+    }
+    .map_err(RowError::InvalidAmount)?;
+
+    if negative != spend_is_negative && !money.is_zero() {
+        return Err(RowError::Credit(amount.to_string()));
+    }
+    Ok((incurred_on, merchant.to_string(), money))
+}
@@ -1,3 +1,10 @@
 pub mod types;
 pub mod manager;
 pub mod errors;
+pub mod money;
+pub mod attachments;
+pub mod import;
+pub mod policy;
+pub mod reports;
+pub mod storage;
+pub mod cli;
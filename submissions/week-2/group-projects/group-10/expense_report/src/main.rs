@@ -1,10 +1,31 @@
-use expense_report::errors::{EditError, RemoveError};
+use expense_report::cli;
+use chrono::NaiveDate;
+use expense_report::errors::{MoneyError, WorkflowError};
 use expense_report::manager::ExpenseManager;
-use expense_report::types::ExpenseStatus;
+use expense_report::money::{ExchangeRates, Money};
+use expense_report::policy::{self, Policy};
+use expense_report::reports::{self, GroupBy};
+use expense_report::storage::{ExpenseStore, JsonFileStore};
+use expense_report::types::{ApproverRole, NewExpense};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
 use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+const DEFAULT_DATA_FILE: &str = "expenses.json";
+const DEFAULT_RATES_FILE: &str = "rates.txt";
+const DEFAULT_BUDGETS_FILE: &str = "budgets.txt";
+const DEFAULT_POLICY_FILE: &str = "policy.json";
 
 fn main() {
-    run_program();
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        run_program();
+    } else {
+        process::exit(run_command(&args));
+    }
 }
 
 fn get_input(prompt: &str) -> String {
@@ -28,7 +49,7 @@ fn get_confirmation(prompt: &str) -> bool {
     }
 }
 
-fn add_expense_menu(manager: &mut ExpenseManager) {
+fn add_expense_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
     println!("Add New Expense");
 
     let name = get_input("Enter expense name: ");
@@ -43,19 +64,48 @@ fn add_expense_menu(manager: &mut ExpenseManager) {
         return;
     }
 
-    let amount = get_input("Enter expense amount: ");
+    let amount = get_input(&format!("Enter expense amount (e.g. 12.50 or 12.50 EUR, default {}): ", manager.rates.base()));
     if amount.is_empty() {
         println!("Error: Amount cannot be empty");
         return;
     }
 
-    match manager.add_expense(name, category, amount) {
-        Ok(id) => println!("Expense added successfully with ID: {}", id),
-        Err(e) => println!("Error adding expense: {}", e),
+    let employee = get_input("Enter employee name: ");
+    if employee.is_empty() {
+        println!("Error: Employee name cannot be empty");
+        return;
+    }
+
+    let incurred_on = get_input("Enter the date incurred (YYYY-MM-DD, blank for today): ");
+    let incurred_on = if incurred_on.is_empty() {
+        None
+    } else {
+        match NaiveDate::parse_from_str(&incurred_on, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                println!("Error: Invalid date, use YYYY-MM-DD");
+                return;
+            }
+        }
+    };
+
+    let merchant = get_input("Enter merchant (optional): ");
+    let notes = get_input("Enter notes (optional): ");
+
+    let details = NewExpense { name, category, amount, employee, incurred_on, merchant, notes };
+    match manager.add_expense_with(details) {
+        Ok(id) => {
+            println!("Expense added successfully with ID: {}", id);
+            let flags = manager.policy_flags(id);
+            if !flags.is_empty() {
+                println!("Flagged for review: {}", policy::describe(flags));
+            }
+        }
+        Err(e) => println!("{}", e),
     }
 }
 
-fn view_expenses_menu(manager: &ExpenseManager) {
+fn view_expenses_menu<S: ExpenseStore>(manager: &ExpenseManager<S>) {
     println!("Your Expenses");
 
     let expenses = manager.view_expenses();
@@ -66,20 +116,36 @@ fn view_expenses_menu(manager: &ExpenseManager) {
     }
 
     for expense in expenses {
-        let status_str = match expense.status {
-            ExpenseStatus::Pending => "Pending",
-            ExpenseStatus::Approved => "Approved",
-            ExpenseStatus::Rejected => "Rejected",
-        };
-
         println!(
-            "ID:{}, Name: {}, Category: {}, Amount: {}, Status: {}",
-            expense.id, expense.name, expense.category, expense.amount, status_str
+            "ID:{}, Date: {}, Name: {}, Merchant: {}, Category: {}, Amount: {}, Employee: {}, Status: {}",
+            expense.id,
+            expense.incurred_on,
+            expense.name,
+            expense.merchant,
+            expense.category,
+            expense.amount,
+            expense.employee,
+            expense.status
         );
+        if !expense.notes.is_empty() {
+            println!("    Notes: {}", expense.notes);
+        }
+        if !expense.flags.is_empty() {
+            let state = if expense.policy_override.is_some() { " (overridden)" } else { "" };
+            println!("    Policy flags: {}{}", policy::describe(&expense.flags), state);
+        }
+        if let Some(receipt) = &expense.receipt {
+            println!("    Receipt: {} ({})", receipt.original_name, receipt.path.display());
+        }
+    }
+
+    match manager.total_expenses() {
+        Ok(total) => println!("Total: {}", total),
+        Err(e) => println!("Total unavailable: {}", e),
     }
 }
 
-fn remove_expense_menu(manager: &mut ExpenseManager) {
+fn remove_expense_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
     println!("Remove Expense");
 
     if manager.view_expenses().is_empty() {
@@ -114,14 +180,11 @@ fn remove_expense_menu(manager: &mut ExpenseManager) {
 
     match manager.remove_expense(id) {
         Ok(()) => println!("Expense removed successfully"),
-        Err(RemoveError::NotFound) => println!("Expense with ID {} not found", id),
-        Err(RemoveError::CannotRemovePending) => {
-            println!("Cannot remove pending expenses. Please approve or reject the expense first.");
-        }
+        Err(e) => println!("{}", e),
     }
 }
 
-fn edit_expense_menu(manager: &mut ExpenseManager) {
+fn edit_expense_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
     println!("Edit Expense");
 
     if manager.view_expenses().is_empty() {
@@ -165,10 +228,6 @@ fn edit_expense_menu(manager: &mut ExpenseManager) {
     let new_amount = get_input(&format!("New amount [{}]: ", current_expense.amount));
     let new_amount = if new_amount.is_empty() { None } else { Some(new_amount) };
 
-    println!("Available statuses: pending, approved, rejected");
-    let new_status = get_input(&format!("New status [{:?}]: ", current_expense.status));
-    let new_status = if new_status.is_empty() { None } else { Some(new_status) };
-
     println!("=== Summary of Changes ===");
     if let Some(ref name) = new_name {
         println!("Name: {} -> {}", current_expense.name, name);
@@ -179,28 +238,329 @@ fn edit_expense_menu(manager: &mut ExpenseManager) {
     if let Some(ref amount) = new_amount {
         println!("Amount: {} -> {}", current_expense.amount, amount);
     }
-    if let Some(ref status) = new_status {
-        println!("Status: {:?} -> {}", current_expense.status, status);
-    }
 
-    if new_name.is_none() && new_category.is_none() && new_amount.is_none() && new_status.is_none() {
+    if new_name.is_none() && new_category.is_none() && new_amount.is_none() {
         println!("No changes to make.");
         return;
     }
 
+    let editor = get_input("Enter your name: ");
+    if editor.is_empty() {
+        println!("Your name is needed to record the change");
+        return;
+    }
+
     let confirm = get_confirmation("Save these changes?");
 
-    match manager.edit_expense(id, new_name, new_amount, new_category, new_status, confirm) {
+    match manager.edit_expense(id, &editor, new_name, new_amount, new_category, confirm) {
         Ok(()) => println!("Expense updated successfully"),
-        Err(EditError::NotFound) => println!("Expense not found"),
-        Err(EditError::InvalidAmount) => println!("Invalid amount format"),
-        Err(EditError::InvalidStatus) => println!("Invalid status! Use: pending, approved, or rejected"),
-        Err(EditError::Cancelled) => println!("Edit cancelled."),
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn review_expense_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
+    println!("Review Expense");
+
+    if manager.view_expenses().is_empty() {
+        println!("No expenses to review");
+        return;
+    }
+
+    view_expenses_menu(manager);
+
+    let id_input = get_input("Enter the ID of the expense to review: ");
+    let id: u32 = match id_input.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Invalid ID");
+            return;
+        }
+    };
+
+    let approver = get_input("Enter your approver name: ");
+
+    println!("Available actions: approve, reject, reimburse");
+    let action = get_input("Action: ");
+    let comment = get_input("Comment: ");
+
+    let result = match action.to_lowercase().as_str() {
+        "approve" => manager.approve(id, &approver, comment),
+        "reject" => manager.reject(id, &approver, comment),
+        "reimburse" => manager.reimburse(id, &approver, comment),
+        _ => {
+            println!("Invalid action! Use: approve, reject, or reimburse");
+            return;
+        }
+    };
+
+    match result {
+        Ok(status) => println!("Expense {} is now: {}", id, status),
+        Err(WorkflowError::PolicyViolation(flags)) => {
+            println!("Expense {} breaks policy: {}", id, policy::describe(&flags));
+            if get_confirmation("Record an override reason?") {
+                let reason = get_input("Reason: ");
+                match manager.override_policy(id, &approver, reason) {
+                    Ok(()) => println!("Override recorded. Approve the expense again to continue."),
+                    Err(e) => println!("Failed to record override: {}", e),
+                }
+            }
+        }
+        Err(
+            e @ (WorkflowError::UnknownApprover | WorkflowError::WrongRole { .. } | WorkflowError::AlreadyApproved),
+        ) => println!("'{}': {}", approver, e),
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn add_approver_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
+    println!("Register Approver");
+
+    let name = get_input("Enter approver name: ");
+    if name.is_empty() {
+        println!("Error: Approver name cannot be empty");
+        return;
+    }
+
+    println!("Available roles: manager, finance");
+    let role = match get_input("Enter approver role: ").to_lowercase().as_str() {
+        "manager" => ApproverRole::Manager,
+        "finance" => ApproverRole::Finance,
+        _ => {
+            println!("Invalid role! Use: manager or finance");
+            return;
+        }
+    };
+
+    match manager.add_approver(name.clone(), role.clone()) {
+        Ok(()) => println!("Registered {} as {:?} approver", name, role),
+        Err(e) => println!("Failed to save approver: {}", e),
+    }
+}
+
+fn audit_trail_menu<S: ExpenseStore>(manager: &ExpenseManager<S>) {
+    println!("Audit Trail");
+
+    let id_input = get_input("Enter the ID of the expense: ");
+    let id: u32 = match id_input.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Invalid ID");
+            return;
+        }
+    };
+
+    let entries = manager.audit_trail(id);
+    if entries.is_empty() {
+        println!("No audit entries for expense {}", id);
+        return;
+    }
+
+    for entry in entries {
+        println!(
+            "{} | {} | {} -> {} | {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.actor,
+            entry.from,
+            entry.to,
+            entry.comment
+        );
+    }
+}
+
+fn attach_receipt_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
+    println!("Attach Receipt");
+
+    let id_input = get_input("Enter the ID of the expense: ");
+    let id: u32 = match id_input.parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Invalid ID");
+            return;
+        }
+    };
+
+    let path = get_input("Enter the path to the receipt file: ");
+    if path.is_empty() {
+        println!("Error: Path cannot be empty");
+        return;
+    }
+
+    match manager.attach_receipt(id, Path::new(&path)) {
+        Ok(receipt) => println!("Receipt stored at {}", receipt.path.display()),
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn import_csv_menu<S: ExpenseStore>(manager: &mut ExpenseManager<S>) {
+    println!("Import Expenses from CSV");
+
+    let path = get_input("Enter the path to the bank export: ");
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            println!("Failed to open {}: {}", path, e);
+            return;
+        }
+    };
+
+    let employee = get_input("Enter employee name: ");
+    if employee.is_empty() {
+        println!("Error: Employee name cannot be empty");
+        return;
+    }
+
+    match manager.import_csv(file, &employee) {
+        Ok(report) => println!("{}", report.summary()),
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn summary_table<S: ExpenseStore>(
+    manager: &ExpenseManager<S>,
+    group_by: GroupBy,
+) -> Result<(Vec<String>, Vec<Vec<String>>), MoneyError> {
+    manager.summary(group_by).map(|rows| reports::summary_rows(group_by, &rows))
+}
+
+fn reports_menu<S: ExpenseStore>(manager: &ExpenseManager<S>) {
+    println!("Reports");
+    println!("1. Totals by category");
+    println!("2. Totals by employee");
+    println!("3. Totals by status");
+    println!("4. Totals by month");
+    println!("5. Top spenders");
+    println!("6. Budget vs actual");
+
+    let report = get_input("Choose a report (1-6): ");
+    let result = match report.as_str() {
+        "1" => summary_table(manager, GroupBy::Category),
+        "2" => summary_table(manager, GroupBy::Employee),
+        "3" => summary_table(manager, GroupBy::Status),
+        "4" => summary_table(manager, GroupBy::Month),
+        "5" => {
+            let n = get_input("How many spenders to show? [5]: ").parse().unwrap_or(5);
+            manager.top_spenders(n).map(|rows| reports::summary_rows(GroupBy::Employee, &rows))
+        }
+        "6" => manager.budget_vs_actual().map(|lines| reports::budget_rows(&lines)),
+        _ => {
+            println!("Invalid report choice");
+            return;
+        }
+    };
+
+    let (headers, rows) = match result {
+        Ok(table) => table,
+        Err(e) => {
+            println!("Cannot build report: {}", e);
+            return;
+        }
+    };
+
+    if rows.is_empty() {
+        println!("Nothing to report yet");
+        return;
+    }
+
+    let format = get_input("Output format (table/csv) [table]: ");
+    match format.to_lowercase().as_str() {
+        "" | "table" => println!("{}", reports::render_table(&headers, &rows)),
+        "csv" => println!("{}", reports::render_csv(&headers, &rows)),
+        _ => println!("Invalid format! Use: table or csv"),
+    }
+}
+
+fn load_rates(path: &str) -> Result<Option<ExchangeRates>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let table = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    ExchangeRates::parse_table(&table).map(Some).map_err(|e| e.to_string())
+}
+
+fn load_budgets(path: &str, rates: &ExchangeRates) -> Result<Option<HashMap<String, Money>>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let table = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    reports::parse_budgets(&table, rates.base()).map(Some).map_err(|e| e.to_string())
+}
+
+fn load_policy(path: &str, rates: &ExchangeRates) -> Result<Option<Policy>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Policy::from_json(&json, rates.base()).map(Some).map_err(|e| e.to_string())
+}
+
+/// Opens the data file and loads the optional rates, budgets and policy files next to it.
+fn open_manager() -> Result<ExpenseManager<JsonFileStore>, String> {
+    let data_file = env::var("EXPENSE_DATA_FILE").unwrap_or_else(|_| DEFAULT_DATA_FILE.to_string());
+    let store = JsonFileStore::open(&data_file).map_err(|e| format!("Failed to open {}: {}", data_file, e))?;
+    let mut manager = ExpenseManager::with_store(store);
+
+    let rates_file = env::var("EXPENSE_RATES_FILE").unwrap_or_else(|_| DEFAULT_RATES_FILE.to_string());
+    if let Some(rates) =
+        load_rates(&rates_file).map_err(|e| format!("Failed to load exchange rates from {}: {}", rates_file, e))?
+    {
+        manager.rates = rates;
+    }
+
+    let budgets_file = env::var("EXPENSE_BUDGETS_FILE").unwrap_or_else(|_| DEFAULT_BUDGETS_FILE.to_string());
+    if let Some(budgets) = load_budgets(&budgets_file, &manager.rates)
+        .map_err(|e| format!("Failed to load budgets from {}: {}", budgets_file, e))?
+    {
+        manager.budgets = budgets;
+    }
+
+    let policy_file = env::var("EXPENSE_POLICY_FILE").unwrap_or_else(|_| DEFAULT_POLICY_FILE.to_string());
+    if let Some(policy) = load_policy(&policy_file, &manager.rates)
+        .map_err(|e| format!("Failed to load policy from {}: {}", policy_file, e))?
+    {
+        manager.policy = policy;
+    }
+
+    Ok(manager)
+}
+
+fn run_command(args: &[String]) -> i32 {
+    let command = match cli::parse_args(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e.message);
+            eprintln!("{}", cli::USAGE);
+            return e.code;
+        }
+    };
+
+    let mut manager = match open_manager() {
+        Ok(manager) => manager,
+        Err(message) => {
+            eprintln!("{}", message);
+            return cli::EXIT_STORAGE;
+        }
+    };
+
+    match cli::run(&mut manager, command) {
+        Ok(output) => {
+            println!("{}", output);
+            cli::EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("{}", e.message);
+            e.code
+        }
     }
 }
 
 fn run_program() {
-    let mut manager = ExpenseManager::new();
+    let mut manager = match open_manager() {
+        Ok(manager) => manager,
+        Err(message) => {
+            println!("{}", message);
+            return;
+        }
+    };
 
     println!("Expense Manager");
 
@@ -212,22 +572,34 @@ fn run_program() {
         println!("2. View all expenses");
         println!("3. Remove an expense");
         println!("4. Edit an expense");
-        println!("5. Exit");
+        println!("5. Review an expense");
+        println!("6. Register an approver");
+        println!("7. View audit trail");
+        println!("8. Reports");
+        println!("9. Attach a receipt");
+        println!("10. Import expenses from CSV");
+        println!("11. Exit");
         println!("==========================================================");
 
-        let choice = get_input("Enter your choice (1-5): ");
+        let choice = get_input("Enter your choice (1-11): ");
 
         match choice.as_str() {
             "1" => add_expense_menu(&mut manager),
             "2" => view_expenses_menu(&manager),
             "3" => remove_expense_menu(&mut manager),
             "4" => edit_expense_menu(&mut manager),
-            "5" => {
+            "5" => review_expense_menu(&mut manager),
+            "6" => add_approver_menu(&mut manager),
+            "7" => audit_trail_menu(&manager),
+            "8" => reports_menu(&manager),
+            "9" => attach_receipt_menu(&mut manager),
+            "10" => import_csv_menu(&mut manager),
+            "11" => {
                 println!("Exiting program");
                 break;
             }
             _ => {
-                println!("Invalid choice! Please enter a number between 1 and 5.");
+                println!("Invalid choice! Please enter a number between 1 and 11.");
             }
         }
     }
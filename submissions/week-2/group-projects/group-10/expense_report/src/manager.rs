@@ -1,44 +1,190 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-use crate::errors::{EditError, RemoveError};
-use crate::types::{Expense, ExpenseStatus};
+use chrono::{Local, Utc};
 
-pub struct ExpenseManager {
-    pub expenses: HashMap<u32, Expense>,
-    pub next_id: u32,
+use crate::attachments;
+use crate::errors::{
+    AddError, AttachError, EditError, ImportError, MoneyError, RemoveError, RowError, StorageError, WorkflowError,
+};
+use crate::import::{self, DuplicateKey, ImportReport, RowIssue};
+use crate::money::{Currency, ExchangeRates, Money};
+use crate::policy::{Policy, PolicyFlag};
+use crate::reports::{self, BudgetLine, GroupBy, GroupSummary};
+use crate::storage::{ExpenseStore, MemoryStore};
+use crate::types::{
+    ApproverRole, AuditEntry, Expense, ExpenseStatus, NewExpense, PolicyOverride, Receipt, WorkflowAction,
+};
+
+pub const DEFAULT_ATTACHMENTS_DIR: &str = "attachments";
+
+/// Expenses at or above this amount (1000.00 USD) need two distinct approvers at each approval stage.
+pub const DEFAULT_SECOND_APPROVAL_THRESHOLD_CENTS: i64 = 100_000;
+
+pub struct ExpenseManager<S: ExpenseStore = MemoryStore> {
+    store: S,
+    /// Compared against each expense after both are converted into the base currency.
+    pub second_approval_threshold: Money,
+    /// Base currency and conversion table. Amounts entered without a currency code use the base.
+    pub rates: ExchangeRates,
+    /// Spending budget per category, used by `budget_vs_actual`.
+    pub budgets: HashMap<String, Money>,
+    /// Where receipt files are copied to.
+    pub attachments_dir: PathBuf,
+    /// Rules checked on every add and edit. Flagged expenses cannot be approved until overridden.
+    pub policy: Policy,
+}
+
+impl Default for ExpenseManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ExpenseManager {
     pub fn new() -> Self {
+        Self::with_store(MemoryStore::new())
+    }
+}
+
+impl<S: ExpenseStore> ExpenseManager<S> {
+    pub fn with_store(store: S) -> Self {
         Self {
-            expenses: HashMap::new(),
-            next_id: 1,
+            store,
+            second_approval_threshold: Money::new(DEFAULT_SECOND_APPROVAL_THRESHOLD_CENTS, Currency::USD)
+                .expect("default threshold is non-negative"),
+            rates: ExchangeRates::default(),
+            budgets: HashMap::new(),
+            attachments_dir: PathBuf::from(DEFAULT_ATTACHMENTS_DIR),
+            policy: Policy::default(),
         }
     }
 
-    pub fn add_expense(&mut self, name: String, category: String, amount_str: String) -> Result<u32, String> {
-        let amount: f64 = amount_str.parse().map_err(|_| "Invalid amount".to_string())?;
-        let id = self.next_id;
-        let expense = Expense {
-            id,
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn next_id(&self) -> u32 {
+        self.store.next_id()
+    }
+
+    pub fn add_expense(
+        &mut self,
+        name: String,
+        category: String,
+        amount_str: String,
+        employee: String,
+    ) -> Result<u32, AddError> {
+        self.add_expense_with(NewExpense {
             name,
-            amount,
             category,
-            status: ExpenseStatus::Pending,
+            amount: amount_str,
+            employee,
+            ..NewExpense::default()
+        })
+    }
+
+    pub fn add_expense_with(&mut self, details: NewExpense) -> Result<u32, AddError> {
+        let amount = Money::parse(&details.amount, self.rates.base()).map_err(AddError::InvalidAmount)?;
+        self.insert_expense(details, amount).map_err(AddError::Storage)
+    }
+
+    fn insert_expense(&mut self, details: NewExpense, amount: Money) -> Result<u32, StorageError> {
+        let mut expense = Expense {
+            // The store assigns the real id when it saves the expense
+            id: self.store.next_id(),
+            name: details.name,
+            amount,
+            category: details.category,
+            status: ExpenseStatus::Submitted,
+            employee: details.employee,
+            submitted_at: Utc::now(),
+            incurred_on: details.incurred_on.unwrap_or_else(|| Local::now().date_naive()),
+            merchant: details.merchant,
+            notes: details.notes,
+            receipt: None,
+            flags: Vec::new(),
+            policy_override: None,
+            approvals: Vec::new(),
         };
-        self.expenses.insert(id, expense);
-        self.next_id += 1;
-        Ok(id)
+        expense.flags = self.check_policy(&expense);
+        self.store.save_new(expense)
+    }
+
+    /// Copies a receipt into the attachments directory and links it to the expense,
+    /// replacing any earlier receipt reference.
+    pub fn attach_receipt(&mut self, id: u32, source: &Path) -> Result<Receipt, AttachError> {
+        let mut expense = self.store.get(id).cloned().ok_or(AttachError::NotFound)?;
+        let receipt = attachments::store_receipt(&self.attachments_dir, source)?;
+        expense.receipt = Some(receipt.clone());
+        self.store.save(expense).map_err(AttachError::Storage)?;
+        Ok(receipt)
+    }
+
+    /// Imports a bank export for `employee`, skipping invalid rows and charges that are already recorded.
+    pub fn import_csv<R: Read>(&mut self, reader: R, employee: &str) -> Result<ImportReport, ImportError> {
+        let rows = import::parse_bank_csv(reader, self.rates.base(), employee)?;
+        let mut seen: HashSet<DuplicateKey> = self.store.all().into_iter().map(DuplicateKey::of).collect();
+        let mut report = ImportReport::default();
+
+        for row in rows {
+            let row = match row {
+                Ok(row) => row,
+                Err(RowIssue { line, error: RowError::Credit(_) }) => {
+                    report.credits.push(line);
+                    continue;
+                }
+                Err(issue) => {
+                    report.errors.push(issue);
+                    continue;
+                }
+            };
+
+            if !seen.insert(row.duplicate_key()) {
+                report.duplicates.push(row.line);
+                continue;
+            }
+
+            let id = self.insert_expense(row.details, row.amount).map_err(ImportError::Storage)?;
+            report.imported.push(id);
+        }
+
+        Ok(report)
+    }
+
+    pub fn get_expense(&self, id: u32) -> Option<&Expense> {
+        self.store.get(id)
     }
 
+    /// All expenses, ordered by id.
     pub fn view_expenses(&self) -> Vec<&Expense> {
-        self.expenses.values().collect()
+        let mut expenses = self.store.all();
+        expenses.sort_by_key(|e| e.id);
+        expenses
+    }
+
+    /// Sum of every expense, converted into the base currency.
+    pub fn total_expenses(&self) -> Result<Money, MoneyError> {
+        self.rates.total(self.store.all().iter().map(|e| &e.amount))
+    }
+
+    pub fn summary(&self, group_by: GroupBy) -> Result<Vec<GroupSummary>, MoneyError> {
+        reports::summarize(self.store.all(), group_by, &self.rates)
+    }
+
+    pub fn top_spenders(&self, n: usize) -> Result<Vec<GroupSummary>, MoneyError> {
+        reports::top_spenders(self.store.all(), n, &self.rates)
+    }
+
+    pub fn budget_vs_actual(&self) -> Result<Vec<BudgetLine>, MoneyError> {
+        reports::budget_vs_actual(self.store.all(), &self.budgets, &self.rates)
     }
 
     pub fn remove_expense(&mut self, id: u32) -> Result<(), RemoveError> {
-        match self.expenses.get(&id) {
-            Some(e) if matches!(e.status, ExpenseStatus::Approved | ExpenseStatus::Rejected) => {
-                self.expenses.remove(&id);
+        match self.store.get(id) {
+            Some(e) if e.status.is_final() => {
+                self.store.remove(id).map_err(RemoveError::Storage)?;
                 Ok(())
             }
             Some(_) => Err(RemoveError::CannotRemovePending),
@@ -46,45 +192,222 @@ impl ExpenseManager {
         }
     }
 
+    /// `editor` is who made the change; it is recorded in the audit trail when the edit clears approvals.
     pub fn edit_expense(
         &mut self,
         id: u32,
+        editor: &str,
         name: Option<String>,
         amount: Option<String>,
         category: Option<String>,
-        status: Option<String>,
         confirm: bool,
     ) -> Result<(), EditError> {
-        let expense = self.expenses.get(&id).cloned().ok_or(EditError::NotFound)?;
+        let expense = self.store.get(id).cloned().ok_or(EditError::NotFound)?;
+
+        if expense.status != ExpenseStatus::Submitted {
+            return Err(EditError::NotEditable);
+        }
 
         if !confirm {
             return Err(EditError::Cancelled);
         }
 
         let amount_parsed = match amount {
-            Some(ref a) => Some(a.parse::<f64>().map_err(|_| EditError::InvalidAmount)?),
-            None => None,
-        };
-
-        let status_enum = match status {
-            Some(s) => match s.to_lowercase().as_str() {
-                "pending" => Some(ExpenseStatus::Pending),
-                "approved" => Some(ExpenseStatus::Approved),
-                "rejected" => Some(ExpenseStatus::Rejected),
-                _ => return Err(EditError::InvalidStatus),
-            },
+            Some(ref a) => Some(Money::parse(a, self.rates.base()).map_err(EditError::InvalidAmount)?),
             None => None,
         };
 
-        let updated = Expense {
+        let mut updated = Expense {
             id,
             name: name.unwrap_or(expense.name),
             amount: amount_parsed.unwrap_or(expense.amount),
             category: category.unwrap_or(expense.category),
-            status: status_enum.unwrap_or(expense.status),
+            status: expense.status,
+            employee: expense.employee,
+            submitted_at: expense.submitted_at,
+            incurred_on: expense.incurred_on,
+            merchant: expense.merchant,
+            notes: expense.notes,
+            receipt: expense.receipt,
+            flags: expense.flags,
+            policy_override: expense.policy_override,
+            approvals: expense.approvals,
         };
 
-        self.expenses.insert(id, updated);
-        Ok(())
+        let flags = self.check_policy(&updated);
+        if flags != updated.flags {
+            // An override only covers the flags it was given for
+            updated.policy_override = None;
+            updated.flags = flags;
+        }
+
+        // A sign-off only covers the amount that was approved
+        let cleared = if updated.amount != expense.amount && !updated.approvals.is_empty() {
+            std::mem::take(&mut updated.approvals)
+        } else {
+            Vec::new()
+        };
+        let status = updated.status.clone();
+        let new_amount = updated.amount;
+
+        if cleared.is_empty() {
+            return self.store.save(updated).map_err(EditError::Storage);
+        }
+        self.store
+            .save_with_audit(
+                updated,
+                AuditEntry {
+                    expense_id: id,
+                    actor: editor.to_string(),
+                    timestamp: Utc::now(),
+                    from: status.clone(),
+                    to: status,
+                    comment: format!(
+                        "Amount changed from {} to {}; approvals from {} cleared",
+                        expense.amount,
+                        new_amount,
+                        cleared.join(", ")
+                    ),
+                },
+            )
+            .map_err(EditError::Storage)
+    }
+
+    pub fn add_approver(&mut self, name: String, role: ApproverRole) -> Result<(), StorageError> {
+        self.store.save_approver(name, role)
+    }
+
+    pub fn approver_role(&self, name: &str) -> Option<&ApproverRole> {
+        self.store.approvers().get(name)
+    }
+
+    pub fn view_approvers(&self) -> Vec<(&String, &ApproverRole)> {
+        self.store.approvers().iter().collect()
+    }
+
+    pub fn approve(&mut self, id: u32, approver: &str, comment: String) -> Result<ExpenseStatus, WorkflowError> {
+        self.transition(id, approver, WorkflowAction::Approve, comment)
+    }
+
+    pub fn reject(&mut self, id: u32, approver: &str, comment: String) -> Result<ExpenseStatus, WorkflowError> {
+        self.transition(id, approver, WorkflowAction::Reject, comment)
+    }
+
+    pub fn reimburse(&mut self, id: u32, approver: &str, comment: String) -> Result<ExpenseStatus, WorkflowError> {
+        self.transition(id, approver, WorkflowAction::Reimburse, comment)
+    }
+
+    /// Policy flags on an expense, or an empty list when it does not exist.
+    pub fn policy_flags(&self, id: u32) -> &[PolicyFlag] {
+        self.store.get(id).map(|e| e.flags.as_slice()).unwrap_or_default()
+    }
+
+    /// Records why a flagged, still-pending expense may be approved despite breaking policy.
+    /// Any registered approver may do this; the override is written to the audit trail.
+    pub fn override_policy(&mut self, id: u32, approver: &str, reason: String) -> Result<(), WorkflowError> {
+        let mut expense = self.store.get(id).cloned().ok_or(WorkflowError::NotFound)?;
+        if self.approver_role(approver).is_none() {
+            return Err(WorkflowError::UnknownApprover);
+        }
+        if expense.flags.is_empty() || expense.status.is_final() {
+            return Err(WorkflowError::NothingToOverride);
+        }
+        let reason = reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(WorkflowError::MissingReason);
+        }
+
+        let timestamp = Utc::now();
+        expense.policy_override = Some(PolicyOverride { actor: approver.to_string(), reason: reason.clone(), timestamp });
+        let status = expense.status.clone();
+        self.store
+            .save_with_audit(
+                expense,
+                AuditEntry {
+                    expense_id: id,
+                    actor: approver.to_string(),
+                    timestamp,
+                    from: status.clone(),
+                    to: status,
+                    comment: format!("Policy override: {}", reason),
+                },
+            )
+            .map_err(WorkflowError::Storage)
+    }
+
+    /// Audit entries for one expense, oldest first.
+    pub fn audit_trail(&self, id: u32) -> Vec<&AuditEntry> {
+        self.store.audit_log().iter().filter(|entry| entry.expense_id == id).collect()
+    }
+
+    fn transition(
+        &mut self,
+        id: u32,
+        approver: &str,
+        action: WorkflowAction,
+        comment: String,
+    ) -> Result<ExpenseStatus, WorkflowError> {
+        let mut expense = self.store.get(id).cloned().ok_or(WorkflowError::NotFound)?;
+        let role = self.approver_role(approver).cloned().ok_or(WorkflowError::UnknownApprover)?;
+        let from = expense.status.clone();
+
+        let (mut to, required) = match (&from, action) {
+            (ExpenseStatus::Submitted, WorkflowAction::Approve) => (ExpenseStatus::ManagerApproved, ApproverRole::Manager),
+            (ExpenseStatus::Submitted, WorkflowAction::Reject) => (ExpenseStatus::Rejected, ApproverRole::Manager),
+            (ExpenseStatus::ManagerApproved, WorkflowAction::Approve) => (ExpenseStatus::FinanceApproved, ApproverRole::Finance),
+            (ExpenseStatus::ManagerApproved, WorkflowAction::Reject) => (ExpenseStatus::Rejected, ApproverRole::Finance),
+            (ExpenseStatus::FinanceApproved, WorkflowAction::Reimburse) => (ExpenseStatus::Reimbursed, ApproverRole::Finance),
+            _ => return Err(WorkflowError::IllegalTransition { from, action }),
+        };
+
+        if role != required {
+            return Err(WorkflowError::WrongRole { required, actual: role });
+        }
+
+        if action == WorkflowAction::Approve && !expense.flags.is_empty() && expense.policy_override.is_none() {
+            return Err(WorkflowError::PolicyViolation(expense.flags));
+        }
+
+        if action == WorkflowAction::Approve && self.needs_second_approval(&expense.amount)? {
+            if expense.approvals.iter().any(|name| name == approver) {
+                return Err(WorkflowError::AlreadyApproved);
+            }
+            expense.approvals.push(approver.to_string());
+
+            // First of two sign-offs: recorded, but the expense stays where it is
+            if expense.approvals.len() < 2 {
+                to = from.clone();
+            }
+        }
+
+        if to != from {
+            expense.approvals.clear();
+            expense.status = to.clone();
+        }
+
+        self.store
+            .save_with_audit(
+                expense,
+                AuditEntry {
+                    expense_id: id,
+                    actor: approver.to_string(),
+                    timestamp: Utc::now(),
+                    from,
+                    to: to.clone(),
+                    comment,
+                },
+            )
+            .map_err(WorkflowError::Storage)?;
+        Ok(to)
+    }
+
+    fn check_policy(&self, expense: &Expense) -> Vec<PolicyFlag> {
+        self.policy.evaluate(expense, self.store.all(), &self.rates)
+    }
+
+    fn needs_second_approval(&self, amount: &Money) -> Result<bool, WorkflowError> {
+        let amount = self.rates.to_base(amount).map_err(WorkflowError::Currency)?;
+        let threshold = self.rates.to_base(&self.second_approval_threshold).map_err(WorkflowError::Currency)?;
+        Ok(amount.minor_units() >= threshold.minor_units())
     }
 }
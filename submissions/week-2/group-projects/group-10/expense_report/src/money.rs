@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::MoneyError;
+
+/// Exchange rates are kept as millionths of a base unit per foreign unit.
+const RATE_SCALE: i128 = 1_000_000;
+const RATE_DIGITS: u32 = 6;
+
+/// ISO 4217 currency code, always stored upper-case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+
+    pub fn new(code: &str) -> Result<Self, MoneyError> {
+        let code = code.trim();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(MoneyError::InvalidCurrency(code.to_string()));
+        }
+        let mut bytes = [0u8; 3];
+        bytes.copy_from_slice(code.to_ascii_uppercase().as_bytes());
+        Ok(Currency(bytes))
+    }
+
+    pub fn code(&self) -> &str {
+        // Only ever built from validated ASCII
+        std::str::from_utf8(&self.0).unwrap_or("???")
+    }
+
+    /// Number of digits after the decimal point in this currency's minor unit.
+    pub fn minor_digits(&self) -> u32 {
+        match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX" | "UYI"
+            | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = MoneyError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().to_string()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A non-negative amount held as an exact count of the currency's minor units (e.g. cents).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawMoney")]
+pub struct Money {
+    minor_units: i64,
+    currency: Currency,
+}
+
+/// `Money` as stored on disk, checked by `Money::new` before it is used.
+#[derive(Deserialize)]
+struct RawMoney {
+    minor_units: i64,
+    currency: Currency,
+}
+
+impl TryFrom<RawMoney> for Money {
+    type Error = MoneyError;
+
+    fn try_from(raw: RawMoney) -> Result<Self, Self::Error> {
+        Money::new(raw.minor_units, raw.currency)
+    }
+}
+
+impl Money {
+    pub fn new(minor_units: i64, currency: Currency) -> Result<Self, MoneyError> {
+        if minor_units < 0 {
+            return Err(MoneyError::Negative);
+        }
+        Ok(Self { minor_units, currency })
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self { minor_units: 0, currency }
+    }
+
+    /// Parses strings such as `"12.50 USD"` or `"12.50"`; the latter uses `default_currency`.
+    pub fn parse(input: &str, default_currency: Currency) -> Result<Self, MoneyError> {
+        let mut parts = input.split_whitespace();
+        let number = parts.next().ok_or(MoneyError::InvalidFormat)?;
+        let currency = match parts.next() {
+            Some(code) => Currency::new(code)?,
+            None => default_currency,
+        };
+        if parts.next().is_some() {
+            return Err(MoneyError::InvalidFormat);
+        }
+
+        let minor_units = parse_decimal(number, currency.minor_digits())?;
+        Money::new(minor_units, currency)
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor_units == 0
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        let minor_units = self.minor_units.checked_add(other.minor_units).ok_or(MoneyError::Overflow)?;
+        Money::new(minor_units, self.currency)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.currency.minor_digits();
+        if digits == 0 {
+            return write!(f, "{} {}", self.minor_units, self.currency);
+        }
+        let scale = 10i64.pow(digits);
+        write!(
+            f,
+            "{}.{:0width$} {}",
+            self.minor_units / scale,
+            self.minor_units % scale,
+            self.currency,
+            width = digits as usize
+        )
+    }
+}
+
+/// Parses a plain non-negative decimal into an integer scaled by `10^digits`, without going through floats.
+fn parse_decimal(number: &str, digits: u32) -> Result<i64, MoneyError> {
+    let lowered = number.to_ascii_lowercase();
+    let unsigned = lowered.trim_start_matches(['+', '-']);
+    if matches!(unsigned, "nan" | "inf" | "infinity") {
+        return Err(MoneyError::NonFinite);
+    }
+    if number.starts_with('-') {
+        return Err(MoneyError::Negative);
+    }
+
+    let (whole, fraction) = match number.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (number, ""),
+    };
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction) {
+        return Err(MoneyError::InvalidFormat);
+    }
+    if fraction.len() > digits as usize {
+        return Err(MoneyError::TooManyDecimals);
+    }
+
+    let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| MoneyError::Overflow)? };
+    let padded = format!("{:0<width$}", fraction, width = digits as usize);
+    let fraction: i64 = if padded.is_empty() { 0 } else { padded.parse().map_err(|_| MoneyError::Overflow)? };
+
+    whole
+        .checked_mul(10i64.pow(digits))
+        .and_then(|scaled| scaled.checked_add(fraction))
+        .ok_or(MoneyError::Overflow)
+}
+
+/// Locally configured conversion table into a single base currency, used for totals and reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    base: Currency,
+    /// Base-currency value of one unit of each foreign currency, in millionths.
+    rates: HashMap<Currency, i64>,
+}
+
+impl Default for ExchangeRates {
+    fn default() -> Self {
+        Self::new(Currency::USD)
+    }
+}
+
+impl ExchangeRates {
+    pub fn new(base: Currency) -> Self {
+        Self { base, rates: HashMap::new() }
+    }
+
+    pub fn base(&self) -> Currency {
+        self.base
+    }
+
+    /// Sets how much one unit of `currency` is worth in the base currency, e.g. `"1.08"`.
+    pub fn set_rate(&mut self, currency: Currency, rate: &str) -> Result<(), MoneyError> {
+        let micros = parse_decimal(rate.trim(), RATE_DIGITS)?;
+        if micros == 0 {
+            return Err(MoneyError::InvalidFormat);
+        }
+        self.rates.insert(currency, micros);
+        Ok(())
+    }
+
+    /// Parses a table with one `CODE RATE` pair per line, plus an optional `base CODE` line
+    /// (USD if absent). Blank lines and `#` comments are ignored.
+    pub fn parse_table(table: &str) -> Result<Self, MoneyError> {
+        let mut rates = ExchangeRates::default();
+        for line in table.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(char::is_whitespace).ok_or(MoneyError::InvalidFormat)?;
+            if key.eq_ignore_ascii_case("base") {
+                rates.base = Currency::new(value)?;
+            } else {
+                rates.set_rate(Currency::new(key)?, value)?;
+            }
+        }
+        Ok(rates)
+    }
+
+    /// Converts into the base currency, rounding half-up to the base's minor unit.
+    pub fn to_base(&self, money: &Money) -> Result<Money, MoneyError> {
+        if money.currency == self.base {
+            return Ok(*money);
+        }
+        let rate = *self.rates.get(&money.currency).ok_or(MoneyError::MissingRate(money.currency))?;
+
+        let numerator = money.minor_units as i128 * rate as i128 * 10i128.pow(self.base.minor_digits());
+        let denominator = 10i128.pow(money.currency.minor_digits()) * RATE_SCALE;
+        let converted = (numerator + denominator / 2) / denominator;
+
+        let minor_units = i64::try_from(converted).map_err(|_| MoneyError::Overflow)?;
+        Money::new(minor_units, self.base)
+    }
+
+    /// Sums amounts in any configured currency into the base currency.
+    pub fn total<'a>(&self, amounts: impl IntoIterator<Item = &'a Money>) -> Result<Money, MoneyError> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(self.base), |total, money| total.checked_add(&self.to_base(money)?))
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{Datelike, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::PolicyError;
+use crate::money::{Currency, ExchangeRates, Money};
+use crate::types::{Expense, ExpenseStatus};
+
+/// A reason an expense falls outside the spending policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PolicyFlag {
+    OverCategoryCap { cap: Money },
+    WeekendSpend,
+    /// Same employee, merchant and amount as another expense incurred within the duplicate window.
+    PossibleDuplicate { of: u32 },
+    RoundAmount,
+}
+
+impl fmt::Display for PolicyFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyFlag::OverCategoryCap { cap } => write!(f, "over the category cap of {}", cap),
+            PolicyFlag::WeekendSpend => f.write_str("incurred on a weekend"),
+            PolicyFlag::PossibleDuplicate { of } => write!(f, "looks like a duplicate of expense {}", of),
+            PolicyFlag::RoundAmount => f.write_str("suspiciously round amount"),
+        }
+    }
+}
+
+/// Spending rules checked whenever an expense is added or edited. The default policy has no rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    /// Largest single expense allowed per category.
+    pub category_caps: HashMap<String, Money>,
+    pub flag_weekends: bool,
+    /// Flag expenses matching another one incurred this many days before or after.
+    pub duplicate_window_days: Option<u32>,
+    /// Flag amounts that are an exact multiple of this many whole currency units, e.g. 50.
+    pub round_amount_multiple: Option<u32>,
+}
+
+/// Policy file layout. Caps are written like any other amount, e.g. `"75.00"` or `"500 EUR"`.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyFile {
+    category_caps: HashMap<String, String>,
+    flag_weekends: bool,
+    duplicate_window_days: Option<u32>,
+    round_amount_multiple: Option<u32>,
+}
+
+impl Policy {
+    /// Parses a JSON policy file; caps without a currency code use `default_currency`.
+    pub fn from_json(json: &str, default_currency: Currency) -> Result<Self, PolicyError> {
+        let file: PolicyFile = serde_json::from_str(json).map_err(|e| PolicyError::Parse(e.to_string()))?;
+
+        let mut category_caps = HashMap::new();
+        for (category, cap) in file.category_caps {
+            let cap = Money::parse(&cap, default_currency)
+                .map_err(|e| PolicyError::InvalidCap { category: category.clone(), error: e })?;
+            category_caps.insert(category, cap);
+        }
+
+        Ok(Self {
+            category_caps,
+            flag_weekends: file.flag_weekends,
+            duplicate_window_days: file.duplicate_window_days,
+            round_amount_multiple: file.round_amount_multiple.filter(|multiple| *multiple > 0),
+        })
+    }
+
+    /// Every rule `expense` breaks. `others` are the rest of the stored expenses, used for the duplicate check.
+    ///
+    /// A cap in a currency that cannot be converted with `rates` is skipped rather than guessed at.
+    pub fn evaluate<'a>(
+        &self,
+        expense: &Expense,
+        others: impl IntoIterator<Item = &'a Expense>,
+        rates: &ExchangeRates,
+    ) -> Vec<PolicyFlag> {
+        let mut flags = Vec::new();
+
+        if let Some(cap) = self.category_caps.get(&expense.category)
+            && exceeds(&expense.amount, cap, rates)
+        {
+            flags.push(PolicyFlag::OverCategoryCap { cap: *cap });
+        }
+
+        if self.flag_weekends && matches!(expense.incurred_on.weekday(), Weekday::Sat | Weekday::Sun) {
+            flags.push(PolicyFlag::WeekendSpend);
+        }
+
+        if let Some(window) = self.duplicate_window_days {
+            let target = payee(expense);
+            let duplicate = others
+                .into_iter()
+                .filter(|other| other.id != expense.id && other.status != ExpenseStatus::Rejected)
+                .filter(|other| other.employee == expense.employee && other.amount == expense.amount)
+                .filter(|other| payee(other) == target)
+                .filter(|other| (other.incurred_on - expense.incurred_on).num_days().unsigned_abs() <= u64::from(window))
+                .map(|other| other.id)
+                .min();
+            if let Some(of) = duplicate {
+                flags.push(PolicyFlag::PossibleDuplicate { of });
+            }
+        }
+
+        if let Some(multiple) = self.round_amount_multiple {
+            let unit = 10_i64.pow(expense.amount.currency().minor_digits()) * i64::from(multiple);
+            if !expense.amount.is_zero() && expense.amount.minor_units() % unit == 0 {
+                flags.push(PolicyFlag::RoundAmount);
+            }
+        }
+
+        flags
+    }
+}
+
+/// Flags as one human-readable line, e.g. for a table cell.
+pub fn describe(flags: &[PolicyFlag]) -> String {
+    flags.iter().map(|flag| flag.to_string()).collect::<Vec<_>>().join("; ")
+}
+
+/// Merchant if one was recorded, otherwise the expense name, compared case-insensitively.
+fn payee(expense: &Expense) -> String {
+    let payee = if expense.merchant.trim().is_empty() { &expense.name } else { &expense.merchant };
+    payee.trim().to_lowercase()
+}
+
+fn exceeds(amount: &Money, cap: &Money, rates: &ExchangeRates) -> bool {
+    if amount.currency() == cap.currency() {
+        return amount.minor_units() > cap.minor_units();
+    }
+    match (rates.to_base(amount), rates.to_base(cap)) {
+        (Ok(amount), Ok(cap)) => amount.minor_units() > cap.minor_units(),
+        _ => false,
+    }
+}
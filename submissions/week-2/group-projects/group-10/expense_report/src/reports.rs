@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::errors::MoneyError;
+use crate::money::{Currency, ExchangeRates, Money};
+use crate::types::{Expense, ExpenseStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    Category,
+    Employee,
+    Status,
+    /// Calendar month the money was spent, keyed as `YYYY-MM`. Submission can be much later,
+    /// e.g. when a bank export is imported.
+    Month,
+}
+
+impl GroupBy {
+    pub fn key(&self, expense: &Expense) -> String {
+        match self {
+            GroupBy::Category => expense.category.clone(),
+            GroupBy::Employee => expense.employee.clone(),
+            GroupBy::Status => expense.status.to_string(),
+            GroupBy::Month => expense.incurred_on.format("%Y-%m").to_string(),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::Category => "Category",
+            GroupBy::Employee => "Employee",
+            GroupBy::Status => "Status",
+            GroupBy::Month => "Month",
+        }
+    }
+}
+
+/// Count and base-currency total for one group.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupSummary {
+    pub key: String,
+    pub count: usize,
+    pub total: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetLine {
+    pub category: String,
+    pub budget: Option<Money>,
+    pub actual: Money,
+}
+
+impl BudgetLine {
+    /// Budget minus actual spend in minor units; negative when over budget.
+    pub fn remaining(&self) -> Option<i64> {
+        self.budget.map(|budget| budget.minor_units() - self.actual.minor_units())
+    }
+
+    pub fn is_over_budget(&self) -> bool {
+        self.remaining().is_some_and(|remaining| remaining < 0)
+    }
+}
+
+/// Totals and counts per group, sorted by key. Amounts are converted into the base currency.
+pub fn summarize<'a>(
+    expenses: impl IntoIterator<Item = &'a Expense>,
+    group_by: GroupBy,
+    rates: &ExchangeRates,
+) -> Result<Vec<GroupSummary>, MoneyError> {
+    let mut groups: BTreeMap<String, GroupSummary> = BTreeMap::new();
+
+    for expense in expenses {
+        let key = group_by.key(expense);
+        let amount = rates.to_base(&expense.amount)?;
+        let entry = groups.entry(key.clone()).or_insert_with(|| GroupSummary {
+            key,
+            count: 0,
+            total: Money::zero(rates.base()),
+        });
+        entry.count += 1;
+        entry.total = entry.total.checked_add(&amount)?;
+    }
+
+    Ok(groups.into_values().collect())
+}
+
+/// The `n` employees with the highest total spend. Rejected expenses are not counted.
+pub fn top_spenders<'a>(
+    expenses: impl IntoIterator<Item = &'a Expense>,
+    n: usize,
+    rates: &ExchangeRates,
+) -> Result<Vec<GroupSummary>, MoneyError> {
+    let spent = expenses.into_iter().filter(|e| e.status != ExpenseStatus::Rejected);
+    let mut rows = summarize(spent, GroupBy::Employee, rates)?;
+    // Stable sort keeps ties in name order
+    rows.sort_by_key(|row| std::cmp::Reverse(row.total.minor_units()));
+    rows.truncate(n);
+    Ok(rows)
+}
+
+/// Budget against actual spend for every category that has either. Rejected expenses are not counted.
+pub fn budget_vs_actual<'a>(
+    expenses: impl IntoIterator<Item = &'a Expense>,
+    budgets: &HashMap<String, Money>,
+    rates: &ExchangeRates,
+) -> Result<Vec<BudgetLine>, MoneyError> {
+    let spent = expenses.into_iter().filter(|e| e.status != ExpenseStatus::Rejected);
+    let mut actuals: BTreeMap<String, Money> = summarize(spent, GroupBy::Category, rates)?
+        .into_iter()
+        .map(|row| (row.key, row.total))
+        .collect();
+
+    for category in budgets.keys() {
+        actuals.entry(category.clone()).or_insert_with(|| Money::zero(rates.base()));
+    }
+
+    actuals
+        .into_iter()
+        .map(|(category, actual)| {
+            let budget = budgets.get(&category).map(|b| rates.to_base(b)).transpose()?;
+            Ok(BudgetLine { category, budget, actual })
+        })
+        .collect()
+}
+
+/// Parses one `Category = AMOUNT` pair per line; amounts without a currency use `default_currency`.
+/// Blank lines and `#` comments are ignored.
+pub fn parse_budgets(table: &str, default_currency: Currency) -> Result<HashMap<String, Money>, MoneyError> {
+    let mut budgets = HashMap::new();
+    for line in table.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (category, amount) = line.split_once('=').ok_or(MoneyError::InvalidFormat)?;
+        budgets.insert(category.trim().to_string(), Money::parse(amount, default_currency)?);
+    }
+    Ok(budgets)
+}
+
+pub fn summary_rows(group_by: GroupBy, rows: &[GroupSummary]) -> (Vec<String>, Vec<Vec<String>>) {
+    let headers = vec![group_by.label().to_string(), "Count".to_string(), "Total".to_string()];
+    let rows = rows
+        .iter()
+        .map(|row| vec![row.key.clone(), row.count.to_string(), row.total.to_string()])
+        .collect();
+    (headers, rows)
+}
+
+pub fn budget_rows(lines: &[BudgetLine]) -> (Vec<String>, Vec<Vec<String>>) {
+    let headers = ["Category", "Budget", "Actual", "Status"].iter().map(|h| h.to_string()).collect();
+    let rows = lines
+        .iter()
+        .map(|line| {
+            let status = match line.budget {
+                None => "No budget",
+                Some(_) if line.is_over_budget() => "Over budget",
+                Some(_) => "Within budget",
+            };
+            vec![
+                line.category.clone(),
+                line.budget.map(|b| b.to_string()).unwrap_or_default(),
+                line.actual.to_string(),
+                status.to_string(),
+            ]
+        })
+        .collect();
+    (headers, rows)
+}
+
+/// Renders rows as a plain-text table with left-aligned, padded columns.
+pub fn render_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format_row(headers);
+    out.push('\n');
+    out.push_str(&widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"));
+    for row in rows {
+        out.push('\n');
+        out.push_str(&format_row(row));
+    }
+    out
+}
+
+pub fn render_csv(headers: &[String], rows: &[Vec<String>]) -> String {
+    std::iter::once(headers)
+        .chain(rows.iter().map(|row| row.as_slice()))
+        .map(|cells| cells.iter().map(|cell| csv_field(cell)).collect::<Vec<_>>().join(","))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::StorageError;
+use crate::types::{ApproverRole, AuditEntry, Expense};
+
+/// Backing store for an `ExpenseManager`.
+///
+/// Ids handed out by `save_new` are never reused, even after the expense is removed.
+pub trait ExpenseStore {
+    fn get(&self, id: u32) -> Option<&Expense>;
+    fn all(&self) -> Vec<&Expense>;
+    fn save(&mut self, expense: Expense) -> Result<(), StorageError>;
+    fn remove(&mut self, id: u32) -> Result<Option<Expense>, StorageError>;
+    fn next_id(&self) -> u32;
+    /// Saves a new expense under the next unused id, replacing whatever id it had, and returns
+    /// that id. Taking the id and saving the expense are one write.
+    fn save_new(&mut self, expense: Expense) -> Result<u32, StorageError>;
+    fn approvers(&self) -> &HashMap<String, ApproverRole>;
+    fn save_approver(&mut self, name: String, role: ApproverRole) -> Result<(), StorageError>;
+    fn audit_log(&self) -> &[AuditEntry];
+    /// Saves the expense and appends its audit entry as one write: either both are kept or neither.
+    fn save_with_audit(&mut self, expense: Expense, entry: AuditEntry) -> Result<(), StorageError>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoreData {
+    expenses: HashMap<u32, Expense>,
+    next_id: u32,
+    approvers: HashMap<String, ApproverRole>,
+    audit_log: Vec<AuditEntry>,
+}
+
+impl Default for StoreData {
+    fn default() -> Self {
+        Self {
+            expenses: HashMap::new(),
+            next_id: 1,
+            approvers: HashMap::new(),
+            audit_log: Vec::new(),
+        }
+    }
+}
+
+impl StoreData {
+    fn insert_new(&mut self, mut expense: Expense) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        expense.id = id;
+        self.expenses.insert(id, expense);
+        id
+    }
+
+    /// Files written before expenses had a date incurred load it as the serde default; the day the
+    /// expense was submitted is the closest known date, so reports do not bucket them in 1970.
+    fn fill_missing_dates(&mut self) {
+        for expense in self.expenses.values_mut().filter(|e| e.incurred_on == NaiveDate::default()) {
+            expense.incurred_on = expense.submitted_at.date_naive();
+        }
+    }
+}
+
+/// Keeps everything in memory; data is lost when the program exits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    data: StoreData,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ExpenseStore for MemoryStore {
+    fn get(&self, id: u32) -> Option<&Expense> {
+        self.data.expenses.get(&id)
+    }
+
+    fn all(&self) -> Vec<&Expense> {
+        self.data.expenses.values().collect()
+    }
+
+    fn save(&mut self, expense: Expense) -> Result<(), StorageError> {
+        self.data.expenses.insert(expense.id, expense);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: u32) -> Result<Option<Expense>, StorageError> {
+        Ok(self.data.expenses.remove(&id))
+    }
+
+    fn next_id(&self) -> u32 {
+        self.data.next_id
+    }
+
+    fn save_new(&mut self, expense: Expense) -> Result<u32, StorageError> {
+        Ok(self.data.insert_new(expense))
+    }
+
+    fn approvers(&self) -> &HashMap<String, ApproverRole> {
+        &self.data.approvers
+    }
+
+    fn save_approver(&mut self, name: String, role: ApproverRole) -> Result<(), StorageError> {
+        self.data.approvers.insert(name, role);
+        Ok(())
+    }
+
+    fn audit_log(&self) -> &[AuditEntry] {
+        &self.data.audit_log
+    }
+
+    fn save_with_audit(&mut self, expense: Expense, entry: AuditEntry) -> Result<(), StorageError> {
+        self.data.expenses.insert(expense.id, expense);
+        self.data.audit_log.push(entry);
+        Ok(())
+    }
+}
+
+/// Keeps a JSON snapshot on disk that is rewritten after every change.
+///
+/// The snapshot is written to a temporary file first and then renamed over the old one,
+/// so a crash mid-write leaves the previous snapshot intact. Changes are applied to a copy
+/// and only kept in memory once that copy is on disk, so a failed write changes nothing.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    data: StoreData,
+}
+
+impl JsonFileStore {
+    /// Opens the store at `path`, starting empty if the file does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref().to_path_buf();
+
+        let data = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| StorageError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+            let mut data: StoreData = serde_json::from_str(&contents)
+                .map_err(|e| StorageError::Corrupt(format!("Failed to parse {}: {}", path.display(), e)))?;
+            data.fill_missing_dates();
+            data
+        } else {
+            StoreData::default()
+        };
+
+        Ok(Self { path, data })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Applies `change` to a copy of the data, writes the copy to disk and only then keeps it.
+    fn update<R>(&mut self, change: impl FnOnce(&mut StoreData) -> R) -> Result<R, StorageError> {
+        let mut data = self.data.clone();
+        let result = change(&mut data);
+        self.persist(&data)?;
+        self.data = data;
+        Ok(result)
+    }
+
+    fn persist(&self, data: &StoreData) -> Result<(), StorageError> {
+        let json = serde_json::to_string_pretty(data)
+            .map_err(|e| StorageError::Corrupt(format!("Failed to serialize data: {}", e)))?;
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)
+            .map_err(|e| StorageError::Io(format!("Failed to write {}: {}", tmp_path.display(), e)))?;
+        fs::rename(&tmp_path, &self.path)
+            .map_err(|e| StorageError::Io(format!("Failed to replace {}: {}", self.path.display(), e)))
+    }
+}
+
+impl ExpenseStore for JsonFileStore {
+    fn get(&self, id: u32) -> Option<&Expense> {
+        self.data.expenses.get(&id)
+    }
+
+    fn all(&self) -> Vec<&Expense> {
+        self.data.expenses.values().collect()
+    }
+
+    fn save(&mut self, expense: Expense) -> Result<(), StorageError> {
+        self.update(|data| {
+            data.expenses.insert(expense.id, expense);
+        })
+    }
+
+    fn remove(&mut self, id: u32) -> Result<Option<Expense>, StorageError> {
+        if !self.data.expenses.contains_key(&id) {
+            return Ok(None);
+        }
+        self.update(|data| data.expenses.remove(&id))
+    }
+
+    fn next_id(&self) -> u32 {
+        self.data.next_id
+    }
+
+    fn save_new(&mut self, expense: Expense) -> Result<u32, StorageError> {
+        self.update(|data| data.insert_new(expense))
+    }
+
+    fn approvers(&self) -> &HashMap<String, ApproverRole> {
+        &self.data.approvers
+    }
+
+    fn save_approver(&mut self, name: String, role: ApproverRole) -> Result<(), StorageError> {
+        self.update(|data| {
+            data.approvers.insert(name, role);
+        })
+    }
+
+    fn audit_log(&self) -> &[AuditEntry] {
+        &self.data.audit_log
+    }
+
+    fn save_with_audit(&mut self, expense: Expense, entry: AuditEntry) -> Result<(), StorageError> {
+        self.update(|data| {
+            data.expenses.insert(expense.id, expense);
+            data.audit_log.push(entry);
+        })
+    }
+}
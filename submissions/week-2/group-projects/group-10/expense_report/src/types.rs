@@ -1,15 +1,124 @@
-#[derive(Debug, Clone, PartialEq)]
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+use crate::policy::PolicyFlag;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ExpenseStatus {
-    Pending,
-    Approved,
+    Submitted,
+    ManagerApproved,
+    FinanceApproved,
+    Reimbursed,
     Rejected,
 }
 
-#[derive(Debug, Clone)]
+impl ExpenseStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(self, ExpenseStatus::Reimbursed | ExpenseStatus::Rejected)
+    }
+}
+
+impl fmt::Display for ExpenseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ExpenseStatus::Submitted => "Submitted",
+            ExpenseStatus::ManagerApproved => "Manager approved",
+            ExpenseStatus::FinanceApproved => "Finance approved",
+            ExpenseStatus::Reimbursed => "Reimbursed",
+            ExpenseStatus::Rejected => "Rejected",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ApproverRole {
+    Manager,
+    Finance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkflowAction {
+    Approve,
+    Reject,
+    Reimburse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Expense {
     pub id: u32,
     pub name: String,
-    pub amount: f64,
+    pub amount: Money,
     pub category: String,
     pub status: ExpenseStatus,
+    /// Who the expense is being claimed by.
+    #[serde(default)]
+    pub employee: String,
+    #[serde(default)]
+    pub submitted_at: DateTime<Utc>,
+    /// The day the money was actually spent. Stores fill it in from `submitted_at` for files saved
+    /// before it existed.
+    #[serde(default)]
+    pub incurred_on: NaiveDate,
+    #[serde(default)]
+    pub merchant: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub receipt: Option<Receipt>,
+    /// Policy rules broken when the expense was last added or edited.
+    #[serde(default)]
+    pub flags: Vec<PolicyFlag>,
+    /// Lets a flagged expense be approved. Cleared if an edit changes the flags.
+    #[serde(default)]
+    pub policy_override: Option<PolicyOverride>,
+    /// Approvers who have signed off on the current stage but not yet moved it on.
+    pub approvals: Vec<String>,
+}
+
+/// Everything needed to submit a new expense. Only the first four fields are required.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewExpense {
+    pub name: String,
+    pub category: String,
+    /// Parsed with `Money::parse`, e.g. `"12.50"` or `"12.50 EUR"`.
+    pub amount: String,
+    pub employee: String,
+    /// Defaults to today.
+    pub incurred_on: Option<NaiveDate>,
+    pub merchant: String,
+    pub notes: String,
+}
+
+/// A receipt file copied into the attachments directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// Location of the stored copy, named after its hash.
+    pub path: PathBuf,
+    /// Hex-encoded SHA-256 of the file contents.
+    pub sha256: String,
+    pub original_name: String,
+}
+
+/// Why a flagged expense may be approved anyway, and who said so.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyOverride {
+    pub actor: String,
+    pub reason: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A single recorded change in an expense's workflow. Entries are only ever appended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub expense_id: u32,
+    pub actor: String,
+    pub timestamp: DateTime<Utc>,
+    pub from: ExpenseStatus,
+    pub to: ExpenseStatus,
+    pub comment: String,
 }
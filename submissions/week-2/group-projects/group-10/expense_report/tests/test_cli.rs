@@ -0,0 +1,214 @@
+use std::process::Command as Process;
+
+use expense_report::cli::{self, Command, OutputFormat};
+use expense_report::manager::ExpenseManager;
+use expense_report::types::{ApproverRole, ExpenseStatus, NewExpense, WorkflowAction};
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn setup() -> ExpenseManager {
+    let mut manager = ExpenseManager::new();
+    manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+    manager
+}
+
+fn run(manager: &mut ExpenseManager, line: &str) -> Result<String, cli::CliError> {
+    cli::run(manager, cli::parse_args(&args(line))?)
+}
+
+#[test]
+fn test_parse_add() {
+    let command = cli::parse_args(&args("add --name Taxi --category Travel --amount=12.50 --employee Ada")).unwrap();
+    assert_eq!(
+        command,
+        Command::Add(NewExpense {
+            name: "Taxi".to_string(),
+            category: "Travel".to_string(),
+            amount: "12.50".to_string(),
+            employee: "Ada".to_string(),
+            ..NewExpense::default()
+        })
+    );
+
+    let command = cli::parse_args(&args(
+        "add --name Taxi --category Travel --amount 12.50 --employee Ada --date 2025-03-04 --merchant Uber",
+    ))
+    .unwrap();
+    let Command::Add(details) = command else { panic!("expected add") };
+    assert_eq!(details.incurred_on, chrono::NaiveDate::from_ymd_opt(2025, 3, 4));
+    assert_eq!(details.merchant, "Uber");
+}
+
+#[test]
+fn test_parse_list_and_review() {
+    let command = cli::parse_args(&args("list --status approved --format json")).unwrap();
+    assert_eq!(
+        command,
+        Command::List {
+            statuses: vec![ExpenseStatus::ManagerApproved, ExpenseStatus::FinanceApproved],
+            format: OutputFormat::Json,
+        }
+    );
+
+    let command = cli::parse_args(&args("approve 3 --approver Mary")).unwrap();
+    assert_eq!(
+        command,
+        Command::Review { id: 3, action: WorkflowAction::Approve, approver: "Mary".to_string(), comment: String::new() }
+    );
+}
+
+#[test]
+fn test_parse_errors_are_usage_errors() {
+    for line in [
+        "",
+        "frobnicate",
+        "add --name Taxi",
+        "add --name Taxi --category Travel --amount 1 --employee Ada --colour red",
+        "remove",
+        "remove abc",
+        "remove 1 2",
+        "list --status paid",
+        "list --format xml",
+        "approve 1",
+        "edit 1 --amount",
+        "edit 1 --amount 15",
+        "attach 1",
+        "import",
+        "import statement.csv",
+    ] {
+        let err = cli::parse_args(&args(line)).unwrap_err();
+        assert_eq!(err.code, cli::EXIT_USAGE, "{}", line);
+    }
+}
+
+#[test]
+fn test_add_list_and_approve() {
+    let mut manager = setup();
+
+    assert_eq!(run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada").unwrap(), "1");
+    run(&mut manager, "add --name Lunch --category Food --amount 8 --employee Bola").unwrap();
+    run(&mut manager, "approve 1 --approver Mary --comment ok").unwrap();
+
+    let json = run(&mut manager, "list --status approved --format json").unwrap();
+    let listed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["name"], "Taxi");
+
+    let csv = run(&mut manager, "list --format csv").unwrap();
+    assert_eq!(csv.lines().count(), 3);
+}
+
+#[test]
+fn test_edit_and_remove() {
+    let mut manager = setup();
+    run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada").unwrap();
+
+    run(&mut manager, "edit 1 --editor Ada --amount 15").unwrap();
+    assert_eq!(manager.get_expense(1).unwrap().amount.minor_units(), 1500);
+
+    assert_eq!(run(&mut manager, "edit 1 --editor Ada").unwrap_err().code, cli::EXIT_USAGE);
+    run(&mut manager, "reject 1 --approver Mary").unwrap();
+    run(&mut manager, "remove 1").unwrap();
+    assert!(manager.get_expense(1).is_none());
+}
+
+#[test]
+fn test_error_exit_codes() {
+    let mut manager = setup();
+    run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada").unwrap();
+
+    assert_eq!(run(&mut manager, "remove 99").unwrap_err().code, cli::EXIT_NOT_FOUND);
+    assert_eq!(run(&mut manager, "edit 99 --editor Ada --name X").unwrap_err().code, cli::EXIT_NOT_FOUND);
+    assert_eq!(run(&mut manager, "remove 1").unwrap_err().code, cli::EXIT_NOT_ALLOWED);
+    assert_eq!(run(&mut manager, "edit 1 --editor Ada --amount -3").unwrap_err().code, cli::EXIT_INVALID_INPUT);
+    assert_eq!(run(&mut manager, "reimburse 1 --approver Mary").unwrap_err().code, cli::EXIT_NOT_ALLOWED);
+    assert_eq!(run(&mut manager, "approve 1 --approver Nobody").unwrap_err().code, cli::EXIT_NOT_FOUND);
+    assert_eq!(
+        run(&mut manager, "add --name A --category B --amount NaN --employee C").unwrap_err().code,
+        cli::EXIT_INVALID_INPUT
+    );
+}
+
+#[test]
+fn test_policy_override() {
+    let mut manager = setup();
+    manager.policy.flag_weekends = true;
+    run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada --date 2025-03-01").unwrap();
+
+    assert!(run(&mut manager, "list").unwrap().contains("incurred on a weekend"));
+    assert_eq!(run(&mut manager, "approve 1 --approver Mary").unwrap_err().code, cli::EXIT_NOT_ALLOWED);
+    assert_eq!(run(&mut manager, "override 1 --approver Mary").unwrap_err().code, cli::EXIT_USAGE);
+
+    run(&mut manager, "override 1 --approver Mary --reason=Conference").unwrap();
+    assert!(run(&mut manager, "list").unwrap().contains("(overridden)"));
+    run(&mut manager, "approve 1 --approver Mary").unwrap();
+}
+
+#[test]
+fn test_attach_and_import() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut manager = setup();
+    manager.attachments_dir = dir.path().join("attachments");
+    run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada").unwrap();
+
+    let receipt = dir.path().join("taxi.pdf");
+    std::fs::write(&receipt, b"receipt").unwrap();
+    let line = format!("attach 1 {}", receipt.display());
+    assert!(run(&mut manager, &line).unwrap().starts_with("Receipt stored at"));
+    assert_eq!(run(&mut manager, "attach 9 Cargo.toml").unwrap_err().code, cli::EXIT_NOT_FOUND);
+
+    let statement = dir.path().join("statement.csv");
+    std::fs::write(&statement, "Date,Description,Amount\n2025-03-01,Cafe,-4.20\n2025-03-02,Cafe,oops\n").unwrap();
+    let err = run(&mut manager, &format!("import {} --employee Ada", statement.display())).unwrap_err();
+    assert_eq!(err.code, cli::EXIT_INVALID_INPUT);
+    assert!(err.message.starts_with("Imported 1 expense(s)"));
+    assert!(err.message.contains("line 3"));
+    assert_eq!(manager.view_expenses().len(), 2);
+
+    let missing = dir.path().join("missing.csv");
+    assert_eq!(run(&mut manager, &format!("import {} --employee Ada", missing.display())).unwrap_err().code, cli::EXIT_STORAGE);
+}
+
+#[test]
+fn test_binary_exit_codes() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_file = dir.path().join("expenses.json");
+    let expense = |line: &str| {
+        Process::new(env!("CARGO_BIN_EXE_expense_report"))
+            .args(args(line))
+            .current_dir(dir.path())
+            .env("EXPENSE_DATA_FILE", &data_file)
+            .output()
+            .unwrap()
+    };
+
+    let added = expense("add --name Taxi --category Travel --amount 12.50 --employee Ada");
+    assert!(added.status.success());
+    assert_eq!(String::from_utf8_lossy(&added.stdout).trim(), "1");
+
+    // The second run sees the first one's data
+    let listed = expense("list --format csv");
+    assert!(String::from_utf8_lossy(&listed.stdout).contains("Taxi"));
+
+    assert_eq!(expense("remove 1").status.code(), Some(cli::EXIT_NOT_ALLOWED));
+    assert_eq!(expense("remove 7").status.code(), Some(cli::EXIT_NOT_FOUND));
+    assert_eq!(expense("bogus").status.code(), Some(cli::EXIT_USAGE));
+}
+
+#[test]
+fn test_error_messages_come_from_the_errors() {
+    let mut manager = setup();
+    run(&mut manager, "add --name Taxi --category Travel --amount 12.50 --employee Ada").unwrap();
+
+    assert_eq!(run(&mut manager, "remove 99").unwrap_err().message, "Expense not found");
+    assert_eq!(
+        run(&mut manager, "remove 1").unwrap_err().message,
+        "Cannot remove expenses still in review. Reimburse or reject the expense first"
+    );
+    assert_eq!(
+        run(&mut manager, "approve 1 --approver Nobody").unwrap_err().message,
+        "'Nobody': Not a registered approver"
+    );
+}
@@ -0,0 +1,145 @@
+use chrono::NaiveDate;
+use expense_report::errors::{AttachError, ImportError, MoneyError, RowError};
+use expense_report::import::{self, DEFAULT_CATEGORY};
+use expense_report::manager::ExpenseManager;
+use expense_report::money::{Currency, Money};
+use expense_report::types::NewExpense;
+use sha2::{Digest, Sha256};
+
+const STATEMENT: &str = "\
+Transaction Date,Description,Amount,Currency,Memo
+2025-03-01,Corner Cafe,-4.20,,flat white
+02/03/2025,Metro Rail,\"-1,250.00\",EUR,
+2025-03-03,,9.99,,
+2025-13-01,Bookshop,12.00,,
+2025-03-04,Bookshop,twelve,,
+";
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_parse_bank_csv() {
+    let rows = import::parse_bank_csv(STATEMENT.as_bytes(), Currency::USD, "Ada").unwrap();
+    assert_eq!(rows.len(), 5);
+
+    let cafe = rows[0].as_ref().unwrap();
+    assert_eq!(cafe.line, 2);
+    assert_eq!(cafe.amount, Money::parse("4.20", Currency::USD).unwrap());
+    assert_eq!(cafe.details.incurred_on, Some(date(2025, 3, 1)));
+    assert_eq!(cafe.details.merchant, "Corner Cafe");
+    assert_eq!(cafe.details.category, DEFAULT_CATEGORY);
+    assert_eq!(cafe.details.notes, "flat white");
+    assert_eq!(cafe.details.employee, "Ada");
+
+    let rail = rows[1].as_ref().unwrap();
+    assert_eq!(rail.amount, Money::parse("1250.00 EUR", Currency::USD).unwrap());
+    assert_eq!(rail.details.incurred_on, Some(date(2025, 3, 2)));
+
+    let errors: Vec<(u64, &RowError)> = rows[2..]
+        .iter()
+        .map(|row| {
+            let issue = row.as_ref().unwrap_err();
+            (issue.line, &issue.error)
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (4, &RowError::MissingField("merchant")),
+            (5, &RowError::InvalidDate("2025-13-01".to_string())),
+            (6, &RowError::InvalidAmount(MoneyError::InvalidFormat)),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_requires_columns() {
+    let err = import::parse_bank_csv("Date,Amount\n2025-03-01,4.20\n".as_bytes(), Currency::USD, "Ada").unwrap_err();
+    assert!(matches!(err, ImportError::MissingColumn(column) if column == "merchant"));
+}
+
+#[test]
+fn test_import_skips_duplicates() {
+    let mut manager = ExpenseManager::new();
+    manager
+        .add_expense_with(NewExpense {
+            name: "Coffee".to_string(),
+            category: "Food".to_string(),
+            amount: "4.20".to_string(),
+            employee: "Ada".to_string(),
+            incurred_on: Some(date(2025, 3, 1)),
+            merchant: "corner cafe ".to_string(),
+            ..NewExpense::default()
+        })
+        .unwrap();
+
+    let csv = "Date,Merchant,Amount\n2025-03-01,Corner Cafe,-4.20\n2025-03-05,Taxi,-18.00\n2025-03-05,TAXI,-18.00\nbad,Taxi,-1\n";
+    let report = manager.import_csv(csv.as_bytes(), "Ada").unwrap();
+
+    assert_eq!(report.imported, vec![2]);
+    assert_eq!(report.duplicates, vec![2, 4]);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, 5);
+    assert!(report.summary().starts_with("Imported 1 expense(s), skipped 2 duplicate(s), 0 credit(s), 1 row(s) with errors"));
+
+    let taxi = manager.get_expense(2).unwrap();
+    assert_eq!(taxi.merchant, "Taxi");
+    assert_eq!(taxi.incurred_on, date(2025, 3, 5));
+
+    // Importing the same file again adds nothing
+    let again = manager.import_csv(csv.as_bytes(), "Ada").unwrap();
+    assert!(again.imported.is_empty());
+}
+
+#[test]
+fn test_import_skips_credits() {
+    let mut manager = ExpenseManager::new();
+    let csv = "\
+Date,Description,Amount
+2025-03-01,Corner Cafe,-4.20
+2025-03-02,Corner Cafe,4.20
+2025-03-03,Salary,\"2,500.00\"
+2025-03-04,Metro Rail,-2.50
+";
+    let report = manager.import_csv(csv.as_bytes(), "Ada").unwrap();
+
+    assert_eq!(report.imported, vec![1, 2]);
+    assert_eq!(report.credits, vec![3, 4]);
+    assert!(report.errors.is_empty());
+    assert!(report.summary().contains("line 4: credit, not an expense"));
+    let total: i64 = manager.view_expenses().iter().map(|e| e.amount.minor_units()).sum();
+    assert_eq!(total, 670);
+}
+
+#[test]
+fn test_debit_column_is_positive_spend() {
+    let csv = "Date,Payee,Debit\n2025-03-01,Corner Cafe,4.20\n2025-03-02,Corner Cafe,-4.20\n";
+    let rows = import::parse_bank_csv(csv.as_bytes(), Currency::USD, "Ada").unwrap();
+
+    assert_eq!(rows[0].as_ref().unwrap().amount, Money::parse("4.20", Currency::USD).unwrap());
+    assert_eq!(rows[1].as_ref().unwrap_err().error, RowError::Credit("-4.20".to_string()));
+}
+
+#[test]
+fn test_attach_receipt() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut manager = ExpenseManager::new();
+    manager.attachments_dir = dir.path().join("attachments");
+    let id = manager.add_expense("Taxi".to_string(), "Travel".to_string(), "12.00".to_string(), "Ada".to_string()).unwrap();
+
+    let source = dir.path().join("Receipt.PDF");
+    std::fs::write(&source, b"%PDF taxi").unwrap();
+    let receipt = manager.attach_receipt(id, &source).unwrap();
+
+    let expected = format!("{:x}", Sha256::digest(b"%PDF taxi"));
+    assert_eq!(receipt.sha256, expected);
+    assert_eq!(receipt.original_name, "Receipt.PDF");
+    assert_eq!(receipt.path, manager.attachments_dir.join(format!("{}.pdf", expected)));
+    assert_eq!(std::fs::read(&receipt.path).unwrap(), b"%PDF taxi");
+    assert_eq!(manager.get_expense(id).unwrap().receipt.as_ref(), Some(&receipt));
+
+    assert!(matches!(manager.attach_receipt(99, &source), Err(AttachError::NotFound)));
+    assert!(matches!(manager.attach_receipt(id, &dir.path().join("missing.jpg")), Err(AttachError::Io(_))));
+}
@@ -0,0 +1,109 @@
+use expense_report::errors::MoneyError;
+use expense_report::manager::ExpenseManager;
+use expense_report::money::{Currency, ExchangeRates, Money};
+
+fn currency(code: &str) -> Currency {
+    Currency::new(code).unwrap()
+}
+
+#[test]
+fn test_parse_with_and_without_currency() {
+    let money = Money::parse("12.50 USD", currency("EUR")).unwrap();
+    assert_eq!(money.minor_units(), 1250);
+    assert_eq!(money.currency(), Currency::USD);
+
+    let money = Money::parse("7", currency("eur")).unwrap();
+    assert_eq!(money.minor_units(), 700);
+    assert_eq!(money.currency(), currency("EUR"));
+}
+
+#[test]
+fn test_parse_respects_minor_digits() {
+    assert_eq!(Money::parse("1500 JPY", Currency::USD).unwrap().minor_units(), 1500);
+    assert_eq!(Money::parse("1.250 KWD", Currency::USD).unwrap().minor_units(), 1250);
+    assert_eq!(Money::parse("0.1", Currency::USD).unwrap().minor_units(), 10);
+    assert_eq!(Money::parse("1.234", Currency::USD).unwrap_err(), MoneyError::TooManyDecimals);
+    assert_eq!(Money::parse("1.5 JPY", Currency::USD).unwrap_err(), MoneyError::TooManyDecimals);
+}
+
+#[test]
+fn test_parse_rejects_bad_input() {
+    assert_eq!(Money::parse("-5.00", Currency::USD).unwrap_err(), MoneyError::Negative);
+    assert_eq!(Money::parse("NaN", Currency::USD).unwrap_err(), MoneyError::NonFinite);
+    assert_eq!(Money::parse("inf USD", Currency::USD).unwrap_err(), MoneyError::NonFinite);
+    assert_eq!(Money::parse("-infinity", Currency::USD).unwrap_err(), MoneyError::NonFinite);
+    assert_eq!(Money::parse("", Currency::USD).unwrap_err(), MoneyError::InvalidFormat);
+    assert_eq!(Money::parse("1e3", Currency::USD).unwrap_err(), MoneyError::InvalidFormat);
+    assert_eq!(Money::parse("12.50 USD extra", Currency::USD).unwrap_err(), MoneyError::InvalidFormat);
+    assert_eq!(Money::parse("99999999999999999999", Currency::USD).unwrap_err(), MoneyError::Overflow);
+    assert!(matches!(
+        Money::parse("12.50 US", Currency::USD).unwrap_err(),
+        MoneyError::InvalidCurrency(_)
+    ));
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(Money::parse("12.5 usd", Currency::USD).unwrap().to_string(), "12.50 USD");
+    assert_eq!(Money::parse("1500 JPY", Currency::USD).unwrap().to_string(), "1500 JPY");
+    assert_eq!(Money::parse("0.005 KWD", Currency::USD).unwrap().to_string(), "0.005 KWD");
+}
+
+#[test]
+fn test_totals_are_exact() {
+    let rates = ExchangeRates::default();
+    let amounts: Vec<Money> = (0..10).map(|_| Money::parse("0.10", Currency::USD).unwrap()).collect();
+
+    assert_eq!(rates.total(&amounts).unwrap(), Money::parse("1.00", Currency::USD).unwrap());
+}
+
+#[test]
+fn test_conversion_to_base() {
+    let rates = ExchangeRates::parse_table("# local rates\nbase USD\nEUR 1.08\nJPY 0.0067\n").unwrap();
+
+    let eur = Money::parse("10.00 EUR", Currency::USD).unwrap();
+    assert_eq!(rates.to_base(&eur).unwrap(), Money::parse("10.80", Currency::USD).unwrap());
+
+    // 1234 * 0.0067 = 8.2678, rounded half-up to cents
+    let jpy = Money::parse("1234 JPY", Currency::USD).unwrap();
+    assert_eq!(rates.to_base(&jpy).unwrap(), Money::parse("8.27", Currency::USD).unwrap());
+
+    let total = rates.total([&eur, &jpy]).unwrap();
+    assert_eq!(total, Money::parse("19.07", Currency::USD).unwrap());
+}
+
+#[test]
+fn test_missing_rate() {
+    let rates = ExchangeRates::default();
+    let gbp = Money::parse("5.00 GBP", Currency::USD).unwrap();
+
+    assert_eq!(rates.to_base(&gbp).unwrap_err(), MoneyError::MissingRate(currency("GBP")));
+}
+
+#[test]
+fn test_rate_table_errors() {
+    assert!(ExchangeRates::parse_table("EUR").is_err());
+    assert_eq!(ExchangeRates::parse_table("EUR -1").unwrap_err(), MoneyError::Negative);
+    assert_eq!(ExchangeRates::parse_table("EUR 0").unwrap_err(), MoneyError::InvalidFormat);
+}
+
+#[test]
+fn test_manager_uses_base_currency() {
+    let mut manager = ExpenseManager::new();
+    manager.rates = ExchangeRates::parse_table("base EUR\nUSD 0.90").unwrap();
+
+    let local = manager.add_expense("Train".to_string(), "Travel".to_string(), "20.00".to_string(), "Sam".to_string()).unwrap();
+    manager.add_expense("Hotel".to_string(), "Travel".to_string(), "100.00 USD".to_string(), "Sam".to_string()).unwrap();
+
+    assert_eq!(manager.get_expense(local).unwrap().amount.currency(), currency("EUR"));
+    assert_eq!(manager.total_expenses().unwrap(), Money::parse("110.00 EUR", Currency::USD).unwrap());
+}
+
+#[test]
+fn test_deserialize_rejects_negative_amounts() {
+    let money: Money = serde_json::from_str(r#"{"minor_units": 1250, "currency": "usd"}"#).unwrap();
+    assert_eq!(money, Money::new(1250, Currency::USD).unwrap());
+
+    let result = serde_json::from_str::<Money>(r#"{"minor_units": -1250, "currency": "USD"}"#);
+    assert!(result.unwrap_err().to_string().contains("amount cannot be negative"));
+}
@@ -0,0 +1,158 @@
+use chrono::NaiveDate;
+use expense_report::errors::{PolicyError, WorkflowError};
+use expense_report::manager::ExpenseManager;
+use expense_report::money::{Currency, Money};
+use expense_report::policy::{Policy, PolicyFlag};
+use expense_report::types::{ApproverRole, ExpenseStatus, NewExpense};
+
+const POLICY: &str = r#"{
+    "category_caps": { "Meals": "75.00", "Travel": "400 EUR" },
+    "flag_weekends": true,
+    "duplicate_window_days": 3,
+    "round_amount_multiple": 50
+}"#;
+
+fn usd(amount: &str) -> Money {
+    Money::parse(amount, Currency::USD).unwrap()
+}
+
+fn setup() -> ExpenseManager {
+    let mut manager = ExpenseManager::new();
+    manager.policy = Policy::from_json(POLICY, Currency::USD).unwrap();
+    manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+    manager.add_approver("Fred".to_string(), ApproverRole::Finance).unwrap();
+    manager
+}
+
+/// 2025-03-03 is a Monday.
+fn submit(manager: &mut ExpenseManager, category: &str, amount: &str, merchant: &str, day: u32) -> u32 {
+    manager
+        .add_expense_with(NewExpense {
+            name: "Expense".to_string(),
+            category: category.to_string(),
+            amount: amount.to_string(),
+            employee: "Ada".to_string(),
+            incurred_on: NaiveDate::from_ymd_opt(2025, 3, day),
+            merchant: merchant.to_string(),
+            ..NewExpense::default()
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_policy_from_json() {
+    let policy = Policy::from_json(POLICY, Currency::USD).unwrap();
+    assert_eq!(policy.category_caps["Meals"], usd("75.00"));
+    assert_eq!(policy.category_caps["Travel"], Money::parse("400 EUR", Currency::USD).unwrap());
+    assert!(policy.flag_weekends);
+
+    assert_eq!(Policy::from_json("{}", Currency::USD).unwrap(), Policy::default());
+    assert!(matches!(Policy::from_json("{\"flag_weekend\": true}", Currency::USD), Err(PolicyError::Parse(_))));
+    assert!(matches!(
+        Policy::from_json("{\"category_caps\": {\"Meals\": \"lots\"}}", Currency::USD),
+        Err(PolicyError::InvalidCap { category, .. }) if category == "Meals"
+    ));
+}
+
+#[test]
+fn test_clean_expense_has_no_flags() {
+    let mut manager = setup();
+    let id = submit(&mut manager, "Meals", "42.10", "Cafe", 3);
+    assert!(manager.policy_flags(id).is_empty());
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+}
+
+#[test]
+fn test_each_rule_flags() {
+    let mut manager = setup();
+
+    let over_cap = submit(&mut manager, "Meals", "80.25", "Steakhouse", 4);
+    assert_eq!(manager.policy_flags(over_cap), [PolicyFlag::OverCategoryCap { cap: usd("75.00") }]);
+
+    let weekend = submit(&mut manager, "Meals", "12.30", "Bakery", 1);
+    assert_eq!(manager.policy_flags(weekend), [PolicyFlag::WeekendSpend]);
+
+    let round = submit(&mut manager, "Office", "150.00", "Stationer", 5);
+    assert_eq!(manager.policy_flags(round), [PolicyFlag::RoundAmount]);
+
+    let original = submit(&mut manager, "Office", "19.99", "Stationer", 5);
+    let duplicate = submit(&mut manager, "Office", "19.99", " STATIONER", 7);
+    let later = submit(&mut manager, "Office", "19.99", "Stationer", 14);
+    assert!(manager.policy_flags(original).is_empty());
+    assert_eq!(manager.policy_flags(duplicate), [PolicyFlag::PossibleDuplicate { of: original }]);
+    assert!(manager.policy_flags(later).is_empty());
+}
+
+#[test]
+fn test_cap_in_other_currency_needs_rates() {
+    let mut manager = setup();
+
+    // No EUR rate: the 400 EUR travel cap cannot be compared against USD spend
+    let id = submit(&mut manager, "Travel", "455.10", "Airline", 3);
+    assert!(manager.policy_flags(id).is_empty());
+
+    manager.rates = expense_report::money::ExchangeRates::parse_table("EUR 1.10").unwrap();
+    let id = submit(&mut manager, "Travel", "455.10", "Rail", 3);
+    assert_eq!(manager.policy_flags(id).len(), 1);
+}
+
+#[test]
+fn test_flagged_expense_needs_override() {
+    let mut manager = setup();
+    let id = submit(&mut manager, "Meals", "80.25", "Steakhouse", 4);
+
+    assert!(matches!(
+        manager.approve(id, "Mary", String::new()),
+        Err(WorkflowError::PolicyViolation(flags)) if flags.len() == 1
+    ));
+    assert!(matches!(manager.override_policy(id, "Mary", "  ".to_string()), Err(WorkflowError::MissingReason)));
+    assert!(matches!(manager.override_policy(id, "Nobody", "ok".to_string()), Err(WorkflowError::UnknownApprover)));
+
+    manager.override_policy(id, "Mary", "Client dinner".to_string()).unwrap();
+    let recorded = manager.get_expense(id).unwrap().policy_override.clone().unwrap();
+    assert_eq!(recorded.actor, "Mary");
+    assert_eq!(recorded.reason, "Client dinner");
+
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+    assert_eq!(manager.approve(id, "Fred", String::new()).unwrap(), ExpenseStatus::FinanceApproved);
+
+    let trail = manager.audit_trail(id);
+    assert_eq!(trail[0].comment, "Policy override: Client dinner");
+    assert_eq!(trail[0].from, trail[0].to);
+}
+
+#[test]
+fn test_override_rules() {
+    let mut manager = setup();
+    let clean = submit(&mut manager, "Meals", "42.10", "Cafe", 3);
+    assert!(matches!(manager.override_policy(clean, "Mary", "why".to_string()), Err(WorkflowError::NothingToOverride)));
+    assert!(matches!(manager.override_policy(99, "Mary", "why".to_string()), Err(WorkflowError::NotFound)));
+
+    let flagged = submit(&mut manager, "Meals", "80.25", "Steakhouse", 4);
+    manager.reject(flagged, "Mary", String::new()).unwrap();
+    assert!(matches!(
+        manager.override_policy(flagged, "Mary", "why".to_string()),
+        Err(WorkflowError::NothingToOverride)
+    ));
+}
+
+#[test]
+fn test_edit_reevaluates_policy() {
+    let mut manager = setup();
+    let id = submit(&mut manager, "Meals", "80.25", "Steakhouse", 4);
+    manager.override_policy(id, "Mary", "Client dinner".to_string()).unwrap();
+
+    // Same flags after the edit: the override still applies
+    manager.edit_expense(id, "Sam", Some("Dinner".to_string()), None, None, true).unwrap();
+    assert!(manager.get_expense(id).unwrap().policy_override.is_some());
+
+    // Different flags: the override is dropped and must be given again
+    manager.edit_expense(id, "Sam", None, Some("100.00".to_string()), None, true).unwrap();
+    let expense = manager.get_expense(id).unwrap();
+    assert_eq!(expense.flags.len(), 2);
+    assert!(expense.policy_override.is_none());
+
+    manager.edit_expense(id, "Sam", None, Some("60.10".to_string()), None, true).unwrap();
+    assert!(manager.policy_flags(id).is_empty());
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+}
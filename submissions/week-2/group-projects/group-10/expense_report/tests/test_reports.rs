@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use expense_report::manager::ExpenseManager;
+use expense_report::money::{Currency, ExchangeRates, Money};
+use expense_report::reports::{self, GroupBy};
+use expense_report::types::{ApproverRole, Expense, ExpenseStatus};
+
+fn usd(amount: &str) -> Money {
+    Money::parse(amount, Currency::USD).unwrap()
+}
+
+fn expense(id: u32, employee: &str, category: &str, amount: &str, month: u32, status: ExpenseStatus) -> Expense {
+    Expense {
+        id,
+        name: format!("Expense {}", id),
+        amount: Money::parse(amount, Currency::USD).unwrap(),
+        category: category.to_string(),
+        status,
+        employee: employee.to_string(),
+        submitted_at: Utc.with_ymd_and_hms(2025, month, 15, 12, 0, 0).unwrap(),
+        incurred_on: NaiveDate::from_ymd_opt(2025, month, 14).unwrap(),
+        merchant: String::new(),
+        notes: String::new(),
+        receipt: None,
+        flags: Vec::new(),
+        policy_override: None,
+        approvals: Vec::new(),
+    }
+}
+
+fn sample() -> Vec<Expense> {
+    vec![
+        expense(1, "Ada", "Travel", "100.00", 1, ExpenseStatus::Submitted),
+        expense(2, "Ada", "Food", "20.50", 1, ExpenseStatus::Reimbursed),
+        expense(3, "Bola", "Travel", "300.00", 2, ExpenseStatus::Rejected),
+        expense(4, "Chidi", "Food", "45.00 EUR", 2, ExpenseStatus::ManagerApproved),
+    ]
+}
+
+fn rates() -> ExchangeRates {
+    ExchangeRates::parse_table("EUR 1.10").unwrap()
+}
+
+#[test]
+fn test_summary_by_category() {
+    let expenses = sample();
+    let rows = reports::summarize(&expenses, GroupBy::Category, &rates()).unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].key, "Food");
+    assert_eq!(rows[0].count, 2);
+    // 20.50 USD + 45.00 EUR at 1.10
+    assert_eq!(rows[0].total, usd("70.00"));
+    assert_eq!(rows[1].key, "Travel");
+    assert_eq!(rows[1].total, usd("400.00"));
+}
+
+#[test]
+fn test_summary_by_month_and_status() {
+    let mut expenses = sample();
+    // Spent in January but only submitted in March, e.g. from an imported bank export
+    let mut imported = expense(5, "Ada", "Food", "9.50", 3, ExpenseStatus::Submitted);
+    imported.incurred_on = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+    expenses.push(imported);
+
+    let months = reports::summarize(&expenses, GroupBy::Month, &rates()).unwrap();
+    let keys: Vec<&str> = months.iter().map(|row| row.key.as_str()).collect();
+    assert_eq!(keys, vec!["2025-01", "2025-02"]);
+    assert_eq!(months[0].count, 3);
+    assert_eq!(months[0].total, usd("130.00"));
+
+    let statuses = reports::summarize(&expenses, GroupBy::Status, &rates()).unwrap();
+    assert_eq!(statuses.len(), 4);
+    assert!(statuses.iter().any(|row| row.key == "Rejected" && row.count == 1));
+    assert!(statuses.iter().any(|row| row.key == "Submitted" && row.count == 2));
+}
+
+#[test]
+fn test_summary_needs_rates() {
+    let expenses = sample();
+    assert!(reports::summarize(&expenses, GroupBy::Employee, &ExchangeRates::default()).is_err());
+}
+
+#[test]
+fn test_top_spenders_skips_rejected() {
+    let expenses = sample();
+    let rows = reports::top_spenders(&expenses, 2, &rates()).unwrap();
+
+    let names: Vec<&str> = rows.iter().map(|row| row.key.as_str()).collect();
+    assert_eq!(names, vec!["Ada", "Chidi"]);
+    assert_eq!(rows[0].total, usd("120.50"));
+}
+
+#[test]
+fn test_budget_vs_actual() {
+    let expenses = sample();
+    let budgets = reports::parse_budgets("# monthly\nTravel = 50\nFood = 100.00\nOffice = 30 EUR\n", Currency::USD).unwrap();
+
+    let lines = reports::budget_vs_actual(&expenses, &budgets, &rates()).unwrap();
+    let categories: Vec<&str> = lines.iter().map(|line| line.category.as_str()).collect();
+    assert_eq!(categories, vec!["Food", "Office", "Travel"]);
+
+    assert_eq!(lines[0].remaining(), Some(3000));
+    assert!(!lines[0].is_over_budget());
+    assert_eq!(lines[1].budget, Some(usd("33.00")));
+    assert_eq!(lines[1].actual, usd("0"));
+    // Rejected travel is not counted
+    assert_eq!(lines[2].actual, usd("100.00"));
+    assert!(lines[2].is_over_budget());
+}
+
+#[test]
+fn test_budget_without_entry() {
+    let expenses = sample();
+    let lines = reports::budget_vs_actual(&expenses, &HashMap::new(), &rates()).unwrap();
+
+    assert!(lines.iter().all(|line| line.budget.is_none() && !line.is_over_budget()));
+}
+
+#[test]
+fn test_render_table_and_csv() {
+    let expenses = sample();
+    let rows = reports::summarize(&expenses, GroupBy::Employee, &rates()).unwrap();
+    let (headers, cells) = reports::summary_rows(GroupBy::Employee, &rows);
+
+    let table = reports::render_table(&headers, &cells);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[0], "Employee | Count | Total");
+    assert_eq!(lines[1], "---------+-------+-----------");
+    assert_eq!(lines[2], "Ada      | 2     | 120.50 USD");
+
+    let csv = reports::render_csv(&headers, &[vec!["Smith, J".to_string(), "1".to_string(), "say \"hi\"".to_string()]]);
+    assert_eq!(csv, "Employee,Count,Total\n\"Smith, J\",1,\"say \"\"hi\"\"\"");
+}
+
+#[test]
+fn test_manager_reports() {
+    let mut manager = ExpenseManager::new();
+    manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+    manager.budgets.insert("Travel".to_string(), usd("10.00"));
+
+    let id = manager.add_expense("Taxi".to_string(), "Travel".to_string(), "12.00".to_string(), "Ada".to_string()).unwrap();
+    manager.add_expense("Bus".to_string(), "Travel".to_string(), "3.00".to_string(), "Bola".to_string()).unwrap();
+    manager.reject(id, "Mary", String::new()).unwrap();
+
+    let by_employee = manager.summary(GroupBy::Employee).unwrap();
+    assert_eq!(by_employee.len(), 2);
+
+    let top = manager.top_spenders(5).unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].key, "Bola");
+
+    let budget = manager.budget_vs_actual().unwrap();
+    assert_eq!(budget[0].remaining(), Some(700));
+}
@@ -0,0 +1,134 @@
+use std::fs;
+
+use expense_report::errors::StorageError;
+use expense_report::manager::ExpenseManager;
+use expense_report::reports::GroupBy;
+use expense_report::storage::JsonFileStore;
+use expense_report::types::{ApproverRole, ExpenseStatus};
+
+fn open(path: &std::path::Path) -> ExpenseManager<JsonFileStore> {
+    ExpenseManager::with_store(JsonFileStore::open(path).unwrap())
+}
+
+#[test]
+fn test_data_survives_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("expenses.json");
+
+    let id = {
+        let mut manager = open(&path);
+        manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+        let id = manager
+            .add_expense("Taxi".to_string(), "Travel".to_string(), "30.00".to_string(), "Sam".to_string())
+            .unwrap();
+        manager.approve(id, "Mary", "ok".to_string()).unwrap();
+        id
+    };
+
+    let manager = open(&path);
+    let expense = manager.get_expense(id).unwrap();
+    assert_eq!(expense.name, "Taxi");
+    assert_eq!(expense.status, ExpenseStatus::ManagerApproved);
+    assert_eq!(manager.approver_role("Mary"), Some(&ApproverRole::Manager));
+    assert_eq!(manager.audit_trail(id).len(), 1);
+}
+
+#[test]
+fn test_ids_are_not_reused_after_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("expenses.json");
+
+    {
+        let mut manager = open(&path);
+        manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+        manager.add_expense("A".to_string(), "Cat".to_string(), "1.00".to_string(), "Sam".to_string()).unwrap();
+        let last = manager.add_expense("B".to_string(), "Cat".to_string(), "2.00".to_string(), "Sam".to_string()).unwrap();
+        manager.reject(last, "Mary", String::new()).unwrap();
+        manager.remove_expense(last).unwrap();
+    }
+
+    let mut manager = open(&path);
+    assert_eq!(manager.next_id(), 3);
+    let id = manager.add_expense("C".to_string(), "Cat".to_string(), "3.00".to_string(), "Sam".to_string()).unwrap();
+    assert_eq!(id, 3);
+}
+
+#[test]
+fn test_missing_file_starts_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let manager = open(&dir.path().join("missing.json"));
+
+    assert_eq!(manager.next_id(), 1);
+    assert!(manager.view_expenses().is_empty());
+}
+
+#[test]
+fn test_corrupt_file_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("expenses.json");
+    fs::write(&path, "{ not json").unwrap();
+
+    let result = JsonFileStore::open(&path);
+    assert!(matches!(result.unwrap_err(), StorageError::Corrupt(_)));
+}
+
+#[test]
+fn test_failed_write_leaves_store_unchanged() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_dir = dir.path().join("data");
+    fs::create_dir(&data_dir).unwrap();
+    let path = data_dir.join("expenses.json");
+
+    let mut manager = open(&path);
+    manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+    let id = manager
+        .add_expense("Taxi".to_string(), "Travel".to_string(), "30.00".to_string(), "Sam".to_string())
+        .unwrap();
+
+    // With the directory gone every write fails
+    fs::remove_dir_all(&data_dir).unwrap();
+
+    assert!(manager.approve(id, "Mary", "ok".to_string()).is_err());
+    assert_eq!(manager.get_expense(id).unwrap().status, ExpenseStatus::Submitted);
+    assert!(manager.audit_trail(id).is_empty());
+
+    assert!(manager.add_expense("Bus".to_string(), "Travel".to_string(), "2.00".to_string(), "Sam".to_string()).is_err());
+    assert_eq!(manager.next_id(), 2);
+    assert_eq!(manager.view_expenses().len(), 1);
+
+    assert!(manager.add_approver("Fred".to_string(), ApproverRole::Finance).is_err());
+    assert_eq!(manager.approver_role("Fred"), None);
+}
+
+#[test]
+fn test_missing_incurred_on_defaults_to_submission_date() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("expenses.json");
+    // Written before expenses had a date incurred, merchant, notes, receipt or policy flags
+    fs::write(
+        &path,
+        r#"{
+  "expenses": {
+    "1": {
+      "id": 1,
+      "name": "Taxi",
+      "amount": { "minor_units": 3000, "currency": "USD" },
+      "category": "Travel",
+      "status": "Submitted",
+      "employee": "Sam",
+      "submitted_at": "2024-11-28T23:30:00Z",
+      "approvals": []
+    }
+  },
+  "next_id": 2,
+  "approvers": {},
+  "audit_log": []
+}"#,
+    )
+    .unwrap();
+
+    let manager = open(&path);
+    let expense = manager.get_expense(1).unwrap();
+    assert_eq!(expense.incurred_on, chrono::NaiveDate::from_ymd_opt(2024, 11, 28).unwrap());
+    assert_eq!(GroupBy::Month.key(expense), "2024-11");
+}
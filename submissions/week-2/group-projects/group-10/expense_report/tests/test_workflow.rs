@@ -0,0 +1,206 @@
+use expense_report::errors::{MoneyError, WorkflowError};
+use expense_report::manager::ExpenseManager;
+use expense_report::money::{Currency, Money};
+use expense_report::types::{ApproverRole, ExpenseStatus, WorkflowAction};
+
+fn setup() -> ExpenseManager {
+    let mut manager = ExpenseManager::new();
+    manager.add_approver("Mary".to_string(), ApproverRole::Manager).unwrap();
+    manager.add_approver("Mike".to_string(), ApproverRole::Manager).unwrap();
+    manager.add_approver("Fred".to_string(), ApproverRole::Finance).unwrap();
+    manager.add_approver("Fiona".to_string(), ApproverRole::Finance).unwrap();
+    manager
+}
+
+fn create_expense(manager: &mut ExpenseManager, amount: &str) -> u32 {
+    manager
+        .add_expense("Flight".to_string(), "Travel".to_string(), amount.to_string(), "Sam".to_string())
+        .unwrap()
+}
+
+fn status_of(manager: &ExpenseManager, id: u32) -> ExpenseStatus {
+    manager.get_expense(id).unwrap().status.clone()
+}
+
+#[test]
+fn test_full_workflow_to_reimbursed() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    assert_eq!(manager.approve(id, "Mary", "ok".to_string()).unwrap(), ExpenseStatus::ManagerApproved);
+    assert_eq!(manager.approve(id, "Fred", "ok".to_string()).unwrap(), ExpenseStatus::FinanceApproved);
+    assert_eq!(manager.reimburse(id, "Fred", "paid".to_string()).unwrap(), ExpenseStatus::Reimbursed);
+    assert_eq!(status_of(&manager, id), ExpenseStatus::Reimbursed);
+}
+
+#[test]
+fn test_reject_from_manager_stage() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    let result = manager.reject(id, "Mary", "no receipt".to_string());
+    assert_eq!(result.unwrap(), ExpenseStatus::Rejected);
+}
+
+#[test]
+fn test_wrong_role_is_refused() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    let result = manager.approve(id, "Fred", String::new());
+    assert!(matches!(
+        result.unwrap_err(),
+        WorkflowError::WrongRole { required: ApproverRole::Manager, actual: ApproverRole::Finance }
+    ));
+    assert_eq!(status_of(&manager, id), ExpenseStatus::Submitted);
+}
+
+#[test]
+fn test_unknown_approver() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    let result = manager.approve(id, "Nobody", String::new());
+    assert!(matches!(result.unwrap_err(), WorkflowError::UnknownApprover));
+}
+
+#[test]
+fn test_transition_on_missing_expense() {
+    let mut manager = setup();
+
+    let result = manager.approve(999, "Mary", String::new());
+    assert!(matches!(result.unwrap_err(), WorkflowError::NotFound));
+}
+
+#[test]
+fn test_illegal_transitions() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    let result = manager.reimburse(id, "Fred", String::new());
+    assert!(matches!(
+        result.unwrap_err(),
+        WorkflowError::IllegalTransition { from: ExpenseStatus::Submitted, action: WorkflowAction::Reimburse }
+    ));
+
+    manager.reject(id, "Mary", String::new()).unwrap();
+    let result = manager.approve(id, "Mary", String::new());
+    assert!(matches!(
+        result.unwrap_err(),
+        WorkflowError::IllegalTransition { from: ExpenseStatus::Rejected, action: WorkflowAction::Approve }
+    ));
+}
+
+#[test]
+fn test_amount_over_threshold_needs_second_approval() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "1500.00");
+
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::Submitted);
+    assert!(matches!(
+        manager.approve(id, "Mary", String::new()).unwrap_err(),
+        WorkflowError::AlreadyApproved
+    ));
+    assert_eq!(manager.approve(id, "Mike", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+
+    assert_eq!(manager.approve(id, "Fred", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+    assert_eq!(manager.approve(id, "Fiona", String::new()).unwrap(), ExpenseStatus::FinanceApproved);
+}
+
+#[test]
+fn test_custom_threshold() {
+    let mut manager = setup();
+    manager.second_approval_threshold = Money::parse("50.00", Currency::USD).unwrap();
+    let id = create_expense(&mut manager, "60.00");
+
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::Submitted);
+}
+
+#[test]
+fn test_audit_trail_records_transitions() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+    let other = create_expense(&mut manager, "20.00");
+
+    manager.approve(id, "Mary", "looks fine".to_string()).unwrap();
+    manager.reject(other, "Mike", "duplicate".to_string()).unwrap();
+    manager.approve(id, "Fred", String::new()).unwrap();
+    // Failed transitions are not recorded
+    let _ = manager.approve(id, "Fred", String::new());
+
+    let trail = manager.audit_trail(id);
+    assert_eq!(trail.len(), 2);
+
+    assert_eq!(trail[0].actor, "Mary");
+    assert_eq!(trail[0].from, ExpenseStatus::Submitted);
+    assert_eq!(trail[0].to, ExpenseStatus::ManagerApproved);
+    assert_eq!(trail[0].comment, "looks fine");
+
+    assert_eq!(trail[1].actor, "Fred");
+    assert_eq!(trail[1].from, ExpenseStatus::ManagerApproved);
+    assert_eq!(trail[1].to, ExpenseStatus::FinanceApproved);
+    assert!(trail[0].timestamp <= trail[1].timestamp);
+}
+
+#[test]
+fn test_audit_trail_survives_removal() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "100.00");
+
+    manager.reject(id, "Mary", String::new()).unwrap();
+    manager.remove_expense(id).unwrap();
+
+    assert_eq!(manager.audit_trail(id).len(), 1);
+}
+
+#[test]
+fn test_threshold_compared_in_base_currency() {
+    let mut manager = setup();
+    manager.rates.set_rate(Currency::new("EUR").unwrap(), "1.10").unwrap();
+
+    // 950 EUR is 1045 USD, over the default 1000 USD threshold
+    let id = create_expense(&mut manager, "950.00 EUR");
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::Submitted);
+}
+
+#[test]
+fn test_threshold_needs_exchange_rate() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "10.00 GBP");
+
+    let result = manager.approve(id, "Mary", String::new());
+    assert!(matches!(result.unwrap_err(), WorkflowError::Currency(MoneyError::MissingRate(_))));
+}
+
+#[test]
+fn test_amount_change_clears_partial_approval() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "1500.00");
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::Submitted);
+
+    // Someone other than the employee makes the change, and the audit trail names them
+    manager.edit_expense(id, "Priya", None, Some("4000.00".to_string()), None, true).unwrap();
+    assert!(manager.get_expense(id).unwrap().approvals.is_empty());
+
+    // Mary never approved 4000.00, so Mike's sign-off is only the first of two
+    assert_eq!(manager.approve(id, "Mike", String::new()).unwrap(), ExpenseStatus::Submitted);
+    assert_eq!(manager.approve(id, "Mary", String::new()).unwrap(), ExpenseStatus::ManagerApproved);
+
+    let trail = manager.audit_trail(id);
+    assert_eq!(trail.len(), 4);
+    assert_eq!(trail[1].actor, "Priya");
+    assert_eq!(trail[1].from, ExpenseStatus::Submitted);
+    assert_eq!(trail[1].to, ExpenseStatus::Submitted);
+    assert!(trail[1].comment.contains("Mary"));
+}
+
+#[test]
+fn test_edit_keeps_approvals_when_amount_unchanged() {
+    let mut manager = setup();
+    let id = create_expense(&mut manager, "1500.00");
+    manager.approve(id, "Mary", String::new()).unwrap();
+
+    manager.edit_expense(id, "Sam", Some("Flight home".to_string()), Some("1500.00".to_string()), None, true).unwrap();
+    assert_eq!(manager.get_expense(id).unwrap().approvals, vec!["Mary".to_string()]);
+    assert_eq!(manager.audit_trail(id).len(), 1);
+}
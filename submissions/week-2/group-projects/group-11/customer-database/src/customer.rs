@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Customer {
+    pub id: u32,
+    pub name: String,
+    pub email: String,
+    pub phone: String,
+}
+
+impl Customer {
+    pub fn new(id: u32, name: String, email: String, phone: String) -> Self {
+        Self { id, name, email, phone }
+    }
+}
+
+/// Details for a customer that has not been given an id yet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewCustomer {
+    pub name: String,
+    pub email: String,
+    pub phone: String,
+}
+
+/// Fields to change on an existing customer; `None` keeps the current value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomerPatch {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+impl CustomerPatch {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.email.is_none() && self.phone.is_none()
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use crate::customer::Customer;
+use crate::errors::Field;
+
+/// Pairs scoring at least this (out of 100) are reported as likely duplicates.
+pub const DEFAULT_THRESHOLD: u8 = 60;
+
+/// How much each signal contributes to the score; they add up to 100. A matching name alone is
+/// not enough to be reported, since different people share names.
+const NAME_WEIGHT: usize = 50;
+const PHONE_WEIGHT: usize = 30;
+const EMAIL_WEIGHT: usize = 20;
+
+/// Two customers that look like the same person.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateMatch {
+    /// The lower id of the pair.
+    pub first: u32,
+    pub second: u32,
+    /// 0 to 100.
+    pub score: u8,
+    /// Characters to change to turn one name into the other, ignoring case and word order.
+    pub name_distance: usize,
+    pub same_phone: bool,
+    /// Email addresses share a local part once case, dots and `+tags` are ignored.
+    pub same_email_local: bool,
+}
+
+impl fmt::Display for DuplicateMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "customers {} and {} (score {}): ", self.first, self.second, self.score)?;
+        match self.name_distance {
+            0 => f.write_str("same name")?,
+            1 => f.write_str("names 1 character apart")?,
+            distance => write!(f, "names {} characters apart", distance)?,
+        }
+        if self.same_phone {
+            f.write_str(", same phone")?;
+        }
+        if self.same_email_local {
+            f.write_str(", same email name")?;
+        }
+        Ok(())
+    }
+}
+
+/// Scores every pair and returns those at or above `threshold`, best first.
+///
+/// Every pair is compared, which is fine for the few thousand records a session holds.
+pub fn find_duplicates(customers: &[&Customer], threshold: u8) -> Vec<DuplicateMatch> {
+    let mut matches = Vec::new();
+    for (index, first) in customers.iter().enumerate() {
+        for second in &customers[index + 1..] {
+            let candidate = score(first, second);
+            if candidate.score >= threshold {
+                matches.push(candidate);
+            }
+        }
+    }
+    matches.sort_by(|a, b| b.score.cmp(&a.score).then(a.first.cmp(&b.first)).then(a.second.cmp(&b.second)));
+    matches
+}
+
+pub fn score(a: &Customer, b: &Customer) -> DuplicateMatch {
+    let (first, second) = if a.id <= b.id { (a, b) } else { (b, a) };
+
+    let (left, right) = (first.name.to_lowercase(), second.name.to_lowercase());
+    let name_distance = edit_distance(&left, &right).min(edit_distance(&sorted_words(&left), &sorted_words(&right)));
+    let longest = left.chars().count().max(right.chars().count()).max(1);
+    let name_points = NAME_WEIGHT * longest.saturating_sub(name_distance) / longest;
+
+    // Phones are stored in E.164, so differently formatted numbers already compare equal
+    let same_phone = first.phone == second.phone;
+    let local = email_local_part(&first.email);
+    let same_email_local = !local.is_empty() && local == email_local_part(&second.email);
+
+    let score = name_points
+        + if same_phone { PHONE_WEIGHT } else { 0 }
+        + if same_email_local { EMAIL_WEIGHT } else { 0 };
+    DuplicateMatch {
+        first: first.id,
+        second: second.id,
+        score: score as u8,
+        name_distance,
+        same_phone,
+        same_email_local,
+    }
+}
+
+/// Levenshtein distance counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The part of an address before `@`, lowercased, without dots or a `+tag`, so
+/// `Ada.Lovelace+shop@x.com` and `adalovelace@y.org` compare equal.
+pub fn email_local_part(email: &str) -> String {
+    let local = email.split('@').next().unwrap_or_default();
+    let local = local.split('+').next().unwrap_or_default();
+    local.chars().filter(|c| *c != '.').collect::<String>().to_lowercase()
+}
+
+fn sorted_words(name: &str) -> String {
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    words.sort_unstable();
+    words.join(" ")
+}
+
+/// Which customer of a merged pair a field's value is taken from.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Source {
+    #[default]
+    Survivor,
+    Duplicate,
+}
+
+/// Field-by-field choice for a merge; by default everything is kept from the survivor.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MergePlan {
+    pub name: Source,
+    pub email: Source,
+    pub phone: Source,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSource {
+    pub field: Field,
+    /// Id of the customer the merged value came from.
+    pub from: u32,
+}
+
+/// What a merge did: the survivor keeps its id, the duplicate is soft-deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeRecord {
+    pub survivor: u32,
+    pub duplicate: u32,
+    /// One entry per field, in name, email, phone order.
+    pub sources: Vec<FieldSource>,
+}
+
+impl fmt::Display for MergeRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Customer {} merged into customer {}", self.duplicate, self.survivor)?;
+        for source in &self.sources {
+            write!(f, "\n  {} from customer {}", source.field, source.from)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Email,
+    Phone,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Field::Name => "Name",
+            Field::Email => "Email",
+            Field::Phone => "Phone",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Why one field was rejected, worded so it can be shown before asking for the field again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: Field,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: Field, message: impl Into<String>) -> Self {
+        Self { field, message: message.into() }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomerError {
+    /// Another customer is already registered with this email.
+    DuplicateEmail(String),
+    /// Every field that failed validation, in name, email, phone order.
+    Invalid(Vec<FieldError>),
+    NotFound(u32),
+    /// Restore and purge only apply to removed customers.
+    NotDeleted(u32),
+    NothingToUndo,
+    NothingToRedo,
+    MergeWithSelf(u32),
+}
+
+impl CustomerError {
+    /// The validation error for `field`, if that field was rejected.
+    pub fn field_error(&self, field: Field) -> Option<&FieldError> {
+        match self {
+            CustomerError::Invalid(errors) => errors.iter().find(|error| error.field == field),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CustomerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomerError::DuplicateEmail(email) => {
+                write!(f, "Email {} is already registered to another customer", email)
+            }
+            CustomerError::Invalid(errors) => {
+                let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                f.write_str(&messages.join("; "))
+            }
+            CustomerError::NotFound(id) => write!(f, "Customer with ID {} not found", id),
+            CustomerError::NotDeleted(id) => write!(f, "Customer with ID {} has not been removed", id),
+            CustomerError::NothingToUndo => f.write_str("Nothing to undo"),
+            CustomerError::NothingToRedo => f.write_str("Nothing to redo"),
+            CustomerError::MergeWithSelf(id) => write!(f, "Customer with ID {} cannot be merged with itself", id),
+        }
+    }
+}
+
+/// Problems that stop a whole import.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    Io(String),
+    MissingColumn(&'static str),
+    Malformed(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(reason) => write!(f, "Cannot read the file: {}", reason),
+            ImportError::MissingColumn(column) => write!(f, "The file has no '{}' column", column),
+            ImportError::Malformed(reason) => write!(f, "The file is not valid: {}", reason),
+        }
+    }
+}
+
+/// Why a single imported record was not applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowError {
+    Malformed(String),
+    Rejected(CustomerError),
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RowError::Malformed(reason) => write!(f, "malformed record: {}", reason),
+            RowError::Rejected(e) => write!(f, "{}", e),
+        }
+    }
+}
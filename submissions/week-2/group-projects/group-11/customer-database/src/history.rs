@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::customer::Customer;
+use crate::errors::Field;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    /// Soft-deleted: the record is kept and can be restored until it is purged.
+    Removed,
+    Restored,
+    Purged,
+    /// This customer survived a merge and took some fields from customer `from`.
+    Merged { from: u32 },
+    /// Soft-deleted because it was merged into customer `into`.
+    MergedInto { into: u32 },
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => f.write_str("Added"),
+            ChangeKind::Updated => f.write_str("Updated"),
+            ChangeKind::Removed => f.write_str("Removed"),
+            ChangeKind::Restored => f.write_str("Restored"),
+            ChangeKind::Purged => f.write_str("Purged"),
+            ChangeKind::Merged { from } => write!(f, "Merged with customer {}", from),
+            ChangeKind::MergedInto { into } => write!(f, "Merged into customer {}", into),
+        }
+    }
+}
+
+/// One field's value before and after a change. `before` is empty when the customer was added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: Field,
+    pub before: String,
+    pub after: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: '{}' -> '{}'", self.field, self.before, self.after)
+    }
+}
+
+/// Something that happened to one customer. Versions start at 1 when the customer is added
+/// and go up by one with every event, including undos and redos.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub customer_id: u32,
+    pub version: u32,
+    pub kind: ChangeKind,
+    /// Fields whose value changed; empty for removals, restores and purges.
+    pub changes: Vec<FieldChange>,
+}
+
+impl fmt::Display for ChangeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{} {}", self.version, self.kind)?;
+        for change in &self.changes {
+            write!(f, "\n  {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Field-level differences between two versions of a customer. Pass `None` as `before` for a
+/// customer that did not exist yet.
+pub fn diff(before: Option<&Customer>, after: &Customer) -> Vec<FieldChange> {
+    let fields = [
+        (Field::Name, before.map(|c| c.name.as_str()), &after.name),
+        (Field::Email, before.map(|c| c.email.as_str()), &after.email),
+        (Field::Phone, before.map(|c| c.phone.as_str()), &after.phone),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| *before != Some(after.as_str()))
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before: before.unwrap_or_default().to_string(),
+            after: after.clone(),
+        })
+        .collect()
+}
@@ -1,7 +1,5 @@
-pub mod lib;
-
-use crate::lib::*;
+use customer_database::run_program;
 
 fn main() {
     run_program();
-}
\ No newline at end of file
+}
@@ -0,0 +1,596 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::customer::{Customer, CustomerPatch, NewCustomer};
+use crate::dedupe::{DEFAULT_THRESHOLD, MergePlan, Source};
+use crate::errors::{CustomerError, ImportError};
+use crate::store::{CustomerStore, SearchField};
+use crate::transfer::{self, ConflictStrategy, Format};
+use crate::validation::{DEFAULT_COUNTRY_CODE, Validator};
+
+/// Environment variable holding the calling code used for phone numbers entered without one.
+pub const COUNTRY_CODE_VAR: &str = "CUSTOMER_DEFAULT_COUNTRY_CODE";
+
+#[derive(Debug)]
+pub enum MenuOption {
+    Add,
+    View,
+    ViewAll,
+    Remove,
+    Edit,
+    Search,
+    Import,
+    Export,
+    History,
+    Undo,
+    Redo,
+    Deleted,
+    Duplicates,
+    Exit,
+    NotFound,
+}
+
+pub fn get_input(prompt: &str) -> Result<String, String> {
+    print!("{}", prompt);
+    io::stdout().flush().map_err(|_| "Failed to display prompt".to_string())?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).map_err(|_| "Failed to read input".to_string())?;
+    let trimmed_input = input.trim();
+    if trimmed_input.is_empty() {
+        Err("Input cannot be empty".to_string())
+    } else {
+        Ok(trimmed_input.to_string())
+    }
+}
+
+pub fn parse_id_input(prompt: &str) -> Result<u32, String> {
+    let input = get_input(prompt)?;
+    input.parse::<u32>().map_err(|_| "ID must be a number.".to_string())
+}
+
+fn confirm(prompt: &str) -> Result<bool, String> {
+    let answer = get_input(prompt)?.to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+fn print_customer(customer: &Customer) {
+    println!(
+        "ID: {} | Name: {} | Email: {} | Phone: {}",
+        customer.id, customer.name, customer.email, customer.phone
+    );
+}
+
+/// Keeps asking until `check` accepts the input, and returns the normalized value.
+fn get_valid_input<E: std::fmt::Display>(prompt: &str, check: impl Fn(&str) -> Result<String, E>) -> String {
+    loop {
+        match get_input(prompt) {
+            Ok(input) => match check(&input) {
+                Ok(value) => return value,
+                Err(e) => println!("Error: {}. Please try again.", e),
+            },
+            Err(e) => println!("Error: {}. Please try again.", e),
+        }
+    }
+}
+
+/// Like `get_valid_input`, but blank input means "keep the current value" and gives `None`.
+fn get_optional_input<E: std::fmt::Display>(
+    prompt: &str,
+    check: impl Fn(&str) -> Result<String, E>,
+) -> Option<String> {
+    loop {
+        let input = get_input(prompt).ok()?;
+        match check(&input) {
+            Ok(value) => return Some(value),
+            Err(e) => println!("Error: {}. Please try again.", e),
+        }
+    }
+}
+
+/// Stage 1
+pub fn add_customer(store: &mut CustomerStore) -> Result<(), String> {
+    let validator = store.validator();
+    let name = get_valid_input("Enter name: ", |input| validator.name(input));
+    let email = get_valid_input("Enter email: ", |input| store.check_email(input, None));
+    let phone = get_valid_input(
+        &format!("Enter phone (without a country code, +{} is assumed): ", validator.default_country_code()),
+        |input| validator.phone(input),
+    );
+
+    let id = store.add(NewCustomer { name, email, phone }).map_err(|e| e.to_string())?;
+    println!("Customer added with ID: {}", id);
+    Ok(())
+}
+
+pub fn view_customer(store: &CustomerStore) {
+    println!("\n--- View Customer ---");
+
+    if store.is_empty() {
+        println!("No customers found.");
+        return;
+    }
+
+    match parse_id_input("Enter customer ID: ") {
+        Ok(id) => match store.get(id) {
+            Some(customer) => print_customer(customer),
+            None => println!("{}", CustomerError::NotFound(id)),
+        },
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+pub fn view_customers(store: &CustomerStore) {
+    if store.is_empty() {
+        println!("No customers found.");
+        return;
+    }
+
+    for customer in store.list() {
+        print_customer(customer);
+    }
+}
+
+/// Stage 2
+pub fn remove_customer(store: &mut CustomerStore) {
+    println!("\n--- Remove Customer ---");
+
+    if store.is_empty() {
+        println!("No customers to remove.");
+        return;
+    }
+
+    // Show all customers first
+    view_customers(store);
+
+    let id = match parse_id_input("Enter customer ID to remove: ") {
+        Ok(id) => id,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let Some(customer) = store.get(id) else {
+        println!("{}", CustomerError::NotFound(id));
+        return;
+    };
+    println!("Customer to remove:");
+    print_customer(customer);
+
+    match confirm("Are you sure you want to remove this customer? (y/n): ") {
+        Ok(true) => match store.remove(id) {
+            Ok(removed) => println!(
+                "Removed customer: {} with ID {}. It can be restored from Deleted Customers until purged.",
+                removed.name, removed.id
+            ),
+            Err(e) => println!("Error: {}", e),
+        },
+        Ok(false) => println!("Remove operation cancelled."),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+/// Stage 3
+pub fn edit_customer(store: &mut CustomerStore) {
+    println!("\n--- Edit Customer ---");
+
+    if store.is_empty() {
+        println!("No customers to edit.");
+        return;
+    }
+
+    // Show all customers first
+    view_customers(store);
+
+    let id = match parse_id_input("Enter customer ID to edit: ") {
+        Ok(id) => id,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let Some(original) = store.get(id).cloned() else {
+        println!("{}", CustomerError::NotFound(id));
+        return;
+    };
+    println!("Customer to edit:");
+    print_customer(&original);
+
+    // Blank input keeps the current value
+    let validator = store.validator();
+    let patch = CustomerPatch {
+        name: get_optional_input("Enter new name or leave blank to keep current: ", |input| validator.name(input)),
+        email: get_optional_input("Enter new email or leave blank to keep current: ", |input| {
+            store.check_email(input, Some(id))
+        }),
+        phone: get_optional_input("Enter new phone or leave blank to keep current: ", |input| {
+            validator.phone(input)
+        }),
+    };
+
+    if patch.is_empty() {
+        println!("No changes to make.");
+        return;
+    }
+
+    println!("\n--- Review Changes ---");
+    println!("Original:");
+    print_customer(&original);
+    println!("Updated:");
+    print_customer(&Customer {
+        id,
+        name: patch.name.clone().unwrap_or_else(|| original.name.clone()),
+        email: patch.email.clone().unwrap_or_else(|| original.email.clone()),
+        phone: patch.phone.clone().unwrap_or_else(|| original.phone.clone()),
+    });
+
+    match confirm("Save these changes? (y/n): ") {
+        Ok(true) => match store.update(id, patch) {
+            Ok(_) => println!("Customer Details updated successfully!"),
+            Err(e) => println!("Error: {}. Customer Details not updated.", e),
+        },
+        Ok(false) => println!("Customer Details not updated."),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+pub fn search_customers(store: &CustomerStore) {
+    println!("\n--- Search Customers ---");
+    println!("1. By ID");
+    println!("2. By name (prefix or part of the name)");
+    println!("3. By email");
+    println!("4. By phone");
+
+    let field = match get_input("Search by: ").as_deref() {
+        Ok("1") => SearchField::Id,
+        Ok("2") => SearchField::Name,
+        Ok("3") => SearchField::Email,
+        Ok("4") => SearchField::Phone,
+        Ok(_) => {
+            println!("Invalid selection.");
+            return;
+        }
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let query = match get_input("Search for: ") {
+        Ok(query) => query,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let results = store.search(field, &query);
+    if results.is_empty() {
+        println!("No customers match '{}'.", query);
+        return;
+    }
+
+    println!("{} match(es):", results.len());
+    for customer in results {
+        print_customer(customer);
+    }
+}
+
+pub fn view_history(store: &CustomerStore) {
+    println!("\n--- Customer History ---");
+
+    let id = match parse_id_input("Enter customer ID: ") {
+        Ok(id) => id,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let events = store.history(id);
+    if events.is_empty() {
+        println!("{}", CustomerError::NotFound(id));
+        return;
+    }
+    for event in events {
+        println!("{}", event);
+    }
+}
+
+pub fn undo_change(store: &mut CustomerStore) {
+    match store.undo() {
+        Ok(ids) => println!("Undid the last change to customer(s) {}", join_ids(&ids)),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+pub fn redo_change(store: &mut CustomerStore) {
+    match store.redo() {
+        Ok(ids) => println!("Redid the change to customer(s) {}", join_ids(&ids)),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+fn join_ids(ids: &[u32]) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Lists soft-deleted customers and offers to restore or purge one.
+pub fn manage_deleted(store: &mut CustomerStore) {
+    println!("\n--- Deleted Customers ---");
+
+    let deleted = store.deleted();
+    if deleted.is_empty() {
+        println!("No deleted customers.");
+        return;
+    }
+    for customer in deleted {
+        print_customer(customer);
+    }
+
+    println!("1. Restore a customer");
+    println!("2. Purge a customer permanently");
+    println!("3. Back");
+    let restore = match get_input("Choose option: ").as_deref() {
+        Ok("1") => true,
+        Ok("2") => false,
+        _ => return,
+    };
+
+    let id = match parse_id_input("Enter customer ID: ") {
+        Ok(id) => id,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    if restore {
+        match store.restore(id) {
+            Ok(customer) => println!("Restored customer: {} with ID {}", customer.name, customer.id),
+            Err(e) => println!("Error: {}", e),
+        }
+        return;
+    }
+
+    match confirm("Purging cannot be undone and clears the undo history. Continue? (y/n): ") {
+        Ok(true) => match store.purge(id) {
+            Ok(purged) => println!("Purged customer: {} with ID {}", purged.name, purged.id),
+            Err(e) => println!("Error: {}", e),
+        },
+        Ok(false) => println!("Purge cancelled."),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+/// Asks which of two differing values to keep; equal values need no question.
+fn choose_source(label: &str, survivor: &str, duplicate: &str) -> Source {
+    if survivor == duplicate {
+        return Source::Survivor;
+    }
+    loop {
+        println!("{}: 1. {}  2. {}", label, survivor, duplicate);
+        match get_input("Keep (1/2): ").as_deref() {
+            Ok("1") => return Source::Survivor,
+            Ok("2") => return Source::Duplicate,
+            _ => println!("Please enter 1 or 2."),
+        }
+    }
+}
+
+/// Lists likely duplicates and merges a chosen pair, field by field.
+pub fn find_duplicates(store: &mut CustomerStore) {
+    println!("\n--- Find Duplicates ---");
+
+    let matches = store.find_duplicates(DEFAULT_THRESHOLD);
+    if matches.is_empty() {
+        println!("No likely duplicates found.");
+        return;
+    }
+    for (index, candidate) in matches.iter().enumerate() {
+        println!("{}. {}", index + 1, candidate);
+        for id in [candidate.first, candidate.second] {
+            if let Some(customer) = store.get(id) {
+                print!("   ");
+                print_customer(customer);
+            }
+        }
+    }
+
+    let Ok(choice) = get_input("Enter a match number to merge, or leave blank to go back: ") else {
+        return;
+    };
+    let Some(candidate) = choice.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| matches.get(i)) else {
+        println!("Invalid selection.");
+        return;
+    };
+
+    let survivor = match get_input(&format!("Which ID should be kept? ({}/{}): ", candidate.first, candidate.second)) {
+        Ok(id) if id == candidate.second.to_string() => candidate.second,
+        Ok(id) if id == candidate.first.to_string() => candidate.first,
+        _ => {
+            println!("Invalid selection.");
+            return;
+        }
+    };
+    let duplicate = if survivor == candidate.first { candidate.second } else { candidate.first };
+    let (Some(kept), Some(other)) = (store.get(survivor).cloned(), store.get(duplicate).cloned()) else {
+        return;
+    };
+
+    let plan = MergePlan {
+        name: choose_source("Name", &kept.name, &other.name),
+        email: choose_source("Email", &kept.email, &other.email),
+        phone: choose_source("Phone", &kept.phone, &other.phone),
+    };
+    let pick = |source: Source, kept: &String, other: &String| match source {
+        Source::Survivor => kept.clone(),
+        Source::Duplicate => other.clone(),
+    };
+    println!("\n--- Review Merge ---");
+    print_customer(&Customer {
+        id: survivor,
+        name: pick(plan.name, &kept.name, &other.name),
+        email: pick(plan.email, &kept.email, &other.email),
+        phone: pick(plan.phone, &kept.phone, &other.phone),
+    });
+
+    match confirm(&format!("Merge customer {} into customer {}? (y/n): ", duplicate, survivor)) {
+        Ok(true) => match store.merge(survivor, duplicate, plan) {
+            Ok(record) => println!("{}", record),
+            Err(e) => println!("Error: {}", e),
+        },
+        Ok(false) => println!("Merge cancelled."),
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
+/// Uses the file extension when it names a known format, otherwise asks.
+fn choose_format(path: &Path) -> Option<Format> {
+    if let Some(format) = Format::from_path(path) {
+        return Some(format);
+    }
+    println!("1. CSV");
+    println!("2. JSON");
+    println!("3. vCard");
+    match get_input("File format: ").as_deref() {
+        Ok("1") => Some(Format::Csv),
+        Ok("2") => Some(Format::Json),
+        Ok("3") => Some(Format::VCard),
+        _ => None,
+    }
+}
+
+pub fn import_customers(store: &mut CustomerStore) -> Result<(), String> {
+    println!("\n--- Import Customers ---");
+
+    let path = get_input("Enter file path (.csv, .json or .vcf): ")?;
+    let format = choose_format(Path::new(&path)).ok_or("Unknown file format")?;
+
+    println!("When an email is already registered:");
+    println!("1. Skip the imported record");
+    println!("2. Overwrite the existing customer");
+    println!("3. Merge, keeping existing values and filling in only blank fields");
+    let strategy = match get_input("Choose strategy: ")?.as_str() {
+        "1" => ConflictStrategy::Skip,
+        "2" => ConflictStrategy::Overwrite,
+        "3" => ConflictStrategy::Merge,
+        _ => return Err("Invalid strategy".to_string()),
+    };
+
+    let input = fs::read_to_string(&path).map_err(|e| ImportError::Io(e.to_string()).to_string())?;
+    let records = transfer::parse(format, &input).map_err(|e| e.to_string())?;
+    let report = store.import(records, strategy);
+    println!("{}", report);
+    Ok(())
+}
+
+pub fn export_customers(store: &CustomerStore) -> Result<(), String> {
+    println!("\n--- Export Customers ---");
+
+    if store.is_empty() {
+        println!("No customers to export.");
+        return Ok(());
+    }
+
+    let path = get_input("Enter file path (.csv, .json or .vcf): ")?;
+    let format = choose_format(Path::new(&path)).ok_or("Unknown file format")?;
+    fs::write(&path, transfer::export(format, &store.list())).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    println!("Exported {} customer(s) to {}", store.len(), path);
+    Ok(())
+}
+
+pub fn get_menu_choice() -> Result<MenuOption, String> {
+    println!("\n--- Customer Database ---");
+    println!("1. Add Customer");
+    println!("2. View Customer");
+    println!("3. View All Customers");
+    println!("4. Remove Customer");
+    println!("5. Edit Customer");
+    println!("6. Search Customers");
+    println!("7. Import Customers");
+    println!("8. Export Customers");
+    println!("9. Customer History");
+    println!("10. Undo");
+    println!("11. Redo");
+    println!("12. Deleted Customers");
+    println!("13. Find Duplicates");
+    println!("14. Exit");
+    print!("Choose option: ");
+    io::stdout().flush().map_err(|_| "Failed to display menu".to_string())?;
+
+    let mut choice = String::new();
+    io::stdin().read_line(&mut choice).map_err(|_| "Failed to read input".to_string())?;
+    match choice.trim() {
+        "1" => Ok(MenuOption::Add),
+        "2" => Ok(MenuOption::View),
+        "3" => Ok(MenuOption::ViewAll),
+        "4" => Ok(MenuOption::Remove),
+        "5" => Ok(MenuOption::Edit),
+        "6" => Ok(MenuOption::Search),
+        "7" => Ok(MenuOption::Import),
+        "8" => Ok(MenuOption::Export),
+        "9" => Ok(MenuOption::History),
+        "10" => Ok(MenuOption::Undo),
+        "11" => Ok(MenuOption::Redo),
+        "12" => Ok(MenuOption::Deleted),
+        "13" => Ok(MenuOption::Duplicates),
+        "14" => Ok(MenuOption::Exit),
+        _ => Ok(MenuOption::NotFound),
+    }
+}
+
+pub fn run_program() {
+    println!("Welcome to Group 11's Customer Database!");
+
+    let country_code = match env::var(COUNTRY_CODE_VAR) {
+        Ok(code) => match code.trim().trim_start_matches('+').parse() {
+            Ok(code) => code,
+            Err(_) => {
+                eprintln!("Ignoring {}={}: not a calling code, using +{}", COUNTRY_CODE_VAR, code, DEFAULT_COUNTRY_CODE);
+                DEFAULT_COUNTRY_CODE
+            }
+        },
+        Err(_) => DEFAULT_COUNTRY_CODE,
+    };
+    let mut store = CustomerStore::with_validator(Validator::new(country_code));
+
+    loop {
+        match get_menu_choice() {
+            Ok(MenuOption::Add) => {
+                if let Err(e) = add_customer(&mut store) {
+                    eprintln!("Error adding customer: {}", e);
+                }
+            }
+            Ok(MenuOption::View) => view_customer(&store),
+            Ok(MenuOption::ViewAll) => view_customers(&store),
+            Ok(MenuOption::Remove) => remove_customer(&mut store),
+            Ok(MenuOption::Edit) => edit_customer(&mut store),
+            Ok(MenuOption::Search) => search_customers(&store),
+            Ok(MenuOption::Import) => {
+                if let Err(e) = import_customers(&mut store) {
+                    eprintln!("Error importing customers: {}", e);
+                }
+            }
+            Ok(MenuOption::Export) => {
+                if let Err(e) = export_customers(&store) {
+                    eprintln!("Error exporting customers: {}", e);
+                }
+            }
+            Ok(MenuOption::History) => view_history(&store),
+            Ok(MenuOption::Undo) => undo_change(&mut store),
+            Ok(MenuOption::Redo) => redo_change(&mut store),
+            Ok(MenuOption::Deleted) => manage_deleted(&mut store),
+            Ok(MenuOption::Duplicates) => find_duplicates(&mut store),
+            Ok(MenuOption::Exit) => {
+                println!("Goodbye!");
+                break;
+            }
+            _ => println!("Invalid selection. Please try again."),
+        }
+    }
+}
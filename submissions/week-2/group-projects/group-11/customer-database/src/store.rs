@@ -0,0 +1,523 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::customer::{Customer, CustomerPatch, NewCustomer};
+use crate::dedupe::{self, DuplicateMatch, FieldSource, MergePlan, MergeRecord, Source};
+use crate::errors::{CustomerError, Field, FieldError, RowError};
+use crate::history::{self, ChangeEvent, ChangeKind};
+use crate::transfer::{ConflictStrategy, ImportRecord, ImportReport, RowIssue};
+use crate::validation::Validator;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchField {
+    Id,
+    /// Case-insensitive; names starting with the query come before names merely containing it.
+    Name,
+    /// Exact, ignoring case.
+    Email,
+    /// Exact, after normalizing the query the same way stored numbers are.
+    Phone,
+}
+
+/// In-memory customer records. Every method validates its input and never touches stdin or stdout.
+///
+/// Names, emails and phone numbers are normalized by the store's `Validator` before they are
+/// saved, so lookups compare like with like.
+///
+/// Email, phone and name indexes are kept in step with every add, update and remove,
+/// so email uniqueness checks and email/phone lookups do not scan the records.
+///
+/// Every mutation is logged as a versioned `ChangeEvent` and can be undone and redone for the
+/// life of the store. Removed customers are only soft-deleted: they leave the indexes (freeing
+/// their email) but can be restored until they are purged.
+#[derive(Debug)]
+pub struct CustomerStore {
+    customers: HashMap<u32, Customer>,
+    next_id: u32,
+    by_email: HashMap<String, u32>,
+    /// Phone numbers are not unique; a household may share one.
+    by_phone: HashMap<String, BTreeSet<u32>>,
+    /// Sorted by lowercase name so prefix searches are a range scan.
+    by_name: BTreeMap<String, BTreeSet<u32>>,
+    validator: Validator,
+    deleted: HashMap<u32, Customer>,
+    history: HashMap<u32, Vec<ChangeEvent>>,
+    /// Each entry is undone or redone as a unit; an import is a single entry.
+    undo_stack: Vec<Vec<Step>>,
+    redo_stack: Vec<Vec<Step>>,
+    /// Steps collected while an import runs.
+    batch: Option<Vec<Step>>,
+}
+
+/// A reversible mutation, as stored on the undo and redo stacks.
+#[derive(Debug, Clone)]
+enum Step {
+    Added(u32),
+    Updated { before: Customer, after: Customer },
+    Removed(u32),
+    Restored(u32),
+}
+
+impl Step {
+    fn id(&self) -> u32 {
+        match self {
+            Step::Added(id) | Step::Removed(id) | Step::Restored(id) => *id,
+            Step::Updated { after, .. } => after.id,
+        }
+    }
+}
+
+impl Default for CustomerStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CustomerStore {
+    pub fn new() -> Self {
+        Self::with_validator(Validator::default())
+    }
+
+    pub fn with_validator(validator: Validator) -> Self {
+        Self {
+            customers: HashMap::new(),
+            next_id: 1,
+            by_email: HashMap::new(),
+            by_phone: HashMap::new(),
+            by_name: BTreeMap::new(),
+            validator,
+            deleted: HashMap::new(),
+            history: HashMap::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            batch: None,
+        }
+    }
+
+    pub fn validator(&self) -> &Validator {
+        &self.validator
+    }
+
+    pub fn add(&mut self, customer: NewCustomer) -> Result<u32, CustomerError> {
+        let name = self.validator.name(&customer.name);
+        let email = self.validator.email(&customer.email);
+        let phone = self.validator.phone(&customer.phone);
+        let (name, email, phone) = match (name, email, phone) {
+            (Ok(name), Ok(email), Ok(phone)) => (name, email, phone),
+            (name, email, phone) => return Err(invalid([name.err(), email.err(), phone.err()])),
+        };
+        self.check_unique_email(&email, None)?;
+
+        let id = self.next_id;
+        let customer = Customer::new(id, name, email, phone);
+        self.log(id, ChangeKind::Added, history::diff(None, &customer));
+        self.index(&customer);
+        self.customers.insert(id, customer);
+        self.next_id += 1;
+        self.record(Step::Added(id));
+        Ok(id)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Customer> {
+        self.customers.get(&id)
+    }
+
+    /// Applies every field in `patch`, or none of them if any is invalid.
+    pub fn update(&mut self, id: u32, patch: CustomerPatch) -> Result<&Customer, CustomerError> {
+        let before = self.customers.get(&id).cloned().ok_or(CustomerError::NotFound(id))?;
+
+        let name = patch.name.map(|name| self.validator.name(&name)).transpose();
+        let email = patch.email.map(|email| self.validator.email(&email)).transpose();
+        let phone = patch.phone.map(|phone| self.validator.phone(&phone)).transpose();
+        let (name, email, phone) = match (name, email, phone) {
+            (Ok(name), Ok(email), Ok(phone)) => (name, email, phone),
+            (name, email, phone) => return Err(invalid([name.err(), email.err(), phone.err()])),
+        };
+
+        let mut after = before.clone();
+        if let Some(name) = name {
+            after.name = name;
+        }
+        if let Some(email) = email {
+            after.email = email;
+        }
+        if let Some(phone) = phone {
+            after.phone = phone;
+        }
+        // Saving the values a customer already has is not a change
+        if after != before {
+            self.replace(after.clone(), ChangeKind::Updated)?;
+            self.record(Step::Updated { before, after });
+        }
+        Ok(&self.customers[&id])
+    }
+
+    /// Soft-deletes the customer and returns the record as it was removed.
+    pub fn remove(&mut self, id: u32) -> Result<Customer, CustomerError> {
+        let customer = self.soft_delete(id, ChangeKind::Removed)?;
+        self.record(Step::Removed(id));
+        Ok(customer)
+    }
+
+    /// Brings back a removed customer, unless someone else has taken their email since.
+    pub fn restore(&mut self, id: u32) -> Result<&Customer, CustomerError> {
+        self.undelete(id)?;
+        self.record(Step::Restored(id));
+        Ok(&self.customers[&id])
+    }
+
+    /// Permanently drops a removed customer. Its history is kept, but because the record is
+    /// gone the undo and redo stacks are cleared.
+    pub fn purge(&mut self, id: u32) -> Result<Customer, CustomerError> {
+        let customer = self.deleted.remove(&id).ok_or_else(|| self.not_deleted(id))?;
+        self.log(id, ChangeKind::Purged, Vec::new());
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        Ok(customer)
+    }
+
+    /// Removed customers that have not been purged, ordered by id.
+    pub fn deleted(&self) -> Vec<&Customer> {
+        let mut customers: Vec<&Customer> = self.deleted.values().collect();
+        customers.sort_by_key(|customer| customer.id);
+        customers
+    }
+
+    /// Every event for this customer, oldest first. Empty for an id that was never used.
+    pub fn history(&self, id: u32) -> &[ChangeEvent] {
+        self.history.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Reverts the latest change that has not been undone yet, and returns the ids it touched.
+    /// Undoing an add soft-deletes the customer, so a redo can bring it back under the same id.
+    pub fn undo(&mut self) -> Result<Vec<u32>, CustomerError> {
+        let steps = self.undo_stack.pop().ok_or(CustomerError::NothingToUndo)?;
+        match self.replay(&steps, true) {
+            Ok(ids) => {
+                self.redo_stack.push(steps);
+                Ok(ids)
+            }
+            Err(e) => {
+                self.undo_stack.push(steps);
+                Err(e)
+            }
+        }
+    }
+
+    /// Re-applies the latest undone change. Any new change clears what can be redone.
+    pub fn redo(&mut self) -> Result<Vec<u32>, CustomerError> {
+        let steps = self.redo_stack.pop().ok_or(CustomerError::NothingToRedo)?;
+        match self.replay(&steps, false) {
+            Ok(ids) => {
+                self.undo_stack.push(steps);
+                Ok(ids)
+            }
+            Err(e) => {
+                self.redo_stack.push(steps);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn find_by_email(&self, email: &str) -> Option<&Customer> {
+        self.by_email.get(&email.trim().to_lowercase()).and_then(|id| self.customers.get(id))
+    }
+
+    /// Customers with this number. The query is normalized first, so `0803 000 0001` finds
+    /// `+2348030000001` under the default country code.
+    pub fn find_by_phone(&self, phone: &str) -> Vec<&Customer> {
+        let Ok(phone) = self.validator.phone(phone) else {
+            return Vec::new();
+        };
+        self.by_phone.get(&phone).map(|ids| self.resolve(ids.iter())).unwrap_or_default()
+    }
+
+    /// Customers whose name starts with `query`, then those whose name contains it elsewhere,
+    /// each group ordered by name. Case is ignored; an empty query matches nobody.
+    pub fn search_name(&self, query: &str) -> Vec<&Customer> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let prefix = self
+            .by_name
+            .range(query.clone()..)
+            .take_while(|(name, _)| name.starts_with(&query))
+            .flat_map(|(_, ids)| ids);
+        let contains = self
+            .by_name
+            .iter()
+            .filter(|(name, _)| !name.starts_with(&query) && name.contains(&query))
+            .flat_map(|(_, ids)| ids);
+        self.resolve(prefix.chain(contains))
+    }
+
+    pub fn search(&self, field: SearchField, query: &str) -> Vec<&Customer> {
+        match field {
+            SearchField::Id => query.trim().parse().ok().and_then(|id| self.get(id)).into_iter().collect(),
+            SearchField::Name => self.search_name(query),
+            SearchField::Email => self.find_by_email(query).into_iter().collect(),
+            SearchField::Phone => self.find_by_phone(query),
+        }
+    }
+
+    /// Applies parsed records in order. New emails are added with the same validation as `add`;
+    /// emails that are already registered are handled by `strategy`.
+    pub fn import(
+        &mut self,
+        records: Vec<Result<ImportRecord, RowIssue>>,
+        strategy: ConflictStrategy,
+    ) -> ImportReport {
+        let mut report = ImportReport::default();
+        self.batch = Some(Vec::new());
+
+        for record in records {
+            let ImportRecord { position, customer } = match record {
+                Ok(record) => record,
+                Err(issue) => {
+                    report.errors.push(issue);
+                    continue;
+                }
+            };
+
+            let existing = self.validator.email(&customer.email).ok().and_then(|email| self.by_email.get(&email).copied());
+            let patch = match (existing, strategy) {
+                (None, _) => {
+                    match self.add(customer) {
+                        Ok(id) => report.added.push(id),
+                        Err(e) => report.errors.push(RowIssue { position, error: RowError::Rejected(e) }),
+                    }
+                    continue;
+                }
+                (Some(_), ConflictStrategy::Skip) => None,
+                (Some(_), ConflictStrategy::Overwrite) => {
+                    Some(CustomerPatch { name: Some(customer.name), email: None, phone: Some(customer.phone) })
+                }
+                (Some(id), ConflictStrategy::Merge) => {
+                    let stored = &self.customers[&id];
+                    let fill = |stored: &str, value: String| {
+                        (stored.trim().is_empty() && !value.trim().is_empty()).then_some(value)
+                    };
+                    Some(CustomerPatch {
+                        name: fill(&stored.name, customer.name),
+                        email: None,
+                        phone: fill(&stored.phone, customer.phone),
+                    })
+                    .filter(|patch| !patch.is_empty())
+                }
+            };
+
+            match (existing, patch) {
+                (Some(id), Some(patch)) => match self.update(id, patch) {
+                    Ok(_) => report.updated.push(id),
+                    Err(e) => report.errors.push(RowIssue { position, error: RowError::Rejected(e) }),
+                },
+                _ => report.skipped.push(position),
+            }
+        }
+
+        if let Some(steps) = self.batch.take()
+            && !steps.is_empty()
+        {
+            self.undo_stack.push(steps);
+        }
+        report
+    }
+
+    /// Likely duplicate pairs among active customers, best match first.
+    pub fn find_duplicates(&self, threshold: u8) -> Vec<DuplicateMatch> {
+        dedupe::find_duplicates(&self.list(), threshold)
+    }
+
+    /// Folds `duplicate` into `survivor`. The survivor keeps its id and takes the fields `plan`
+    /// picks from the duplicate; the duplicate is soft-deleted. Both history entries name the
+    /// other customer, and the merge is undone as one change.
+    pub fn merge(&mut self, survivor: u32, duplicate: u32, plan: MergePlan) -> Result<MergeRecord, CustomerError> {
+        if survivor == duplicate {
+            return Err(CustomerError::MergeWithSelf(survivor));
+        }
+        let kept = self.customers.get(&survivor).cloned().ok_or(CustomerError::NotFound(survivor))?;
+        let other = self.customers.get(&duplicate).cloned().ok_or(CustomerError::NotFound(duplicate))?;
+
+        let mut sources = Vec::new();
+        let mut pick = |field: Field, source: Source, kept: &str, other: &str| {
+            let (value, from) = match source {
+                Source::Survivor => (kept, survivor),
+                Source::Duplicate => (other, duplicate),
+            };
+            sources.push(FieldSource { field, from });
+            value.to_string()
+        };
+        let merged = Customer::new(
+            survivor,
+            pick(Field::Name, plan.name, &kept.name, &other.name),
+            pick(Field::Email, plan.email, &kept.email, &other.email),
+            pick(Field::Phone, plan.phone, &kept.phone, &other.phone),
+        );
+
+        // The duplicate goes first so the survivor can take over its email
+        self.soft_delete(duplicate, ChangeKind::MergedInto { into: survivor })?;
+        self.replace(merged.clone(), ChangeKind::Merged { from: duplicate })?;
+        self.record_group(vec![Step::Removed(duplicate), Step::Updated { before: kept, after: merged }]);
+        Ok(MergeRecord { survivor, duplicate, sources })
+    }
+
+    /// All customers, ordered by id.
+    pub fn list(&self) -> Vec<&Customer> {
+        let mut customers: Vec<&Customer> = self.customers.values().collect();
+        customers.sort_by_key(|customer| customer.id);
+        customers
+    }
+
+    pub fn len(&self) -> usize {
+        self.customers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.customers.is_empty()
+    }
+
+    /// Validates `email` and checks that no customer other than `except` uses it.
+    /// Returns the normalized address.
+    pub fn check_email(&self, email: &str, except: Option<u32>) -> Result<String, CustomerError> {
+        let email = self.validator.email(email).map_err(|e| CustomerError::Invalid(vec![e]))?;
+        self.check_unique_email(&email, except)?;
+        Ok(email)
+    }
+
+    fn check_unique_email(&self, email: &str, except: Option<u32>) -> Result<(), CustomerError> {
+        match self.by_email.get(email) {
+            Some(&owner) if Some(owner) != except => Err(CustomerError::DuplicateEmail(email.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    fn record(&mut self, step: Step) {
+        self.record_group(vec![step]);
+    }
+
+    /// Records steps that are undone together.
+    fn record_group(&mut self, steps: Vec<Step>) {
+        self.redo_stack.clear();
+        match &mut self.batch {
+            Some(batch) => batch.extend(steps),
+            None => self.undo_stack.push(steps),
+        }
+    }
+
+    fn log(&mut self, id: u32, kind: ChangeKind, changes: Vec<history::FieldChange>) {
+        let events = self.history.entry(id).or_default();
+        let version = events.len() as u32 + 1;
+        events.push(ChangeEvent { customer_id: id, version, kind, changes });
+    }
+
+    /// Applies a group of steps, backwards when undoing. If one fails, the steps already
+    /// applied are reversed again so the group takes effect all or nothing.
+    fn replay(&mut self, steps: &[Step], undo: bool) -> Result<Vec<u32>, CustomerError> {
+        let ordered: Vec<&Step> = if undo { steps.iter().rev().collect() } else { steps.iter().collect() };
+        for (done, step) in ordered.iter().enumerate() {
+            if let Err(e) = self.apply(step, undo) {
+                for step in ordered[..done].iter().rev() {
+                    let _ = self.apply(step, !undo);
+                }
+                return Err(e);
+            }
+        }
+
+        let mut ids: Vec<u32> = steps.iter().map(Step::id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    fn apply(&mut self, step: &Step, undo: bool) -> Result<(), CustomerError> {
+        match (step, undo) {
+            (Step::Added(id) | Step::Restored(id), true) | (Step::Removed(id), false) => {
+                self.soft_delete(*id, ChangeKind::Removed).map(|_| ())
+            }
+            (Step::Removed(id), true) | (Step::Added(id) | Step::Restored(id), false) => self.undelete(*id),
+            (Step::Updated { before, .. }, true) => self.replace(before.clone(), ChangeKind::Updated),
+            (Step::Updated { after, .. }, false) => self.replace(after.clone(), ChangeKind::Updated),
+        }
+    }
+
+    fn soft_delete(&mut self, id: u32, kind: ChangeKind) -> Result<Customer, CustomerError> {
+        let customer = self.customers.remove(&id).ok_or(CustomerError::NotFound(id))?;
+        self.unindex(&customer);
+        self.deleted.insert(id, customer.clone());
+        self.log(id, kind, Vec::new());
+        Ok(customer)
+    }
+
+    fn undelete(&mut self, id: u32) -> Result<(), CustomerError> {
+        let customer = self.deleted.get(&id).ok_or_else(|| self.not_deleted(id))?;
+        self.check_unique_email(&customer.email, Some(id))?;
+
+        let customer = self.deleted.remove(&id).ok_or(CustomerError::NotDeleted(id))?;
+        self.index(&customer);
+        self.customers.insert(id, customer);
+        self.log(id, ChangeKind::Restored, Vec::new());
+        Ok(())
+    }
+
+    /// Swaps in a new version of an active customer, re-indexing it and logging the diff.
+    fn replace(&mut self, customer: Customer, kind: ChangeKind) -> Result<(), CustomerError> {
+        let id = customer.id;
+        if !self.customers.contains_key(&id) {
+            return Err(CustomerError::NotFound(id));
+        }
+        self.check_unique_email(&customer.email, Some(id))?;
+
+        let before = self.customers.remove(&id).ok_or(CustomerError::NotFound(id))?;
+        self.unindex(&before);
+        self.log(id, kind, history::diff(Some(&before), &customer));
+        self.index(&customer);
+        self.customers.insert(id, customer);
+        Ok(())
+    }
+
+    fn not_deleted(&self, id: u32) -> CustomerError {
+        if self.customers.contains_key(&id) { CustomerError::NotDeleted(id) } else { CustomerError::NotFound(id) }
+    }
+
+    fn index(&mut self, customer: &Customer) {
+        self.by_email.insert(customer.email.clone(), customer.id);
+        self.by_phone.entry(customer.phone.clone()).or_default().insert(customer.id);
+        self.by_name.entry(customer.name.to_lowercase()).or_default().insert(customer.id);
+    }
+
+    fn unindex(&mut self, customer: &Customer) {
+        self.by_email.remove(&customer.email);
+
+        if let Some(ids) = self.by_phone.get_mut(&customer.phone) {
+            ids.remove(&customer.id);
+            if ids.is_empty() {
+                self.by_phone.remove(&customer.phone);
+            }
+        }
+
+        let name = customer.name.to_lowercase();
+        if let Some(ids) = self.by_name.get_mut(&name) {
+            ids.remove(&customer.id);
+            if ids.is_empty() {
+                self.by_name.remove(&name);
+            }
+        }
+    }
+
+    fn resolve<'a>(&self, ids: impl Iterator<Item = &'a u32>) -> Vec<&Customer> {
+        ids.filter_map(|id| self.customers.get(id)).collect()
+    }
+}
+
+fn invalid(errors: [Option<FieldError>; 3]) -> CustomerError {
+    CustomerError::Invalid(errors.into_iter().flatten().collect())
+}
@@ -0,0 +1,340 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::customer::{Customer, NewCustomer};
+use crate::errors::{ImportError, RowError};
+
+/// Column names, in the same title-case style as the live-session `students.csv`.
+pub const CSV_HEADERS: [&str; 4] = ["ID", "Name", "Email", "Phone"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    /// vCard 3.0 (RFC 2426).
+    VCard,
+}
+
+impl Format {
+    /// Guesses the format from a `.csv`, `.json`, `.vcf` or `.vcard` extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "vcf" | "vcard" => Some(Format::VCard),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with an imported record whose email already belongs to a customer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictStrategy {
+    /// Keep the existing customer untouched.
+    Skip,
+    /// Replace the existing name and phone with the imported ones.
+    Overwrite,
+    /// Keep every existing value and take imported values only for fields the existing customer has
+    /// blank. Records that would fill nothing in are reported as skipped.
+    Merge,
+}
+
+/// One record read from an import file, before validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRecord {
+    /// Line the record starts on for CSV and vCard, or its 1-based position in a JSON array.
+    pub position: u64,
+    pub customer: NewCustomer,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowIssue {
+    pub position: u64,
+    pub error: RowError,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: Vec<u32>,
+    /// Existing customers changed by the overwrite or merge strategy.
+    pub updated: Vec<u32>,
+    /// Positions of records skipped because their email was already registered.
+    pub skipped: Vec<u64>,
+    pub errors: Vec<RowIssue>,
+}
+
+impl ImportReport {
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "Added {}, updated {}, skipped {} existing, {} rejected",
+            self.added.len(),
+            self.updated.len(),
+            self.skipped.len(),
+            self.errors.len()
+        )];
+        lines.extend(self.skipped.iter().map(|position| format!("record {}: email already registered, skipped", position)));
+        lines.extend(self.errors.iter().map(|issue| format!("record {}: {}", issue.position, issue.error)));
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.summary())
+    }
+}
+
+/// Reads every record in `input`. Records that cannot be read are returned as issues so the
+/// rest of the file can still be imported.
+pub fn parse(format: Format, input: &str) -> Result<Vec<Result<ImportRecord, RowIssue>>, ImportError> {
+    match format {
+        Format::Csv => parse_csv(input),
+        Format::Json => parse_json(input),
+        Format::VCard => Ok(parse_vcards(input)),
+    }
+}
+
+/// Customer ids are included for reference; importing assigns fresh ones.
+pub fn export(format: Format, customers: &[&Customer]) -> String {
+    match format {
+        Format::Csv => export_csv(customers),
+        Format::Json => serde_json::to_string_pretty(customers).expect("customers always serialize"),
+        Format::VCard => customers.iter().map(|customer| vcard(customer)).collect(),
+    }
+}
+
+#[derive(Deserialize)]
+struct CsvRow {
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Email", default)]
+    email: String,
+    #[serde(rename = "Phone", default)]
+    phone: String,
+}
+
+fn parse_csv(input: &str) -> Result<Vec<Result<ImportRecord, RowIssue>>, ImportError> {
+    let mut reader = csv::ReaderBuilder::new().has_headers(true).trim(csv::Trim::All).from_reader(input.as_bytes());
+
+    let headers = reader.headers().map_err(|e| ImportError::Malformed(e.to_string()))?.clone();
+    for column in ["Name", "Email"] {
+        if !headers.iter().any(|header| header == column) {
+            return Err(ImportError::MissingColumn(column));
+        }
+    }
+
+    let mut records = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // The header is line 1
+        let fallback = index as u64 + 2;
+        let row = record.and_then(|record| {
+            let position = record.position().map(|p| p.line()).unwrap_or(fallback);
+            record.deserialize::<CsvRow>(Some(&headers)).map(|row| (position, row))
+        });
+        records.push(match row {
+            Ok((position, row)) => Ok(ImportRecord {
+                position,
+                customer: NewCustomer { name: row.name, email: row.email, phone: row.phone },
+            }),
+            Err(e) => Err(RowIssue {
+                position: e.position().map(|p| p.line()).unwrap_or(fallback),
+                error: RowError::Malformed(e.to_string()),
+            }),
+        });
+    }
+    Ok(records)
+}
+
+fn export_csv(customers: &[&Customer]) -> String {
+    let mut writer = csv::WriterBuilder::new().has_headers(true).from_writer(Vec::new());
+    writer.write_record(CSV_HEADERS).expect("writing to memory cannot fail");
+    for customer in customers {
+        writer
+            .write_record([&customer.id.to_string(), &customer.name, &customer.email, &customer.phone])
+            .expect("writing to memory cannot fail");
+    }
+    let bytes = writer.into_inner().expect("writing to memory cannot fail");
+    String::from_utf8(bytes).expect("records are valid UTF-8")
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonCustomer {
+    name: String,
+    email: String,
+    phone: String,
+}
+
+fn parse_json(input: &str) -> Result<Vec<Result<ImportRecord, RowIssue>>, ImportError> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(input).map_err(|e| ImportError::Malformed(e.to_string()))?;
+
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let position = index as u64 + 1;
+            match serde_json::from_value::<JsonCustomer>(value) {
+                Ok(c) => Ok(ImportRecord { position, customer: NewCustomer { name: c.name, email: c.email, phone: c.phone } }),
+                Err(e) => Err(RowIssue { position, error: RowError::Malformed(e.to_string()) }),
+            }
+        })
+        .collect())
+}
+
+/// Longest line allowed by RFC 2426 before folding, in octets.
+const VCARD_LINE_LIMIT: usize = 75;
+
+fn vcard(customer: &Customer) -> String {
+    let (given, family) = match customer.name.rsplit_once(' ') {
+        Some((given, family)) => (given, family),
+        None => (customer.name.as_str(), ""),
+    };
+    let lines = [
+        "BEGIN:VCARD".to_string(),
+        "VERSION:3.0".to_string(),
+        format!("FN:{}", escape(&customer.name)),
+        format!("N:{};{};;;", escape(family), escape(given)),
+        format!("EMAIL;TYPE=INTERNET:{}", escape(&customer.email)),
+        format!("TEL;TYPE=CELL:{}", escape(&customer.phone)),
+        "END:VCARD".to_string(),
+    ];
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace(';', "\\;").replace('\n', "\\n")
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Splits a structured value on `;`, leaving escaped `\\;` alone, and unescapes each part.
+fn split_components(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => parts.push(unescape(&std::mem::take(&mut current))),
+            _ => current.push(c),
+        }
+    }
+    parts.push(unescape(&current));
+    parts
+}
+
+/// Splits a content line into CRLF-terminated chunks, continuation lines starting with a space.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > VCARD_LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Reads every `BEGIN:VCARD` .. `END:VCARD` block. `FN` gives the name (falling back to `N`),
+/// and the first `EMAIL` and `TEL` are used. Other properties are ignored.
+fn parse_vcards(input: &str) -> Vec<Result<ImportRecord, RowIssue>> {
+    // Unfold continuation lines, remembering where each logical line started
+    let mut lines: Vec<(u64, String)> = Vec::new();
+    for (index, raw) in input.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, line))) => line.push_str(rest),
+            _ => lines.push((index as u64 + 1, raw.to_string())),
+        }
+    }
+
+    let mut records = Vec::new();
+    let mut card: Option<(u64, VCardFields)> = None;
+    for (line_no, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            if let Some((start, _)) = card.take() {
+                records.push(Err(RowIssue {
+                    position: start,
+                    error: RowError::Malformed(format!("line {} has no ':'", line_no)),
+                }));
+            }
+            continue;
+        };
+        // Property names may carry a group ("item1.EMAIL") and parameters ("EMAIL;TYPE=WORK")
+        let name = key.split(';').next().unwrap_or_default();
+        let name = name.rsplit('.').next().unwrap_or_default().to_uppercase();
+
+        match (name.as_str(), &mut card) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                if let Some((start, _)) = card.replace((line_no, VCardFields::default())) {
+                    records.push(Err(RowIssue { position: start, error: RowError::Malformed("missing END:VCARD".to_string()) }));
+                }
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VCARD") => {
+                let (start, fields) = card.take().expect("matched Some above");
+                records.push(Ok(ImportRecord { position: start, customer: fields.into_customer() }));
+            }
+            ("FN", Some((_, fields))) => fields.full_name = fields.full_name.take().or(Some(unescape(value))),
+            ("N", Some((_, fields))) => fields.structured_name = fields.structured_name.take().or(Some(value.to_string())),
+            ("EMAIL", Some((_, fields))) => fields.email = fields.email.take().or(Some(unescape(value))),
+            ("TEL", Some((_, fields))) => fields.phone = fields.phone.take().or(Some(unescape(value))),
+            _ => {}
+        }
+    }
+
+    if let Some((start, _)) = card {
+        records.push(Err(RowIssue { position: start, error: RowError::Malformed("missing END:VCARD".to_string()) }));
+    }
+    records
+}
+
+#[derive(Default)]
+struct VCardFields {
+    full_name: Option<String>,
+    /// Raw `N` value: family;given;additional;prefixes;suffixes.
+    structured_name: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+}
+
+impl VCardFields {
+    fn into_customer(self) -> NewCustomer {
+        let name = self.full_name.unwrap_or_else(|| {
+            let parts = split_components(&self.structured_name.unwrap_or_default());
+            let family = parts.first().map(String::as_str).unwrap_or_default();
+            let given = parts.get(1).map(String::as_str).unwrap_or_default();
+            format!("{} {}", given, family).trim().to_string()
+        });
+        NewCustomer { name, email: self.email.unwrap_or_default(), phone: self.phone.unwrap_or_default() }
+    }
+}
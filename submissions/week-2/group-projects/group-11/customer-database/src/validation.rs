@@ -0,0 +1,163 @@
+use unicode_normalization::UnicodeNormalization;
+
+use crate::errors::{Field, FieldError};
+
+/// Country calling code assumed for numbers entered without one (Nigeria).
+pub const DEFAULT_COUNTRY_CODE: u16 = 234;
+
+pub const MAX_NAME_CHARS: usize = 100;
+const MAX_EMAIL_LEN: usize = 254;
+const MAX_LOCAL_PART_LEN: usize = 64;
+const MAX_DOMAIN_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+/// E.164 allows at most 15 digits including the country code; shorter than 8 is not a real subscriber number.
+const MIN_PHONE_DIGITS: usize = 8;
+const MAX_PHONE_DIGITS: usize = 15;
+
+/// Characters allowed in an unquoted email local part besides letters, digits and dots (RFC 5322 `atext`).
+const LOCAL_PART_SYMBOLS: &str = "!#$%&'*+/=?^_`{|}~-";
+
+/// Checks and normalizes contact details. Each method returns the value to store, or
+/// an error for that one field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validator {
+    default_country_code: u16,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new(DEFAULT_COUNTRY_CODE)
+    }
+}
+
+impl Validator {
+    /// `default_country_code` is the calling code without the `+`, e.g. 44 for the UK.
+    pub fn new(default_country_code: u16) -> Self {
+        Self { default_country_code }
+    }
+
+    pub fn default_country_code(&self) -> u16 {
+        self.default_country_code
+    }
+
+    /// Trims, NFC-normalizes and collapses runs of whitespace to one space.
+    pub fn name(&self, input: &str) -> Result<String, FieldError> {
+        let normalized: String = input.nfc().collect();
+        let name = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if name.is_empty() {
+            return Err(FieldError::new(Field::Name, "cannot be empty"));
+        }
+        if name.chars().any(char::is_control) {
+            return Err(FieldError::new(Field::Name, "cannot contain control characters"));
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(FieldError::new(Field::Name, format!("must be at most {} characters", MAX_NAME_CHARS)));
+        }
+        Ok(name)
+    }
+
+    /// Checks `local@domain` syntax in the spirit of RFC 5321/5322 (quoted local parts and IP
+    /// literals are not accepted) and lowercases the result.
+    pub fn email(&self, input: &str) -> Result<String, FieldError> {
+        let email = input.trim().to_lowercase();
+        let invalid = |message: &str| Err(FieldError::new(Field::Email, message));
+
+        if email.is_empty() {
+            return invalid("cannot be empty");
+        }
+        if email.len() > MAX_EMAIL_LEN {
+            return Err(FieldError::new(Field::Email, format!("must be at most {} characters", MAX_EMAIL_LEN)));
+        }
+        let Some((local, domain)) = email.split_once('@') else {
+            return invalid("must contain '@'");
+        };
+        if domain.contains('@') {
+            return invalid("must contain only one '@'");
+        }
+
+        if local.is_empty() || local.len() > MAX_LOCAL_PART_LEN {
+            return Err(FieldError::new(
+                Field::Email,
+                format!("the part before '@' must be 1 to {} characters", MAX_LOCAL_PART_LEN),
+            ));
+        }
+        if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+            return invalid("dots cannot start or end the part before '@' or appear twice in a row");
+        }
+        if let Some(c) = local.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '.' || LOCAL_PART_SYMBOLS.contains(*c)))
+        {
+            return Err(FieldError::new(Field::Email, format!("'{}' is not allowed before '@'", c)));
+        }
+
+        if domain.is_empty() || domain.len() > MAX_DOMAIN_LEN {
+            return Err(FieldError::new(
+                Field::Email,
+                format!("the domain must be 1 to {} characters", MAX_DOMAIN_LEN),
+            ));
+        }
+        let labels: Vec<&str> = domain.split('.').collect();
+        if labels.len() < 2 {
+            return invalid("the domain needs a dot, e.g. example.com");
+        }
+        for label in &labels {
+            if label.is_empty() || label.len() > MAX_LABEL_LEN {
+                return invalid("each part of the domain must be 1 to 63 characters");
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return invalid("domain parts cannot start or end with '-'");
+            }
+            if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return invalid("the domain can only contain letters, digits, '-' and '.'");
+            }
+        }
+        if labels.last().is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit())) {
+            return invalid("the domain must end in a name, not a number");
+        }
+
+        Ok(email)
+    }
+
+    /// Normalizes to E.164 (`+` and up to 15 digits). Spaces, dashes, dots and brackets are
+    /// ignored; `00` is read as `+`, and numbers without a country code get the default one,
+    /// dropping a leading trunk `0`.
+    pub fn phone(&self, input: &str) -> Result<String, FieldError> {
+        let compact: String = input.chars().filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')')).collect();
+        if compact.is_empty() {
+            return Err(FieldError::new(Field::Phone, "cannot be empty"));
+        }
+
+        let (has_plus, digits) = match compact.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, compact.as_str()),
+        };
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(FieldError::new(
+                Field::Phone,
+                "can only contain digits, spaces, dashes, dots, brackets and a leading '+'",
+            ));
+        }
+
+        let international = if has_plus {
+            digits.to_string()
+        } else if let Some(rest) = digits.strip_prefix("00") {
+            rest.to_string()
+        } else {
+            format!("{}{}", self.default_country_code, digits.strip_prefix('0').unwrap_or(digits))
+        };
+
+        if international.starts_with('0') {
+            return Err(FieldError::new(Field::Phone, "country codes cannot start with 0"));
+        }
+        if !(MIN_PHONE_DIGITS..=MAX_PHONE_DIGITS).contains(&international.len()) {
+            return Err(FieldError::new(
+                Field::Phone,
+                format!(
+                    "must have {} to {} digits including the country code",
+                    MIN_PHONE_DIGITS, MAX_PHONE_DIGITS
+                ),
+            ));
+        }
+        Ok(format!("+{}", international))
+    }
+}
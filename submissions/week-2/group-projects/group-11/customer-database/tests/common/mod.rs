@@ -0,0 +1,26 @@
+// Each test file compiles this module separately and uses only part of it
+#![allow(dead_code)]
+
+use customer_database::customer::NewCustomer;
+use customer_database::store::CustomerStore;
+
+pub fn new_customer(name: &str, email: &str, phone: &str) -> NewCustomer {
+    NewCustomer { name: name.to_string(), email: email.to_string(), phone: phone.to_string() }
+}
+
+/// A store holding the given customers, added in order so ids start at 1.
+pub fn store_with(customers: &[(&str, &str, &str)]) -> CustomerStore {
+    let mut store = CustomerStore::new();
+    for (name, email, phone) in customers {
+        store.add(new_customer(name, email, phone)).unwrap();
+    }
+    store
+}
+
+/// Ada (id 1) and Bola (id 2).
+pub fn setup() -> CustomerStore {
+    store_with(&[
+        ("Ada Lovelace", "ada@example.com", "08030000001"),
+        ("Bola Tinubu", "bola@example.com", "08030000002"),
+    ])
+}
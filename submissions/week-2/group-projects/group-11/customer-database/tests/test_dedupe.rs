@@ -0,0 +1,125 @@
+use customer_database::customer::Customer;
+use customer_database::dedupe::{self, DEFAULT_THRESHOLD, FieldSource, MergePlan, Source};
+use customer_database::errors::{CustomerError, Field};
+use customer_database::history::ChangeKind;
+use customer_database::store::CustomerStore;
+
+mod common;
+use common::new_customer;
+
+fn customer(id: u32, name: &str, email: &str, phone: &str) -> Customer {
+    Customer::new(id, name.to_string(), email.to_string(), phone.to_string())
+}
+
+fn setup() -> CustomerStore {
+    common::store_with(&[
+        ("Ada Lovelace", "ada.lovelace@example.com", "08030000001"),
+        ("Bola Tinubu", "bola@example.com", "08030000002"),
+        // Same person as 1, entered with a typo and a differently formatted number
+        ("Ada Lovelce", "adalovelace+shop@mail.org", "+234 803 000 0001"),
+    ])
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(dedupe::edit_distance("", ""), 0);
+    assert_eq!(dedupe::edit_distance("kitten", "sitting"), 3);
+    assert_eq!(dedupe::edit_distance("ada", ""), 3);
+    assert_eq!(dedupe::edit_distance("chloé", "chloe"), 1);
+}
+
+#[test]
+fn test_email_local_part() {
+    assert_eq!(dedupe::email_local_part("Ada.Lovelace+shop@x.com"), "adalovelace");
+    assert_eq!(dedupe::email_local_part("ada@x.com"), "ada");
+}
+
+#[test]
+fn test_score_signals() {
+    let ada = customer(1, "Ada Lovelace", "ada@example.com", "+2348030000001");
+
+    let typo = dedupe::score(&ada, &customer(2, "Ada Lovelce", "a.d.a@mail.org", "+2348030000001"));
+    assert_eq!(typo.name_distance, 1);
+    assert!(typo.same_phone && typo.same_email_local);
+    assert_eq!(typo.score, 95);
+
+    // Word order does not count against the name
+    let swapped = dedupe::score(&customer(3, "Lovelace Ada", "x@example.com", "+2348030000009"), &ada);
+    assert_eq!((swapped.first, swapped.second), (1, 3));
+    assert_eq!(swapped.name_distance, 0);
+    assert_eq!(swapped.score, 50);
+
+    let stranger = dedupe::score(&ada, &customer(4, "Bola Tinubu", "bola@example.com", "+2348030000002"));
+    assert!(stranger.score < 20);
+}
+
+#[test]
+fn test_find_duplicates() {
+    let mut store = setup();
+    // Same name only: not enough on its own
+    store.add(new_customer("Bola Tinubu", "b.tinubu@example.com", "08030000005")).unwrap();
+
+    let matches = store.find_duplicates(DEFAULT_THRESHOLD);
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].first, matches[0].second), (1, 3));
+    assert_eq!(matches[0].to_string(), "customers 1 and 3 (score 95): names 1 character apart, same phone, same email name");
+
+    assert_eq!(store.find_duplicates(50).len(), 2);
+    assert_eq!(store.find_duplicates(50)[1].to_string(), "customers 2 and 4 (score 50): same name");
+}
+
+#[test]
+fn test_merge_keeps_survivor_id() {
+    let mut store = setup();
+    let plan = MergePlan { email: Source::Duplicate, ..MergePlan::default() };
+    let record = store.merge(1, 3, plan).unwrap();
+
+    assert_eq!(
+        record.sources,
+        vec![
+            FieldSource { field: Field::Name, from: 1 },
+            FieldSource { field: Field::Email, from: 3 },
+            FieldSource { field: Field::Phone, from: 1 },
+        ]
+    );
+    assert_eq!(record.to_string(), "Customer 3 merged into customer 1\n  Name from customer 1\n  Email from customer 3\n  Phone from customer 1");
+
+    let merged = store.get(1).unwrap();
+    assert_eq!((merged.name.as_str(), merged.email.as_str()), ("Ada Lovelace", "adalovelace+shop@mail.org"));
+    assert!(store.get(3).is_none());
+    assert_eq!(store.find_by_email("adalovelace+shop@mail.org").unwrap().id, 1);
+    assert!(store.find_by_email("ada.lovelace@example.com").is_none());
+    assert!(store.find_duplicates(DEFAULT_THRESHOLD).is_empty());
+
+    // Provenance is in both customers' history
+    let survivor_event = store.history(1).last().unwrap();
+    assert_eq!(survivor_event.kind, ChangeKind::Merged { from: 3 });
+    assert_eq!(survivor_event.changes.len(), 1);
+    assert_eq!(survivor_event.changes[0].field, Field::Email);
+    assert_eq!(store.history(3).last().unwrap().kind, ChangeKind::MergedInto { into: 1 });
+}
+
+#[test]
+fn test_merge_undoes_as_one_change() {
+    let mut store = setup();
+    store.merge(1, 3, MergePlan { email: Source::Duplicate, ..MergePlan::default() }).unwrap();
+
+    assert_eq!(store.undo().unwrap(), vec![1, 3]);
+    assert_eq!(store.get(1).unwrap().email, "ada.lovelace@example.com");
+    assert_eq!(store.get(3).unwrap().email, "adalovelace+shop@mail.org");
+
+    store.redo().unwrap();
+    assert!(store.get(3).is_none());
+    assert_eq!(store.get(1).unwrap().email, "adalovelace+shop@mail.org");
+}
+
+#[test]
+fn test_merge_errors() {
+    let mut store = setup();
+    assert_eq!(store.merge(1, 1, MergePlan::default()), Err(CustomerError::MergeWithSelf(1)));
+    assert_eq!(store.merge(1, 99, MergePlan::default()), Err(CustomerError::NotFound(99)));
+
+    store.remove(3).unwrap();
+    assert_eq!(store.merge(1, 3, MergePlan::default()), Err(CustomerError::NotFound(3)));
+    assert_eq!(store.len(), 2);
+}
@@ -0,0 +1,182 @@
+use customer_database::customer::CustomerPatch;
+use customer_database::errors::{CustomerError, Field};
+use customer_database::history::{ChangeKind, FieldChange};
+use customer_database::store::CustomerStore;
+use customer_database::transfer::{self, ConflictStrategy, Format};
+
+mod common;
+use common::{new_customer, setup};
+
+fn rename(store: &mut CustomerStore, id: u32, name: &str) {
+    store.update(id, CustomerPatch { name: Some(name.to_string()), ..CustomerPatch::default() }).unwrap();
+}
+
+fn kinds(store: &CustomerStore, id: u32) -> Vec<ChangeKind> {
+    store.history(id).iter().map(|event| event.kind).collect()
+}
+
+#[test]
+fn test_history_records_field_diffs() {
+    let mut store = setup();
+    store
+        .update(1, CustomerPatch { name: Some("Ada King".to_string()), phone: Some("09000000000".to_string()), email: None })
+        .unwrap();
+
+    let history = store.history(1);
+    assert_eq!(history.len(), 2);
+    assert_eq!((history[0].version, history[0].kind), (1, ChangeKind::Added));
+    assert_eq!(history[0].changes.len(), 3);
+    assert_eq!(history[0].changes[0].before, "");
+
+    assert_eq!((history[1].version, history[1].kind), (2, ChangeKind::Updated));
+    assert_eq!(
+        history[1].changes,
+        vec![
+            FieldChange { field: Field::Name, before: "Ada Lovelace".to_string(), after: "Ada King".to_string() },
+            FieldChange { field: Field::Phone, before: "+2348030000001".to_string(), after: "+2349000000000".to_string() },
+        ]
+    );
+    assert_eq!(history[1].to_string(), "v2 Updated\n  Name: 'Ada Lovelace' -> 'Ada King'\n  Phone: '+2348030000001' -> '+2349000000000'");
+
+    assert!(store.history(99).is_empty());
+}
+
+#[test]
+fn test_unchanged_update_is_not_recorded() {
+    let mut store = setup();
+    store.update(1, CustomerPatch { email: Some("ADA@example.com".to_string()), ..CustomerPatch::default() }).unwrap();
+    assert_eq!(store.history(1).len(), 1);
+
+    // Only the add is left to undo
+    store.undo().unwrap();
+    store.undo().unwrap();
+    assert_eq!(store.undo(), Err(CustomerError::NothingToUndo));
+}
+
+#[test]
+fn test_remove_is_soft() {
+    let mut store = setup();
+    store.remove(1).unwrap();
+
+    assert!(store.get(1).is_none());
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.deleted().iter().map(|c| c.id).collect::<Vec<_>>(), vec![1]);
+    assert!(store.find_by_email("ada@example.com").is_none());
+
+    let restored = store.restore(1).unwrap();
+    assert_eq!(restored.name, "Ada Lovelace");
+    assert_eq!(store.find_by_email("ada@example.com").unwrap().id, 1);
+    assert!(store.deleted().is_empty());
+    assert_eq!(kinds(&store, 1), vec![ChangeKind::Added, ChangeKind::Removed, ChangeKind::Restored]);
+
+    assert_eq!(store.restore(1), Err(CustomerError::NotDeleted(1)));
+    assert_eq!(store.restore(99), Err(CustomerError::NotFound(99)));
+}
+
+#[test]
+fn test_restore_needs_free_email() {
+    let mut store = setup();
+    store.remove(1).unwrap();
+    store.add(new_customer("Ada Again", "ada@example.com", "08030000003")).unwrap();
+
+    assert_eq!(store.restore(1), Err(CustomerError::DuplicateEmail("ada@example.com".to_string())));
+    assert_eq!(store.deleted().len(), 1);
+}
+
+#[test]
+fn test_purge() {
+    let mut store = setup();
+    assert_eq!(store.purge(1), Err(CustomerError::NotDeleted(1)));
+
+    store.remove(1).unwrap();
+    assert_eq!(store.purge(1).unwrap().name, "Ada Lovelace");
+    assert!(store.deleted().is_empty());
+    assert_eq!(store.restore(1), Err(CustomerError::NotFound(1)));
+    assert_eq!(kinds(&store, 1).last(), Some(&ChangeKind::Purged));
+
+    // Purging cannot be undone
+    assert!(!store.can_undo());
+    assert_eq!(store.undo(), Err(CustomerError::NothingToUndo));
+
+    // The id is not handed out again
+    assert_eq!(store.add(new_customer("Chidi", "chidi@example.com", "08030000003")).unwrap(), 3);
+}
+
+#[test]
+fn test_multi_level_undo_redo() {
+    let mut store = setup();
+    rename(&mut store, 1, "Ada King");
+    rename(&mut store, 1, "Ada Byron");
+    store.remove(2).unwrap();
+
+    assert_eq!(store.undo().unwrap(), vec![2]);
+    assert_eq!(store.get(2).unwrap().name, "Bola Tinubu");
+    store.undo().unwrap();
+    assert_eq!(store.get(1).unwrap().name, "Ada King");
+    store.undo().unwrap();
+    assert_eq!(store.get(1).unwrap().name, "Ada Lovelace");
+
+    store.redo().unwrap();
+    assert_eq!(store.get(1).unwrap().name, "Ada King");
+    store.redo().unwrap();
+    store.redo().unwrap();
+    assert_eq!(store.get(1).unwrap().name, "Ada Byron");
+    assert!(store.get(2).is_none());
+    assert_eq!(store.redo(), Err(CustomerError::NothingToRedo));
+
+    // Undos and redos are versions too
+    assert_eq!(store.history(1).last().unwrap().version, 7);
+}
+
+#[test]
+fn test_new_change_clears_redo() {
+    let mut store = setup();
+    rename(&mut store, 1, "Ada King");
+    store.undo().unwrap();
+    assert!(store.can_redo());
+
+    rename(&mut store, 1, "Ada Byron");
+    assert!(!store.can_redo());
+    assert_eq!(store.redo(), Err(CustomerError::NothingToRedo));
+}
+
+#[test]
+fn test_undo_add_keeps_the_id() {
+    let mut store = setup();
+    store.undo().unwrap();
+    assert!(store.get(2).is_none());
+    assert_eq!(store.deleted().len(), 1);
+
+    store.redo().unwrap();
+    assert_eq!(store.get(2).unwrap().email, "bola@example.com");
+    assert_eq!(store.add(new_customer("Chidi", "chidi@example.com", "08030000003")).unwrap(), 3);
+}
+
+#[test]
+fn test_restore_can_be_undone() {
+    let mut store = setup();
+    store.remove(1).unwrap();
+    store.restore(1).unwrap();
+
+    assert_eq!(store.undo().unwrap(), vec![1]);
+    assert!(store.get(1).is_none());
+    assert_eq!(store.undo().unwrap(), vec![1]);
+    assert_eq!(store.get(1).unwrap().name, "Ada Lovelace");
+    assert!(store.deleted().is_empty());
+}
+
+#[test]
+fn test_import_undoes_as_one_change() {
+    let mut store = setup();
+    let csv = "Name,Email,Phone\nAda King,ada@example.com,08030000001\nChidi,chidi@example.com,08030000003\nDayo,dayo@example.com,08030000004\n";
+    store.import(transfer::parse(Format::Csv, csv).unwrap(), ConflictStrategy::Overwrite);
+    assert_eq!(store.len(), 4);
+
+    assert_eq!(store.undo().unwrap(), vec![1, 3, 4]);
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.get(1).unwrap().name, "Ada Lovelace");
+
+    store.redo().unwrap();
+    assert_eq!(store.len(), 4);
+    assert_eq!(store.get(1).unwrap().name, "Ada King");
+}
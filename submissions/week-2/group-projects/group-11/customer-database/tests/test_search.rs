@@ -0,0 +1,107 @@
+use customer_database::customer::{Customer, CustomerPatch};
+use customer_database::errors::CustomerError;
+use customer_database::store::{CustomerStore, SearchField};
+
+mod common;
+use common::new_customer;
+
+fn setup() -> CustomerStore {
+    common::store_with(&[
+        ("Ada Lovelace", "ada@example.com", "0803 000 0001"),
+        ("Adaeze Obi", "adaeze@example.com", "0803-000-0002"),
+        ("Kolade Adams", "kolade@example.com", "0803 000 0002"),
+        ("Bola Tinubu", "bola@example.com", "08030000004"),
+    ])
+}
+
+fn ids(customers: Vec<&Customer>) -> Vec<u32> {
+    customers.iter().map(|customer| customer.id).collect()
+}
+
+#[test]
+fn test_find_by_email_ignores_case() {
+    let store = setup();
+    assert_eq!(store.find_by_email("ADA@example.com").unwrap().id, 1);
+    assert!(store.find_by_email("nobody@example.com").is_none());
+}
+
+#[test]
+fn test_duplicate_email_ignores_case() {
+    let mut store = setup();
+    assert_eq!(
+        store.add(new_customer("Ada", "Ada@Example.com", "08030000009")),
+        Err(CustomerError::DuplicateEmail("ada@example.com".to_string()))
+    );
+}
+
+#[test]
+fn test_find_by_phone_ignores_formatting() {
+    let store = setup();
+    assert_eq!(ids(store.find_by_phone("08030000002")), vec![2, 3]);
+    assert_eq!(ids(store.find_by_phone("(0803) 000.0001")), vec![1]);
+    assert!(store.find_by_phone("08039999999").is_empty());
+    assert!(store.find_by_phone("not a number").is_empty());
+}
+
+#[test]
+fn test_search_name_prefix_then_substring() {
+    let store = setup();
+    // "ada lovelace" and "adaeze obi" start with "ada"; "kolade adams" only contains it
+    assert_eq!(ids(store.search_name("ADA")), vec![1, 2, 3]);
+    assert_eq!(ids(store.search_name("obi")), vec![2]);
+    assert_eq!(ids(store.search_name("lade")), vec![3]);
+    assert!(store.search_name("  ").is_empty());
+    assert!(store.search_name("zed").is_empty());
+}
+
+#[test]
+fn test_search_by_field() {
+    let store = setup();
+    assert_eq!(ids(store.search(SearchField::Id, "4")), vec![4]);
+    assert!(store.search(SearchField::Id, "four").is_empty());
+    assert_eq!(ids(store.search(SearchField::Name, "bola")), vec![4]);
+    assert_eq!(ids(store.search(SearchField::Email, "kolade@example.com")), vec![3]);
+    assert_eq!(ids(store.search(SearchField::Phone, "0803 000 0004")), vec![4]);
+}
+
+#[test]
+fn test_indexes_follow_updates() {
+    let mut store = setup();
+
+    let patch = CustomerPatch {
+        name: Some("Ada King".to_string()),
+        email: Some("ada@king.org".to_string()),
+        phone: Some("07000000000".to_string()),
+    };
+    store.update(1, patch).unwrap();
+
+    assert!(store.find_by_email("ada@example.com").is_none());
+    assert_eq!(store.find_by_email("ada@king.org").unwrap().id, 1);
+    assert!(store.find_by_phone("08030000001").is_empty());
+    assert_eq!(ids(store.find_by_phone("+234 700 000 0000")), vec![1]);
+    assert!(store.search_name("lovelace").is_empty());
+    assert_eq!(ids(store.search_name("king")), vec![1]);
+
+    // The old email can be taken by someone else now
+    assert!(store.add(new_customer("Other Ada", "ada@example.com", "08030000009")).is_ok());
+}
+
+#[test]
+fn test_failed_update_keeps_indexes() {
+    let mut store = setup();
+    let patch = CustomerPatch { email: Some("bola@example.com".to_string()), ..CustomerPatch::default() };
+    assert!(store.update(1, patch).is_err());
+
+    assert_eq!(store.find_by_email("ada@example.com").unwrap().id, 1);
+    assert_eq!(store.find_by_email("bola@example.com").unwrap().id, 4);
+}
+
+#[test]
+fn test_indexes_follow_removal() {
+    let mut store = setup();
+    store.remove(2).unwrap();
+
+    assert!(store.find_by_email("adaeze@example.com").is_none());
+    assert_eq!(ids(store.find_by_phone("08030000002")), vec![3]);
+    assert_eq!(ids(store.search_name("ada")), vec![1, 3]);
+}
@@ -0,0 +1,158 @@
+use customer_database::customer::CustomerPatch;
+use customer_database::errors::{CustomerError, Field};
+use customer_database::store::CustomerStore;
+
+mod common;
+use common::{new_customer, setup};
+
+#[test]
+fn test_add_assigns_sequential_ids() {
+    let mut store = CustomerStore::new();
+    assert!(store.is_empty());
+
+    assert_eq!(store.add(new_customer("Ada", "ada@example.com", "08030000011")).unwrap(), 1);
+    assert_eq!(store.add(new_customer("Bola", "bola@example.com", "08030000012")).unwrap(), 2);
+    assert_eq!(store.len(), 2);
+
+    let ada = store.get(1).unwrap();
+    assert_eq!(ada.name, "Ada");
+    assert_eq!(ada.email, "ada@example.com");
+    assert_eq!(ada.phone, "+2348030000011");
+}
+
+#[test]
+fn test_add_normalizes_fields() {
+    let mut store = CustomerStore::new();
+    let id = store.add(new_customer("  Ada ", " Ada@Example.COM ", " 0803 000 0011 ")).unwrap();
+
+    let ada = store.get(id).unwrap();
+    assert_eq!(ada.name, "Ada");
+    assert_eq!(ada.email, "ada@example.com");
+    assert_eq!(ada.phone, "+2348030000011");
+}
+
+#[test]
+fn test_add_rejects_invalid_input() {
+    let mut store = setup();
+
+    let err = store.add(new_customer("Chidi", "chidi.example.com", "08030000013")).unwrap_err();
+    assert!(err.field_error(Field::Email).is_some());
+    assert_eq!(
+        store.add(new_customer("Chidi", "ADA@example.com", "08030000013")),
+        Err(CustomerError::DuplicateEmail("ada@example.com".to_string()))
+    );
+
+    let Err(CustomerError::Invalid(errors)) = store.add(new_customer(" ", "", "")) else {
+        panic!("expected validation errors");
+    };
+    let fields: Vec<Field> = errors.iter().map(|error| error.field).collect();
+    assert_eq!(fields, vec![Field::Name, Field::Email, Field::Phone]);
+    assert_eq!(store.len(), 2);
+
+    // A rejected add does not use up an id
+    assert_eq!(store.add(new_customer("Chidi", "chidi@example.com", "08030000013")).unwrap(), 3);
+}
+
+#[test]
+fn test_get_missing() {
+    let store = setup();
+    assert!(store.get(99).is_none());
+}
+
+#[test]
+fn test_update_applies_patch() {
+    let mut store = setup();
+
+    let updated = store
+        .update(1, CustomerPatch { phone: Some("09000000000".to_string()), ..CustomerPatch::default() })
+        .unwrap();
+    assert_eq!(updated.phone, "+2349000000000");
+    assert_eq!(updated.name, "Ada Lovelace");
+
+    store
+        .update(1, CustomerPatch { name: Some("Ada King".to_string()), email: Some("ada@king.org".to_string()), phone: None })
+        .unwrap();
+    let ada = store.get(1).unwrap();
+    assert_eq!(ada.name, "Ada King");
+    assert_eq!(ada.email, "ada@king.org");
+}
+
+#[test]
+fn test_update_validates_email() {
+    let mut store = setup();
+
+    let taken = CustomerPatch { email: Some("bola@example.com".to_string()), ..CustomerPatch::default() };
+    assert_eq!(store.update(1, taken), Err(CustomerError::DuplicateEmail("bola@example.com".to_string())));
+
+    let invalid = CustomerPatch { email: Some("nope".to_string()), ..CustomerPatch::default() };
+    assert!(store.update(1, invalid).unwrap_err().field_error(Field::Email).is_some());
+
+    // Keeping your own email is not a conflict
+    let same = CustomerPatch { email: Some("ada@example.com".to_string()), ..CustomerPatch::default() };
+    assert!(store.update(1, same).is_ok());
+}
+
+#[test]
+fn test_failed_update_changes_nothing() {
+    let mut store = setup();
+    let before = store.get(1).unwrap().clone();
+
+    let patch = CustomerPatch {
+        name: Some("New Name".to_string()),
+        email: Some("bola@example.com".to_string()),
+        phone: Some("08030000099".to_string()),
+    };
+    assert!(store.update(1, patch).is_err());
+    assert_eq!(store.get(1).unwrap(), &before);
+
+    let blank = CustomerPatch { name: Some("   ".to_string()), phone: Some("12".to_string()), ..CustomerPatch::default() };
+    let Err(CustomerError::Invalid(errors)) = store.update(1, blank) else {
+        panic!("expected validation errors");
+    };
+    assert_eq!(errors.len(), 2);
+    assert_eq!(store.get(1).unwrap(), &before);
+}
+
+#[test]
+fn test_update_missing() {
+    let mut store = setup();
+    assert_eq!(store.update(99, CustomerPatch::default()), Err(CustomerError::NotFound(99)));
+}
+
+#[test]
+fn test_remove() {
+    let mut store = setup();
+
+    let removed = store.remove(1).unwrap();
+    assert_eq!(removed.name, "Ada Lovelace");
+    assert!(store.get(1).is_none());
+    assert_eq!(store.remove(1), Err(CustomerError::NotFound(1)));
+
+    // The email is free again
+    assert!(store.add(new_customer("Ada", "ada@example.com", "08030000011")).is_ok());
+}
+
+#[test]
+fn test_list_is_ordered_by_id() {
+    let mut store = setup();
+    store.add(new_customer("Chidi", "chidi@example.com", "08030000013")).unwrap();
+    store.remove(2).unwrap();
+
+    let ids: Vec<u32> = store.list().iter().map(|customer| customer.id).collect();
+    assert_eq!(ids, vec![1, 3]);
+}
+
+#[test]
+fn test_patch_is_empty() {
+    assert!(CustomerPatch::default().is_empty());
+    assert!(!CustomerPatch { phone: Some("1".to_string()), ..CustomerPatch::default() }.is_empty());
+}
+
+#[test]
+fn test_error_messages() {
+    assert_eq!(CustomerError::NotFound(7).to_string(), "Customer with ID 7 not found");
+    assert_eq!(
+        CustomerError::DuplicateEmail("a@b.c".to_string()).to_string(),
+        "Email a@b.c is already registered to another customer"
+    );
+}
@@ -0,0 +1,202 @@
+use customer_database::errors::{CustomerError, Field, ImportError, RowError};
+use customer_database::store::CustomerStore;
+use customer_database::transfer::{self, ConflictStrategy, Format, ImportRecord};
+
+mod common;
+use common::{new_customer, setup};
+
+fn round_trip(format: Format) {
+    let mut source = setup();
+    source.add(new_customer("Chidi, \"CJ\"; Okafor", "chidi@example.com", "+44 20 7946 0958")).unwrap();
+
+    let exported = transfer::export(format, &source.list());
+    let mut target = CustomerStore::new();
+    let report = target.import(transfer::parse(format, &exported).unwrap(), ConflictStrategy::Skip);
+
+    assert_eq!(report.added, vec![1, 2, 3]);
+    assert!(report.errors.is_empty());
+    for (before, after) in source.list().iter().zip(target.list()) {
+        assert_eq!(*before, after);
+    }
+}
+
+#[test]
+fn test_round_trips() {
+    round_trip(Format::Csv);
+    round_trip(Format::Json);
+    round_trip(Format::VCard);
+}
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(Format::from_path("customers.CSV".as_ref()), Some(Format::Csv));
+    assert_eq!(Format::from_path("out/customers.json".as_ref()), Some(Format::Json));
+    assert_eq!(Format::from_path("contacts.vcf".as_ref()), Some(Format::VCard));
+    assert_eq!(Format::from_path("customers.txt".as_ref()), None);
+    assert_eq!(Format::from_path("customers".as_ref()), None);
+}
+
+#[test]
+fn test_csv_export_headers() {
+    let store = setup();
+    let csv = transfer::export(Format::Csv, &store.list());
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("ID,Name,Email,Phone"));
+    assert_eq!(lines.next(), Some("1,Ada Lovelace,ada@example.com,+2348030000001"));
+}
+
+#[test]
+fn test_csv_import_columns() {
+    // Only Name and Email are required, in any order; the ID column is ignored
+    let csv = "Email,Name\nada@example.com,Ada\n";
+    let records = transfer::parse(Format::Csv, csv).unwrap();
+    assert_eq!(
+        records,
+        vec![Ok(ImportRecord { position: 2, customer: new_customer("Ada", "ada@example.com", "") })]
+    );
+
+    assert_eq!(transfer::parse(Format::Csv, "Name,Phone\nAda,0803\n"), Err(ImportError::MissingColumn("Email")));
+    assert_eq!(transfer::parse(Format::Csv, ""), Err(ImportError::MissingColumn("Name")));
+}
+
+#[test]
+fn test_import_validates_each_row() {
+    let mut store = CustomerStore::new();
+    let csv = "Name,Email,Phone\n\
+               Ada,ada@example.com,08030000001\n\
+               Bola,not-an-email,08030000002\n\
+               Chidi,chidi@example.com,08030000003,extra\n\
+               Dayo,dayo@example.com,08030000004\n";
+    let report = store.import(transfer::parse(Format::Csv, csv).unwrap(), ConflictStrategy::Skip);
+
+    assert_eq!(report.added, vec![1, 2]);
+    assert_eq!(report.errors.len(), 2);
+    assert_eq!(report.errors[0].position, 3);
+    assert!(matches!(&report.errors[0].error, RowError::Rejected(e) if e.field_error(Field::Email).is_some()));
+    assert_eq!(report.errors[1].position, 4);
+    assert!(matches!(report.errors[1].error, RowError::Malformed(_)));
+    assert_eq!(store.get(2).unwrap().name, "Dayo");
+}
+
+#[test]
+fn test_import_rejects_duplicates_within_file() {
+    let mut store = CustomerStore::new();
+    let json = r#"[
+        {"name": "Ada", "email": "ada@example.com", "phone": "08030000001"},
+        {"name": "Ada Again", "email": "ADA@example.com", "phone": "08030000009"},
+        {"name": 7}
+    ]"#;
+    let report = store.import(transfer::parse(Format::Json, json).unwrap(), ConflictStrategy::Skip);
+
+    // The second record conflicts with the first, so the skip strategy applies
+    assert_eq!(report.added, vec![1]);
+    assert_eq!(report.skipped, vec![2]);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].position, 3);
+
+    assert!(matches!(transfer::parse(Format::Json, "{}"), Err(ImportError::Malformed(_))));
+}
+
+#[test]
+fn test_conflict_strategies() {
+    let incoming = "Name,Email,Phone\nAda King,ADA@example.com,\nChidi,chidi@example.com,08030000003\n";
+
+    let mut store = setup();
+    let report = store.import(transfer::parse(Format::Csv, incoming).unwrap(), ConflictStrategy::Skip);
+    assert_eq!((report.added.clone(), report.skipped.clone()), (vec![3], vec![2]));
+    assert_eq!(store.get(1).unwrap().name, "Ada Lovelace");
+
+    // Merge keeps the stored name and phone, since neither is blank, so the record is skipped
+    let mut store = setup();
+    let report = store.import(transfer::parse(Format::Csv, incoming).unwrap(), ConflictStrategy::Merge);
+    assert!(report.updated.is_empty());
+    assert_eq!((report.added.clone(), report.skipped.clone()), (vec![3], vec![2]));
+    let ada = store.get(1).unwrap();
+    assert_eq!((ada.name.as_str(), ada.phone.as_str()), ("Ada Lovelace", "+2348030000001"));
+
+    // Overwrite replaces every field, so the blank phone is rejected and nothing changes
+    let mut store = setup();
+    let report = store.import(transfer::parse(Format::Csv, incoming).unwrap(), ConflictStrategy::Overwrite);
+    assert!(report.updated.is_empty());
+    assert!(matches!(
+        &report.errors[0].error,
+        RowError::Rejected(CustomerError::Invalid(errors)) if errors[0].field == Field::Phone
+    ));
+    assert_eq!(store.get(1).unwrap().name, "Ada Lovelace");
+
+    let overwrite = "Name,Email,Phone\nAda King,ada@example.com,09000000000\n";
+    let report = store.import(transfer::parse(Format::Csv, overwrite).unwrap(), ConflictStrategy::Overwrite);
+    assert_eq!(report.updated, vec![1]);
+    assert_eq!(store.get(1).unwrap().phone, "+2349000000000");
+}
+
+#[test]
+fn test_merge_keeps_existing_values() {
+    let mut store = setup();
+    let history_before = store.history(1).len();
+    let incoming = "Name,Email,Phone\nAda King,ada@example.com,09000000000\n";
+
+    let report = store.import(transfer::parse(Format::Csv, incoming).unwrap(), ConflictStrategy::Merge);
+    assert!(report.updated.is_empty() && report.errors.is_empty());
+    assert_eq!(report.skipped, vec![2]);
+    let ada = store.get(1).unwrap();
+    assert_eq!((ada.name.as_str(), ada.phone.as_str()), ("Ada Lovelace", "+2348030000001"));
+    assert_eq!(store.history(1).len(), history_before);
+}
+
+#[test]
+fn test_import_summary() {
+    let mut store = setup();
+    let csv = "Name,Email,Phone\nAda,ada@example.com,08030000001\nEmeka,emeka@,08030000005\nFola,fola@example.com,08030000006\n";
+    let report = store.import(transfer::parse(Format::Csv, csv).unwrap(), ConflictStrategy::Skip);
+
+    assert_eq!(
+        report.summary(),
+        "Added 1, updated 0, skipped 1 existing, 1 rejected\n\
+         record 2: email already registered, skipped\n\
+         record 3: Email: the domain must be 1 to 253 characters"
+    );
+}
+
+#[test]
+fn test_vcard_export_format() {
+    let mut store = CustomerStore::new();
+    let long_name = "Oluwaseun Adebayo-Williams Ogunleye Babatunde Olamide Oluwafemi Adekunle-Bankole";
+    store.add(new_customer(long_name, "seun@example.com", "08030000007")).unwrap();
+    store.add(new_customer("Bola", "bola@example.com", "08030000002")).unwrap();
+
+    let vcard = transfer::export(Format::VCard, &store.list());
+    assert!(vcard.starts_with("BEGIN:VCARD\r\nVERSION:3.0\r\n"));
+    assert!(vcard.contains("EMAIL;TYPE=INTERNET:seun@example.com\r\n"));
+    assert!(vcard.contains("TEL;TYPE=CELL:+2348030000007\r\n"));
+    assert!(vcard.contains("N:;Bola;;;\r\n"));
+    assert!(vcard.ends_with("END:VCARD\r\n"));
+
+    // Long lines are folded to 75 octets with a leading space on the continuation
+    assert!(vcard.split("\r\n").all(|line| line.len() <= 75));
+    assert!(vcard.contains("\r\n "));
+    let records = transfer::parse(Format::VCard, &vcard).unwrap();
+    assert!(matches!(&records[0], Ok(record) if record.customer.name == long_name));
+}
+
+#[test]
+fn test_vcard_import() {
+    let input = "BEGIN:VCARD\n\
+                 VERSION:3.0\n\
+                 N:Lovelace;Ada;;;\n\
+                 item1.EMAIL;TYPE=WORK:ada@exam\n ple.com\n\
+                 EMAIL:second@example.com\n\
+                 TEL;TYPE=CELL:+234 803 000 0001\n\
+                 END:VCARD\n\
+                 BEGIN:VCARD\n\
+                 FN:Bola\\, Jr.\n\
+                 EMAIL:bola@example.com\n";
+    let records = transfer::parse(Format::VCard, input).unwrap();
+
+    assert_eq!(
+        records[0],
+        Ok(ImportRecord { position: 1, customer: new_customer("Ada Lovelace", "ada@example.com", "+234 803 000 0001") })
+    );
+    // The second card never ends
+    assert!(matches!(&records[1], Err(issue) if issue.position == 9));
+}
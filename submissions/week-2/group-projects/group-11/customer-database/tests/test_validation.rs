@@ -0,0 +1,114 @@
+use customer_database::customer::NewCustomer;
+use customer_database::errors::Field;
+use customer_database::store::CustomerStore;
+use customer_database::validation::{MAX_NAME_CHARS, Validator};
+
+fn validator() -> Validator {
+    Validator::default()
+}
+
+#[test]
+fn test_name_is_trimmed_and_normalized() {
+    let v = validator();
+    assert_eq!(v.name("  Ada   Lovelace \t").unwrap(), "Ada Lovelace");
+    // "e" followed by a combining acute accent becomes the single precomposed character
+    assert_eq!(v.name("Ade\u{301}ye\u{301}mi\u{301}").unwrap(), "Adéyémí");
+    assert_eq!(v.name("Adéyémí").unwrap().chars().count(), 7);
+}
+
+#[test]
+fn test_name_errors() {
+    let v = validator();
+    assert_eq!(v.name("   ").unwrap_err().field, Field::Name);
+    assert!(v.name("Ada\u{7}").is_err());
+    assert!(v.name(&"a".repeat(MAX_NAME_CHARS)).is_ok());
+    assert!(v.name(&"a".repeat(MAX_NAME_CHARS + 1)).is_err());
+}
+
+#[test]
+fn test_valid_emails_are_lowercased() {
+    let v = validator();
+    assert_eq!(v.email(" Ada.Lovelace@Example.COM ").unwrap(), "ada.lovelace@example.com");
+    assert_eq!(v.email("o'brien+tag@mail.co.uk").unwrap(), "o'brien+tag@mail.co.uk");
+    assert_eq!(v.email("a_b-c@sub-domain.example.ng").unwrap(), "a_b-c@sub-domain.example.ng");
+}
+
+#[test]
+fn test_invalid_emails() {
+    let v = validator();
+    for email in [
+        "",
+        "plainaddress",
+        "@example.com",
+        "ada@",
+        "ada@@example.com",
+        "ada@b@example.com",
+        ".ada@example.com",
+        "ada.@example.com",
+        "a..da@example.com",
+        "a da@example.com",
+        "ada@localhost",
+        "ada@-example.com",
+        "ada@example-.com",
+        "ada@exa_mple.com",
+        "ada@example..com",
+        "ada@127.0.0.1",
+    ] {
+        let err = v.email(email).unwrap_err();
+        assert_eq!(err.field, Field::Email, "{}", email);
+    }
+
+    let long_local = format!("{}@example.com", "a".repeat(65));
+    assert!(v.email(&long_local).is_err());
+}
+
+#[test]
+fn test_phone_normalized_to_e164() {
+    let v = validator();
+    assert_eq!(v.phone("0803 000 0001").unwrap(), "+2348030000001");
+    assert_eq!(v.phone("803-000-0001").unwrap(), "+2348030000001");
+    assert_eq!(v.phone("+234 (803) 000.0001").unwrap(), "+2348030000001");
+    assert_eq!(v.phone("00447911123456").unwrap(), "+447911123456");
+}
+
+#[test]
+fn test_phone_uses_configured_country_code() {
+    let uk = Validator::new(44);
+    assert_eq!(uk.default_country_code(), 44);
+    assert_eq!(uk.phone("07911 123456").unwrap(), "+447911123456");
+    // An explicit country code wins over the default
+    assert_eq!(uk.phone("+2348030000001").unwrap(), "+2348030000001");
+}
+
+#[test]
+fn test_phone_errors() {
+    let v = validator();
+    for phone in ["", "  ", "0803-CALL-ME", "+", "+0123456789", "12", "+1234567890123456", "080+30000001"] {
+        assert_eq!(v.phone(phone).unwrap_err().field, Field::Phone, "{}", phone);
+    }
+}
+
+#[test]
+fn test_store_uses_its_validator() {
+    let mut store = CustomerStore::with_validator(Validator::new(1));
+    let id = store
+        .add(NewCustomer {
+            name: "Grace  Hopper".to_string(),
+            email: "Grace@Navy.MIL".to_string(),
+            phone: "(202) 555-0143".to_string(),
+        })
+        .unwrap();
+
+    let grace = store.get(id).unwrap();
+    assert_eq!(grace.name, "Grace Hopper");
+    assert_eq!(grace.email, "grace@navy.mil");
+    assert_eq!(grace.phone, "+12025550143");
+    assert_eq!(store.find_by_phone("202 555 0143").len(), 1);
+}
+
+#[test]
+fn test_check_email_returns_normalized_address() {
+    let store = CustomerStore::new();
+    assert_eq!(store.check_email("Ada@Example.com", None).unwrap(), "ada@example.com");
+    assert!(store.check_email("nope", None).unwrap_err().field_error(Field::Email).is_some());
+}
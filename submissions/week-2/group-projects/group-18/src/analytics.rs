@@ -0,0 +1,328 @@
+use crate::models::Feedback;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const POSITIVE_WORDS: &[&str] = &[
+    "amazing",
+    "awesome",
+    "best",
+    "easy",
+    "excellent",
+    "fantastic",
+    "fast",
+    "friendly",
+    "good",
+    "great",
+    "happy",
+    "helpful",
+    "impressed",
+    "love",
+    "loved",
+    "nice",
+    "perfect",
+    "pleasant",
+    "quick",
+    "recommend",
+    "reliable",
+    "satisfied",
+    "smooth",
+    "thank",
+    "thanks",
+    "wonderful",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "angry",
+    "awful",
+    "bad",
+    "broken",
+    "confusing",
+    "crash",
+    "crashed",
+    "delay",
+    "delayed",
+    "disappointed",
+    "disappointing",
+    "expensive",
+    "fail",
+    "failed",
+    "hate",
+    "horrible",
+    "issue",
+    "late",
+    "poor",
+    "problem",
+    "refund",
+    "rude",
+    "slow",
+    "terrible",
+    "unhappy",
+    "useless",
+    "worst",
+    "wrong",
+];
+
+/// A negator flips the sentiment of the word straight after it, so "not good" counts as negative.
+const NEGATORS: &[&str] = &[
+    "not", "no", "never", "dont", "didnt", "isnt", "wasnt", "cant",
+];
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "am", "an", "and", "any", "are", "as", "at", "be",
+    "been", "but", "by", "can", "could", "did", "do", "does", "for", "from", "get", "got", "had",
+    "has", "have", "he", "her", "him", "his", "i", "if", "im", "in", "is", "it", "its", "ive",
+    "just", "me", "my", "of", "on", "or", "our", "out", "she", "so", "than", "that", "the",
+    "their", "them", "then", "there", "they", "this", "to", "too", "up", "us", "very", "was", "we",
+    "were", "what", "when", "which", "will", "with", "would", "you", "your",
+];
+
+/// How many keywords and bigrams the report lists.
+pub const TOP_TERMS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum TimeWindow {
+    Last7Days,
+    Last30Days,
+    Last90Days,
+    AllTime,
+}
+
+impl TimeWindow {
+    pub const ALL: [TimeWindow; 4] = [
+        TimeWindow::Last7Days,
+        TimeWindow::Last30Days,
+        TimeWindow::Last90Days,
+        TimeWindow::AllTime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeWindow::Last7Days => "Last 7 days",
+            TimeWindow::Last30Days => "Last 30 days",
+            TimeWindow::Last90Days => "Last 90 days",
+            TimeWindow::AllTime => "All time",
+        }
+    }
+
+    /// Whether feedback created at `created` falls in the window ending at `now`.
+    pub fn contains(&self, created: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let days = match self {
+            TimeWindow::Last7Days => 7,
+            TimeWindow::Last30Days => 30,
+            TimeWindow::Last90Days => 90,
+            TimeWindow::AllTime => return true,
+        };
+        now - created <= Duration::days(days)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RatingSummary {
+    pub window: TimeWindow,
+    pub count: usize,
+    pub average: Option<f64>,
+    /// Number of 1- to 5-star ratings, in that order.
+    pub distribution: [usize; 5],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Sentiment {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SentimentScore {
+    /// Positive words minus negative words, after negation.
+    pub score: i32,
+    pub sentiment: Sentiment,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TermCount {
+    pub term: String,
+    pub count: usize,
+}
+
+/// Feedback whose rating and comment disagree, such as five stars with a complaint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub id: u32,
+    pub customer_name: String,
+    pub rating: u8,
+    pub sentiment: SentimentScore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedbackSentiment {
+    pub id: u32,
+    pub rating: u8,
+    pub sentiment: SentimentScore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalyticsReport {
+    pub generated_at: DateTime<Utc>,
+    pub ratings: Vec<RatingSummary>,
+    /// Per feedback entry, by id.
+    pub sentiments: Vec<FeedbackSentiment>,
+    pub top_keywords: Vec<TermCount>,
+    pub top_bigrams: Vec<TermCount>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+pub fn build_report(feedbacks: &[&Feedback], now: DateTime<Utc>) -> AnalyticsReport {
+    let mut feedbacks = feedbacks.to_vec();
+    feedbacks.sort_by_key(|f| f.id);
+
+    let sentiments: Vec<FeedbackSentiment> = feedbacks
+        .iter()
+        .map(|f| FeedbackSentiment {
+            id: f.id,
+            rating: f.rating,
+            sentiment: score_sentiment(&f.comment),
+        })
+        .collect();
+    let mismatches = feedbacks
+        .iter()
+        .zip(&sentiments)
+        .filter(|(f, s)| is_mismatch(f.rating, &s.sentiment))
+        .map(|(f, s)| Mismatch {
+            id: f.id,
+            customer_name: f.customer_name.clone(),
+            rating: f.rating,
+            sentiment: s.sentiment.clone(),
+        })
+        .collect();
+    let comments: Vec<&str> = feedbacks.iter().map(|f| f.comment.as_str()).collect();
+
+    AnalyticsReport {
+        generated_at: now,
+        ratings: TimeWindow::ALL
+            .iter()
+            .map(|window| rating_summary(&feedbacks, *window, now))
+            .collect(),
+        sentiments,
+        top_keywords: top_keywords(&comments, TOP_TERMS),
+        top_bigrams: top_bigrams(&comments, TOP_TERMS),
+        mismatches,
+    }
+}
+
+pub fn report_to_json(report: &AnalyticsReport) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(report)
+}
+
+pub fn rating_summary(
+    feedbacks: &[&Feedback],
+    window: TimeWindow,
+    now: DateTime<Utc>,
+) -> RatingSummary {
+    let mut distribution = [0; 5];
+    let mut total = 0u32;
+    let mut count = 0;
+    for feedback in feedbacks
+        .iter()
+        .filter(|f| window.contains(f.date_created, now))
+    {
+        let slot = usize::from(feedback.rating)
+            .checked_sub(1)
+            .and_then(|index| distribution.get_mut(index));
+        if let Some(slot) = slot {
+            *slot += 1;
+            total += u32::from(feedback.rating);
+            count += 1;
+        }
+    }
+    RatingSummary {
+        window,
+        count,
+        average: (count > 0).then(|| f64::from(total) / count as f64),
+        distribution,
+    }
+}
+
+/// Lowercase words with apostrophes dropped, so "Don't" becomes "dont".
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.replace('\'', "").to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+pub fn score_sentiment(comment: &str) -> SentimentScore {
+    let mut score = 0;
+    let mut negated = false;
+    for word in words(comment) {
+        let value = if POSITIVE_WORDS.contains(&word.as_str()) {
+            1
+        } else if NEGATIVE_WORDS.contains(&word.as_str()) {
+            -1
+        } else {
+            0
+        };
+        score += if negated { -value } else { value };
+        negated = NEGATORS.contains(&word.as_str());
+    }
+    let sentiment = match score {
+        s if s > 0 => Sentiment::Positive,
+        s if s < 0 => Sentiment::Negative,
+        _ => Sentiment::Neutral,
+    };
+    SentimentScore { score, sentiment }
+}
+
+/// Four or five stars with a negative comment, or one or two stars with a positive one.
+pub fn is_mismatch(rating: u8, sentiment: &SentimentScore) -> bool {
+    match sentiment.sentiment {
+        Sentiment::Negative => rating >= 4,
+        Sentiment::Positive => rating <= 2,
+        Sentiment::Neutral => false,
+    }
+}
+
+/// Words of each comment with stop words removed. Numbers are dropped too.
+fn content_words(comment: &str) -> Vec<String> {
+    words(comment)
+        .into_iter()
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+pub fn top_keywords(comments: &[&str], limit: usize) -> Vec<TermCount> {
+    let terms = comments.iter().flat_map(|comment| content_words(comment));
+    top_terms(terms, limit)
+}
+
+/// Pairs of neighbouring words once stop words are removed, e.g. "delivery was slow" gives
+/// "delivery slow". Pairs never span two sentences or two comments.
+pub fn top_bigrams(comments: &[&str], limit: usize) -> Vec<TermCount> {
+    let sentences = comments
+        .iter()
+        .flat_map(|comment| comment.split(['.', '!', '?', ';']));
+    let terms = sentences.flat_map(|sentence| {
+        content_words(sentence)
+            .windows(2)
+            .map(|pair| format!("{} {}", pair[0], pair[1]))
+            .collect::<Vec<String>>()
+    });
+    top_terms(terms, limit)
+}
+
+/// Most frequent first; ties in alphabetical order so the output is stable.
+fn top_terms(terms: impl Iterator<Item = String>, limit: usize) -> Vec<TermCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for term in terms {
+        *counts.entry(term).or_default() += 1;
+    }
+    let mut counts: Vec<TermCount> = counts
+        .into_iter()
+        .map(|(term, count)| TermCount { term, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    counts.truncate(limit);
+    counts
+}
@@ -1,4 +1,5 @@
 pub mod models;
+pub mod analytics;
 pub mod manager;
 pub mod input;
 pub mod menu;
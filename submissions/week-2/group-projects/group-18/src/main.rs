@@ -1,20 +1,17 @@
-mod models;
-mod manager;
-mod input;
-mod menu;
-mod ui;
-mod persistence;
-
-use std::thread;
-use std::time::Duration;
+use std::env;
 use customer_feedback_logger::utils::delay_execution;
-use manager::FeedbackManager;
-use input::get_user_input;
-use menu::{add_feedback_menu, remove_feedback_menu, edit_feedback_menu};
-use ui::{display_welcome, display_menu, display_goodbye};
+use customer_feedback_logger::input::get_user_input;
+use customer_feedback_logger::menu::{
+    add_feedback_menu, analytics_menu, edit_feedback_menu, filter_feedback_menu, follow_up_menu,
+    open_manager, remove_feedback_menu, view_all_feedback_menu,
+};
+use customer_feedback_logger::persistence::{resolve_data_path, FileStorage, DATA_FILE_ENV};
+use customer_feedback_logger::ui::{display_welcome, display_menu, display_goodbye};
 
 fn main() {
-    let mut manager = FeedbackManager::new();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let data_path = resolve_data_path(&args, env::var(DATA_FILE_ENV).ok());
+    let mut manager = open_manager(Box::new(FileStorage::new(data_path)));
 
     display_welcome();
 
@@ -23,7 +20,7 @@ fn main() {
 
         let choice: u8;
         loop {
-            let _choice = match get_user_input("Enter your choice (1-6): ") {
+            let _choice = match get_user_input("Enter your choice (1-9): ") {
                 Ok(input) => input,
                 Err(_) => {
                     println!("Error reading input. Please try again.");
@@ -35,7 +32,7 @@ fn main() {
              choice = match _choice.trim().parse() {
                 Ok(c) => c,
                 Err(_) => {
-                    println!("Please enter a valid number (1-6).");
+                    println!("Please enter a valid number (1-9).");
                     delay_execution();
                     continue;
                 }
@@ -45,7 +42,7 @@ fn main() {
 
         match choice {
             1 => add_feedback_menu(&mut manager),
-            2 => manager.view_all_feedback(),
+            2 => view_all_feedback_menu(&manager),
             3 => remove_feedback_menu(&mut manager),
             4 => edit_feedback_menu(&mut manager),
             5 => {
@@ -54,7 +51,10 @@ fn main() {
                     Err(e) => println!("Failed to save data: {:?}", e),
                 }
             }
-            6 => {
+            6 => analytics_menu(&manager),
+            7 => follow_up_menu(&mut manager),
+            8 => filter_feedback_menu(&manager),
+            9 => {
                 // Final save before exit
                 if let Err(e) = manager.save_data() {
                     println!("Warning: Failed to save data before exit: {:?}", e);
@@ -62,7 +62,7 @@ fn main() {
                 display_goodbye();
                 break;
             }
-            _ => println!("Invalid choice. Please enter a number between 1 and 6."),
+            _ => println!("Invalid choice. Please enter a number between 1 and 9."),
         }
         delay_execution();
     }
@@ -1,178 +1,176 @@
-use crate::input::get_user_input;
-use crate::models::{Feedback, FeedbackError};
-use crate::persistence;
+use crate::models::{
+    Feedback, FeedbackError, FeedbackFilter, FeedbackPatch, FollowUpStatus, StaffResponse,
+};
+use crate::persistence::{FeedbackData, Storage, CURRENT_VERSION};
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
+/// The feedback database. It never reads input or prints; every change is saved through the
+/// storage it was given, and a change that cannot be saved is undone before the error is returned.
 pub struct FeedbackManager {
     feedbacks: HashMap<u32, Feedback>,
     next_id: u32,
+    storage: Box<dyn Storage>,
 }
 
 impl FeedbackManager {
-    pub fn new() -> Self {
-        // Try to load existing data from file
-        match persistence::load_from_file() {
-            Ok((feedbacks, next_id)) => {
-                println!("Loaded {} existing feedback entries.", feedbacks.len());
-                Self { feedbacks, next_id }
-            }
-            Err(e) => {
-                eprintln!("Warning: Could not load existing data: {:?}", e);
-                eprintln!("Starting with empty feedback database.");
-                Self {
-                    feedbacks: HashMap::new(),
-                    next_id: 1,
-                }
-            }
+    /// An empty database that saves to `storage`. The first change overwrites anything already
+    /// stored there; use `load` to keep it.
+    pub fn empty(storage: Box<dyn Storage>) -> Self {
+        Self::with_data(storage, FeedbackData::empty())
+    }
+
+    /// Starts from what `storage` holds. A corrupt store is reported as
+    /// `FeedbackError::CorruptData` so the caller can decide whether to restore a backup.
+    pub fn load(storage: Box<dyn Storage>) -> Result<Self, FeedbackError> {
+        let data = storage.load()?;
+        Ok(Self::with_data(storage, data))
+    }
+
+    pub fn with_data(storage: Box<dyn Storage>, data: FeedbackData) -> Self {
+        Self {
+            feedbacks: data.feedbacks,
+            next_id: data.next_id,
+            storage,
         }
     }
 
+    fn data(&self) -> FeedbackData {
+        FeedbackData {
+            version: CURRENT_VERSION,
+            feedbacks: self.feedbacks.clone(),
+            next_id: self.next_id,
+        }
+    }
+
+    /// Adds an entry and returns its id.
     pub fn add_feedback(
         &mut self,
         customer_name: String,
         comment: String,
         rating: u8,
-    ) -> Result<(), FeedbackError> {
-        if rating < 1 || rating > 5 {
-            return Err(FeedbackError::InvalidRating);
-        }
+    ) -> Result<u32, FeedbackError> {
+        let customer_name = validate_text(&customer_name)?;
+        let comment = validate_text(&comment)?;
+        validate_rating(rating)?;
 
+        let id = self.next_id;
         let feedback = Feedback {
-            id: self.next_id,
+            id,
             customer_name,
             comment,
             rating,
             date_created: Utc::now(),
+            responses: Vec::new(),
+            tags: BTreeSet::new(),
+            status: FollowUpStatus::New,
+            due_date: None,
         };
-
-        self.feedbacks.insert(self.next_id, feedback);
+        self.feedbacks.insert(id, feedback);
         self.next_id += 1;
 
-        // Auto-save after adding
-        persistence::auto_save(&self.feedbacks, self.next_id);
-        Ok(())
+        if let Err(e) = self.save_data() {
+            self.feedbacks.remove(&id);
+            self.next_id = id;
+            return Err(e);
+        }
+        Ok(id)
     }
 
-    pub fn view_all_feedback(&self) {
-        if self.feedbacks.is_empty() {
-            println!("No feedback entries found.");
-            return;
-        }
+    pub fn get(&self, id: u32) -> Result<&Feedback, FeedbackError> {
+        self.feedbacks
+            .get(&id)
+            .ok_or(FeedbackError::FeedbackNotFound)
+    }
 
-        println!("\n=== ALL FEEDBACK ENTRIES ===");
-        for feedback in self.feedbacks.values() {
-            println!("ID: {}", feedback.id);
-            println!("Customer: {}", feedback.customer_name);
-            println!("Rating: {}/5 stars", feedback.rating);
-            println!("Comment: {}", feedback.comment);
-            println!(
-                "Date: {}",
-                feedback.date_created.format("%Y-%m-%d %H:%M:%S UTC")
-            );
-            println!("{}", "-".repeat(40));
-        }
+    /// Every entry, by id.
+    pub fn all_feedback(&self) -> Vec<&Feedback> {
+        let mut feedbacks: Vec<&Feedback> = self.feedbacks.values().collect();
+        feedbacks.sort_by_key(|f| f.id);
+        feedbacks
     }
 
-    pub fn remove_feedback(&mut self, id: u32) -> Result<(), FeedbackError> {
-        match self.feedbacks.remove(&id) {
-            Some(feedback) => {
-                println!("Removed feedback from customer: {}", feedback.customer_name);
-                // Auto-save after removing
-                persistence::auto_save(&self.feedbacks, self.next_id);
-                Ok(())
-            }
-            None => Err(FeedbackError::FeedbackNotFound),
-        }
+    /// The entries matching every criterion set in `filter`, by id.
+    pub fn filter(&self, filter: &FeedbackFilter) -> Vec<&Feedback> {
+        self.all_feedback()
+            .into_iter()
+            .filter(|f| filter.matches(f))
+            .collect()
     }
 
-    pub fn edit_feedback(&mut self, id: u32) -> Result<(), FeedbackError> {
+    /// Removes an entry and returns it.
+    pub fn remove_feedback(&mut self, id: u32) -> Result<Feedback, FeedbackError> {
         let feedback = self
             .feedbacks
-            .get(&id)
+            .remove(&id)
             .ok_or(FeedbackError::FeedbackNotFound)?;
-
-        println!("\nCurrent feedback details:");
-        println!("Customer: {}", feedback.customer_name);
-        println!("Rating: {}/5", feedback.rating);
-        println!("Comment: {}", feedback.comment);
-
-        println!("\nWhat would you like to edit?");
-        println!("1. Customer name");
-        println!("2. Rating");
-        println!("3. Comment");
-        println!("4. Cancel");
-
-        let choice = get_user_input("Enter your choice (1-4): ")?;
-        let choice: u8 = choice
-            .trim()
-            .parse()
-            .map_err(|_| FeedbackError::InvalidInput)?;
-
-        let mut updated_feedback = self.feedbacks[&id].clone();
-
-        match choice {
-            1 => {
-                let prompt = format!(
-                    "Enter new customer name [{}]: ",
-                    updated_feedback.customer_name
-                );
-                let new_name = get_user_input(&prompt)?;
-                if !new_name.trim().is_empty() {
-                    updated_feedback.customer_name = new_name.trim().to_string();
-                }
-            }
-            2 => {
-                let prompt = format!("Enter new rating (1-5) [{}]: ", updated_feedback.rating);
-                let new_rating = get_user_input(&prompt)?;
-                if !new_rating.trim().is_empty() {
-                    let rating: u8 = new_rating
-                        .trim()
-                        .parse()
-                        .map_err(|_| FeedbackError::InvalidInput)?;
-                    if rating < 1 || rating > 5 {
-                        return Err(FeedbackError::InvalidRating);
-                    }
-                    updated_feedback.rating = rating;
-                }
-            }
-            3 => {
-                let prompt = format!("Enter new comment [{}]: ", updated_feedback.comment);
-                let new_comment = get_user_input(&prompt)?;
-                if !new_comment.trim().is_empty() {
-                    updated_feedback.comment = new_comment.trim().to_string();
-                }
-            }
-            4 => {
-                println!("Edit cancelled.");
-                return Ok(());
-            }
-            _ => return Err(FeedbackError::InvalidInput),
+        if let Err(e) = self.save_data() {
+            self.feedbacks.insert(id, feedback);
+            return Err(e);
         }
+        Ok(feedback)
+    }
 
-        // Confirmation step
-        let confirm = get_user_input("Save changes? (y/n): ")?;
-        if confirm.trim().to_lowercase() == "y" || confirm.trim().to_lowercase() == "yes" {
-            self.feedbacks.insert(id, updated_feedback);
-            println!("Feedback updated successfully!");
-            persistence::auto_save(&self.feedbacks, self.next_id);
-        } else {
-            println!("Changes cancelled.");
+    /// Applies every field set in `patch`. Nothing changes unless all of them are valid.
+    pub fn edit(&mut self, id: u32, patch: FeedbackPatch) -> Result<&Feedback, FeedbackError> {
+        let mut updated = self.get(id)?.clone();
+        if let Some(name) = &patch.customer_name {
+            updated.customer_name = validate_text(name)?;
         }
-
-        Ok(())
+        if let Some(comment) = &patch.comment {
+            updated.comment = validate_text(comment)?;
+        }
+        if let Some(rating) = patch.rating {
+            validate_rating(rating)?;
+            updated.rating = rating;
+        }
+        if let Some(status) = patch.status {
+            updated.status = status;
+        }
+        if let Some(due_date) = patch.due_date {
+            updated.due_date = due_date;
+        }
+        if let Some(tags) = &patch.tags {
+            updated.tags = normalize_tags(tags);
+        }
+        self.replace(updated)
     }
 
-    pub fn list_feedback_ids(&self) {
-        if self.feedbacks.is_empty() {
-            println!("No feedback entries available.");
-            return;
+    /// Adds a staff reply. Replying to a `New` entry marks it `Acknowledged`; any other status is
+    /// left for staff to move on themselves.
+    pub fn respond(
+        &mut self,
+        id: u32,
+        author: String,
+        message: String,
+    ) -> Result<&Feedback, FeedbackError> {
+        let mut updated = self.get(id)?.clone();
+        updated.responses.push(StaffResponse {
+            author: validate_text(&author)?,
+            message: validate_text(&message)?,
+            responded_at: Utc::now(),
+        });
+        if updated.status == FollowUpStatus::New {
+            updated.status = FollowUpStatus::Acknowledged;
         }
+        self.replace(updated)
+    }
 
-        println!("\nAvailable feedback IDs:");
-        for (id, feedback) in &self.feedbacks {
-            println!("ID: {} - Customer: {}", id, feedback.customer_name);
+    /// Stores an updated copy of an existing entry, putting the old one back if saving fails.
+    fn replace(&mut self, updated: Feedback) -> Result<&Feedback, FeedbackError> {
+        let id = updated.id;
+        let previous = self.feedbacks.insert(id, updated);
+        if let Err(e) = self.save_data() {
+            if let Some(previous) = previous {
+                self.feedbacks.insert(id, previous);
+            }
+            return Err(e);
         }
+        self.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.feedbacks.len()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -180,6 +178,30 @@ impl FeedbackManager {
     }
 
     pub fn save_data(&self) -> Result<(), FeedbackError> {
-        persistence::save_to_file(&self.feedbacks, self.next_id)
+        self.storage.save(&self.data())
+    }
+}
+
+fn validate_rating(rating: u8) -> Result<(), FeedbackError> {
+    if !(1..=5).contains(&rating) {
+        return Err(FeedbackError::InvalidRating);
+    }
+    Ok(())
+}
+
+/// Tags are trimmed and lowercased so "Bug" and " bug" are the same tag; blank ones are dropped.
+fn normalize_tags(tags: &[String]) -> BTreeSet<String> {
+    tags.iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Trims the text, refusing it if nothing is left.
+fn validate_text(text: &str) -> Result<String, FeedbackError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(FeedbackError::InvalidInput);
     }
+    Ok(text.to_string())
 }
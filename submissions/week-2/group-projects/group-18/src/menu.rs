@@ -1,6 +1,64 @@
+use crate::analytics::{build_report, report_to_json, Sentiment};
 use crate::input::get_user_input;
 use crate::manager::FeedbackManager;
-use crate::models::FeedbackError;
+use crate::models::{Feedback, FeedbackError, FeedbackFilter, FeedbackPatch, FollowUpStatus};
+use crate::persistence::{FeedbackData, Storage};
+use chrono::{NaiveDate, Utc};
+
+/// Loads the manager from `storage`. If the stored data is corrupt, offers to restore the latest
+/// good backup; declining, or having no backup, moves the unreadable file aside and starts empty.
+/// Data from a newer version of the program, a corrupt file that cannot be moved, or a file that
+/// cannot be read at all is never overwritten: the program exits instead.
+pub fn open_manager(storage: Box<dyn Storage>) -> FeedbackManager {
+    let data = match storage.load() {
+        Ok(data) => data,
+        Err(FeedbackError::CorruptData(reason)) => {
+            eprintln!("Warning: The data file is corrupt ({}).", reason);
+            let answer =
+                get_user_input("Restore the latest good backup? (y/n): ").unwrap_or_default();
+            let restored = if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                storage
+                    .restore_latest_backup()
+                    .inspect(|_| eprintln!("Backup restored. The corrupt file was kept beside it."))
+                    .inspect_err(|e| eprintln!("Warning: Could not restore a backup: {:?}", e))
+                    .ok()
+            } else {
+                None
+            };
+            restored.unwrap_or_else(|| {
+                match storage.set_aside_corrupt() {
+                    Ok(Some(aside)) => {
+                        eprintln!("The corrupt file was moved to {}.", aside.display())
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("Error: Could not move the data file aside: {:?}", e);
+                        eprintln!("Move the data file yourself before starting again.");
+                        std::process::exit(1);
+                    }
+                }
+                eprintln!("Starting with empty feedback database.");
+                FeedbackData::empty()
+            })
+        }
+        Err(FeedbackError::UnsupportedVersion(version)) => {
+            eprintln!(
+                "Error: The data file uses format version {}, but this program only reads up to version {}.",
+                version,
+                crate::persistence::CURRENT_VERSION
+            );
+            eprintln!("Please upgrade the program before using this data file.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: Could not load existing data: {:?}", e);
+            eprintln!("The data file was left untouched. Fix or move it before starting again.");
+            std::process::exit(1);
+        }
+    };
+    println!("Loaded {} existing feedback entries.", data.feedbacks.len());
+    FeedbackManager::with_data(storage, data)
+}
 
 pub fn add_feedback_menu(manager: &mut FeedbackManager) {
     println!("\n=== ADD FEEDBACK ===");
@@ -51,7 +109,7 @@ pub fn add_feedback_menu(manager: &mut FeedbackManager) {
 
         rating = match rating_input.trim().parse() {
             Ok(r) => {
-                if r < 1 || r > 5 {
+                if !(1..=5).contains(&r) {
                     println!("Rating must be between 1 and 5.");
                     continue;
                 }
@@ -66,15 +124,73 @@ pub fn add_feedback_menu(manager: &mut FeedbackManager) {
     }
 
     match manager.add_feedback(customer_name, comment, rating) {
-        Ok(()) => println!("Feedback added successfully!"),
-        // Err(FeedbackError::InvalidRating) => println!("Rating must be between 1 and 5."),
+        Ok(_) => println!("Feedback added successfully!"),
+        Err(FeedbackError::PersistenceError(e)) => println!("Failed to save feedback: {}", e),
         Err(_) => println!("Failed to add feedback."),
     }
 }
 
+pub fn view_all_feedback_menu(manager: &FeedbackManager) {
+    if manager.is_empty() {
+        println!("No feedback entries found.");
+        return;
+    }
+
+    println!("\n=== ALL FEEDBACK ENTRIES ===");
+    for feedback in manager.all_feedback() {
+        print_feedback(feedback);
+    }
+}
+
+fn print_feedback(feedback: &Feedback) {
+    println!("ID: {}", feedback.id);
+    println!("Customer: {}", feedback.customer_name);
+    println!("Rating: {}/5 stars", feedback.rating);
+    println!("Comment: {}", feedback.comment);
+    println!(
+        "Date: {}",
+        feedback.date_created.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("Status: {}", feedback.status.label());
+    if !feedback.tags.is_empty() {
+        let tags: Vec<&str> = feedback.tags.iter().map(String::as_str).collect();
+        println!("Tags: {}", tags.join(", "));
+    }
+    if let Some(due_date) = feedback.due_date {
+        let overdue =
+            feedback.status != FollowUpStatus::Closed && due_date < Utc::now().date_naive();
+        println!(
+            "Due: {}{}",
+            due_date.format("%Y-%m-%d"),
+            if overdue { " (overdue)" } else { "" }
+        );
+    }
+    for response in &feedback.responses {
+        println!(
+            "  Reply from {} on {}: {}",
+            response.author,
+            response.responded_at.format("%Y-%m-%d %H:%M UTC"),
+            response.message
+        );
+    }
+    println!("{}", "-".repeat(40));
+}
+
+fn list_feedback_ids(manager: &FeedbackManager) {
+    if manager.is_empty() {
+        println!("No feedback entries available.");
+        return;
+    }
+
+    println!("\nAvailable feedback IDs:");
+    for feedback in manager.all_feedback() {
+        println!("ID: {} - Customer: {}", feedback.id, feedback.customer_name);
+    }
+}
+
 pub fn remove_feedback_menu(manager: &mut FeedbackManager) {
     println!("\n=== REMOVE FEEDBACK ===");
-    manager.list_feedback_ids();
+    list_feedback_ids(manager);
 
     if manager.is_empty() {
         return;
@@ -97,7 +213,10 @@ pub fn remove_feedback_menu(manager: &mut FeedbackManager) {
     };
 
     match manager.remove_feedback(id) {
-        Ok(()) => println!("Feedback removed successfully!"),
+        Ok(feedback) => {
+            println!("Removed feedback from customer: {}", feedback.customer_name);
+            println!("Feedback removed successfully!");
+        }
         Err(FeedbackError::FeedbackNotFound) => println!("Feedback with ID {} not found.", id),
         Err(_) => println!("Failed to remove feedback."),
     }
@@ -105,7 +224,7 @@ pub fn remove_feedback_menu(manager: &mut FeedbackManager) {
 
 pub fn edit_feedback_menu(manager: &mut FeedbackManager) {
     println!("\n=== EDIT FEEDBACK ===");
-    manager.list_feedback_ids();
+    list_feedback_ids(manager);
 
     if manager.is_empty() {
         return;
@@ -127,11 +246,339 @@ pub fn edit_feedback_menu(manager: &mut FeedbackManager) {
         }
     };
 
-    match manager.edit_feedback(id) {
-        Ok(()) => {}
-        Err(FeedbackError::FeedbackNotFound) => println!("Feedback with ID {} not found.", id),
-        Err(FeedbackError::InvalidRating) => println!("Rating must be between 1 and 5."),
-        Err(FeedbackError::InvalidInput) => println!("Invalid input provided."),
-        Err(FeedbackError::PersistenceError(_)) => println!("Storage failed"),
+    let patch = match prompt_feedback_patch(manager, id) {
+        Ok(Some(patch)) => patch,
+        Ok(None) => return,
+        Err(e) => return print_edit_error(id, e),
+    };
+
+    match manager.edit(id, patch) {
+        Ok(_) => println!("Feedback updated successfully!"),
+        Err(e) => print_edit_error(id, e),
+    }
+}
+
+fn print_edit_error(id: u32, error: FeedbackError) {
+    match error {
+        FeedbackError::FeedbackNotFound => println!("Feedback with ID {} not found.", id),
+        FeedbackError::InvalidRating => println!("Rating must be between 1 and 5."),
+        FeedbackError::InvalidInput => println!("Invalid input provided."),
+        FeedbackError::PersistenceError(_)
+        | FeedbackError::CorruptData(_)
+        | FeedbackError::UnsupportedVersion(_) => println!("Storage failed"),
+    }
+}
+
+/// Asks which field to change and its new value. `None` means the user cancelled.
+fn prompt_feedback_patch(
+    manager: &FeedbackManager,
+    id: u32,
+) -> Result<Option<FeedbackPatch>, FeedbackError> {
+    let feedback = manager.get(id)?;
+
+    println!("\nCurrent feedback details:");
+    println!("Customer: {}", feedback.customer_name);
+    println!("Rating: {}/5", feedback.rating);
+    println!("Comment: {}", feedback.comment);
+
+    println!("\nWhat would you like to edit?");
+    println!("1. Customer name");
+    println!("2. Rating");
+    println!("3. Comment");
+    println!("4. Cancel");
+
+    let choice = get_user_input("Enter your choice (1-4): ")?;
+    let choice: u8 = choice
+        .trim()
+        .parse()
+        .map_err(|_| FeedbackError::InvalidInput)?;
+
+    let mut patch = FeedbackPatch::default();
+    match choice {
+        1 => {
+            let prompt = format!("Enter new customer name [{}]: ", feedback.customer_name);
+            let new_name = get_user_input(&prompt)?;
+            if !new_name.trim().is_empty() {
+                patch.customer_name = Some(new_name.trim().to_string());
+            }
+        }
+        2 => {
+            let prompt = format!("Enter new rating (1-5) [{}]: ", feedback.rating);
+            let new_rating = get_user_input(&prompt)?;
+            if !new_rating.trim().is_empty() {
+                let rating: u8 = new_rating
+                    .trim()
+                    .parse()
+                    .map_err(|_| FeedbackError::InvalidInput)?;
+                patch.rating = Some(rating);
+            }
+        }
+        3 => {
+            let prompt = format!("Enter new comment [{}]: ", feedback.comment);
+            let new_comment = get_user_input(&prompt)?;
+            if !new_comment.trim().is_empty() {
+                patch.comment = Some(new_comment.trim().to_string());
+            }
+        }
+        4 => {
+            println!("Edit cancelled.");
+            return Ok(None);
+        }
+        _ => return Err(FeedbackError::InvalidInput),
+    }
+
+    // Confirmation step
+    let confirm = get_user_input("Save changes? (y/n): ")?;
+    if confirm.trim().to_lowercase() == "y" || confirm.trim().to_lowercase() == "yes" {
+        Ok(Some(patch))
+    } else {
+        println!("Changes cancelled.");
+        Ok(None)
+    }
+}
+
+pub fn follow_up_menu(manager: &mut FeedbackManager) {
+    println!("\n=== FOLLOW UP ===");
+    list_feedback_ids(manager);
+
+    if manager.is_empty() {
+        return;
+    }
+
+    let id_input = match get_user_input("Enter feedback ID to follow up: ") {
+        Ok(input) => input,
+        Err(_) => {
+            println!("Error reading input.");
+            return;
+        }
+    };
+
+    let id: u32 = match id_input.trim().parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Please enter a valid feedback ID.");
+            return;
+        }
+    };
+
+    let result = match prompt_follow_up(manager, id) {
+        Ok(Some(result)) => result,
+        Ok(None) => return,
+        Err(e) => return print_edit_error(id, e),
+    };
+
+    let outcome = match result {
+        FollowUp::Respond { author, message } => manager.respond(id, author, message),
+        FollowUp::Edit(patch) => manager.edit(id, patch),
+    };
+    match outcome {
+        Ok(feedback) => {
+            println!("Feedback updated successfully!");
+            print_feedback(feedback);
+        }
+        Err(e) => print_edit_error(id, e),
+    }
+}
+
+enum FollowUp {
+    Respond { author: String, message: String },
+    Edit(FeedbackPatch),
+}
+
+/// Asks what to do with the entry. `None` means the user cancelled.
+fn prompt_follow_up(manager: &FeedbackManager, id: u32) -> Result<Option<FollowUp>, FeedbackError> {
+    let feedback = manager.get(id)?;
+    print_feedback(feedback);
+
+    println!("\nWhat would you like to do?");
+    println!("1. Respond");
+    println!("2. Set status");
+    println!("3. Set tags");
+    println!("4. Set due date");
+    println!("5. Cancel");
+
+    let choice = get_user_input("Enter your choice (1-5): ")?;
+    let choice: u8 = choice
+        .trim()
+        .parse()
+        .map_err(|_| FeedbackError::InvalidInput)?;
+
+    let mut patch = FeedbackPatch::default();
+    match choice {
+        1 => {
+            let author = get_user_input("Your name: ")?;
+            let message = get_user_input("Response: ")?;
+            return Ok(Some(FollowUp::Respond { author, message }));
+        }
+        2 => {
+            let prompt = format!(
+                "New status (New, Acknowledged, Actioned, Closed) [{}]: ",
+                feedback.status.label()
+            );
+            let status = get_user_input(&prompt)?;
+            patch.status = Some(FollowUpStatus::parse(&status).ok_or(FeedbackError::InvalidInput)?);
+        }
+        3 => {
+            let current: Vec<&str> = feedback.tags.iter().map(String::as_str).collect();
+            let prompt = format!(
+                "Tags, comma separated; blank clears them [{}]: ",
+                current.join(", ")
+            );
+            let tags = get_user_input(&prompt)?;
+            patch.tags = Some(tags.split(',').map(str::to_string).collect());
+        }
+        4 => {
+            let due_date = get_user_input("Due date (YYYY-MM-DD); blank clears it: ")?;
+            patch.due_date = Some(parse_optional_date(&due_date)?);
+        }
+        5 => {
+            println!("Follow up cancelled.");
+            return Ok(None);
+        }
+        _ => return Err(FeedbackError::InvalidInput),
+    }
+    Ok(Some(FollowUp::Edit(patch)))
+}
+
+/// A blank answer is `None`; anything else must be a `YYYY-MM-DD` date.
+fn parse_optional_date(input: &str) -> Result<Option<NaiveDate>, FeedbackError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| FeedbackError::InvalidInput)
+}
+
+/// Like `parse_optional_date`, for ratings.
+fn parse_optional_rating(input: &str) -> Result<Option<u8>, FeedbackError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    match input.parse() {
+        Ok(rating) if (1..=5).contains(&rating) => Ok(Some(rating)),
+        _ => Err(FeedbackError::InvalidRating),
+    }
+}
+
+/// Asks for each criterion in turn; a blank answer leaves it unset.
+fn prompt_feedback_filter() -> Result<FeedbackFilter, FeedbackError> {
+    let tag = get_user_input("Tag: ")?;
+    let status = get_user_input("Status (New, Acknowledged, Actioned, Closed): ")?;
+    let status = match status.trim() {
+        "" => None,
+        status => Some(FollowUpStatus::parse(status).ok_or(FeedbackError::InvalidInput)?),
+    };
+    Ok(FeedbackFilter {
+        tag: Some(tag.trim().to_string()).filter(|tag| !tag.is_empty()),
+        status,
+        min_rating: parse_optional_rating(&get_user_input("Minimum rating (1-5): ")?)?,
+        max_rating: parse_optional_rating(&get_user_input("Maximum rating (1-5): ")?)?,
+        from: parse_optional_date(&get_user_input("Created on or after (YYYY-MM-DD): ")?)?,
+        to: parse_optional_date(&get_user_input("Created on or before (YYYY-MM-DD): ")?)?,
+    })
+}
+
+pub fn filter_feedback_menu(manager: &FeedbackManager) {
+    println!("\n=== FILTER FEEDBACK ===");
+    println!("Leave a field blank to match everything.");
+
+    let filter = match prompt_feedback_filter() {
+        Ok(filter) => filter,
+        Err(FeedbackError::InvalidRating) => {
+            println!("Rating must be between 1 and 5.");
+            return;
+        }
+        Err(_) => {
+            println!("Invalid input provided.");
+            return;
+        }
+    };
+
+    let matches = manager.filter(&filter);
+    if matches.is_empty() {
+        println!("No feedback entries match.");
+        return;
+    }
+    println!("\n{} matching entries:", matches.len());
+    for feedback in matches {
+        print_feedback(feedback);
+    }
+}
+
+pub fn analytics_menu(manager: &FeedbackManager) {
+    println!("\n=== FEEDBACK ANALYTICS ===");
+    if manager.is_empty() {
+        println!("No feedback entries to analyse.");
+        return;
+    }
+
+    let report = build_report(&manager.all_feedback(), Utc::now());
+
+    println!("\nRatings:");
+    for summary in &report.ratings {
+        let average = match summary.average {
+            Some(average) => format!("{:.2}", average),
+            None => "-".to_string(),
+        };
+        println!(
+            "  {:<13} {:>4} entries, average {:>4}  1*:{} 2*:{} 3*:{} 4*:{} 5*:{}",
+            summary.window.label(),
+            summary.count,
+            average,
+            summary.distribution[0],
+            summary.distribution[1],
+            summary.distribution[2],
+            summary.distribution[3],
+            summary.distribution[4]
+        );
+    }
+
+    let count = |sentiment: Sentiment| {
+        report
+            .sentiments
+            .iter()
+            .filter(|s| s.sentiment.sentiment == sentiment)
+            .count()
+    };
+    println!(
+        "\nSentiment: {} positive, {} neutral, {} negative",
+        count(Sentiment::Positive),
+        count(Sentiment::Neutral),
+        count(Sentiment::Negative)
+    );
+
+    println!("\nTop keywords:");
+    for term in &report.top_keywords {
+        println!("  {:<20} {}", term.term, term.count);
+    }
+    println!("\nTop word pairs:");
+    for term in &report.top_bigrams {
+        println!("  {:<30} {}", term.term, term.count);
+    }
+
+    if !report.mismatches.is_empty() {
+        println!("\nRating and comment disagree:");
+        for mismatch in &report.mismatches {
+            println!(
+                "  ID {} ({}): {}/5 stars but the comment reads {:?}",
+                mismatch.id, mismatch.customer_name, mismatch.rating, mismatch.sentiment.sentiment
+            );
+        }
+    }
+
+    let choice = get_user_input("\nExport as JSON to a file? Enter a path or leave blank: ");
+    let path = match choice {
+        Ok(path) if !path.trim().is_empty() => path.trim().to_string(),
+        _ => return,
+    };
+    let result = report_to_json(&report)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => println!("Analytics exported to {}.", path),
+        Err(e) => println!("Failed to export analytics: {}", e),
     }
 }
@@ -1,19 +1,111 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Feedback {
     pub id: u32,
     pub customer_name: String,
     pub comment: String,
     pub rating: u8,
     pub date_created: DateTime<Utc>,
+    /// Replies from staff, oldest first.
+    pub responses: Vec<StaffResponse>,
+    /// Lowercase labels such as "bug", "praise" or "pricing".
+    pub tags: BTreeSet<String>,
+    pub status: FollowUpStatus,
+    /// When the follow-up should be done by, if anyone has set a date.
+    pub due_date: Option<NaiveDate>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaffResponse {
+    pub author: String,
+    pub message: String,
+    pub responded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FollowUpStatus {
+    #[default]
+    New,
+    Acknowledged,
+    Actioned,
+    Closed,
+}
+
+impl FollowUpStatus {
+    pub const ALL: [FollowUpStatus; 4] = [
+        FollowUpStatus::New,
+        FollowUpStatus::Acknowledged,
+        FollowUpStatus::Actioned,
+        FollowUpStatus::Closed,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FollowUpStatus::New => "New",
+            FollowUpStatus::Acknowledged => "Acknowledged",
+            FollowUpStatus::Actioned => "Actioned",
+            FollowUpStatus::Closed => "Closed",
+        }
+    }
+
+    /// Parses a label, ignoring case.
+    pub fn parse(text: &str) -> Option<FollowUpStatus> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.label().eq_ignore_ascii_case(text.trim()))
+    }
+}
+
+/// Fields to change on an existing entry; `None` keeps the current value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedbackPatch {
+    pub customer_name: Option<String>,
+    pub comment: Option<String>,
+    pub rating: Option<u8>,
+    pub status: Option<FollowUpStatus>,
+    /// `Some(None)` clears the due date.
+    pub due_date: Option<Option<NaiveDate>>,
+    /// Replaces every tag.
+    pub tags: Option<Vec<String>>,
+}
+
+/// Which entries to show. Every criterion that is set must match; ranges include both ends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedbackFilter {
+    pub tag: Option<String>,
+    pub status: Option<FollowUpStatus>,
+    pub min_rating: Option<u8>,
+    pub max_rating: Option<u8>,
+    /// Compared with the UTC date the feedback was created.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl FeedbackFilter {
+    pub fn matches(&self, feedback: &Feedback) -> bool {
+        let created = feedback.date_created.date_naive();
+        self.tag
+            .as_ref()
+            .is_none_or(|tag| feedback.tags.contains(&tag.trim().to_lowercase()))
+            && self.status.is_none_or(|status| feedback.status == status)
+            && self.min_rating.is_none_or(|min| feedback.rating >= min)
+            && self.max_rating.is_none_or(|max| feedback.rating <= max)
+            && self.from.is_none_or(|from| created >= from)
+            && self.to.is_none_or(|to| created <= to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum FeedbackError {
     InvalidRating,
     FeedbackNotFound,
     InvalidInput,
     PersistenceError(String),
+    /// The data file exists but could not be parsed.
+    CorruptData(String),
+    /// The data file was written by a newer version of the program.
+    UnsupportedVersion(u32),
 }
@@ -1,58 +1,322 @@
-use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, BufWriter, Write};
-use std::path::Path;
 use crate::models::{Feedback, FeedbackError};
-use serde_json;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-const DATA_FILE: &str = "feedback_data.json";
+pub const DEFAULT_DATA_FILE: &str = "feedback_data.json";
+pub const DATA_FILE_ENV: &str = "FEEDBACK_DATA_FILE";
+pub const DATA_FILE_FLAG: &str = "--data-file";
+const DEFAULT_MAX_BACKUPS: usize = 5;
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct FeedbackData {
-    feedbacks: HashMap<u32, Feedback>,
-    next_id: u32,
+/// The format written by this build. Files without a `version` field are version 1, from before
+/// feedback had responses, tags, a follow-up status or a due date.
+pub const CURRENT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FeedbackData {
+    pub version: u32,
+    pub feedbacks: HashMap<u32, Feedback>,
+    pub next_id: u32,
 }
 
-pub fn save_to_file(feedbacks: &HashMap<u32, Feedback>, next_id: u32) -> Result<(), FeedbackError> {
-    let data = FeedbackData {
-        feedbacks: feedbacks.clone(),
-        next_id,
-    };
+impl FeedbackData {
+    pub fn empty() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            feedbacks: HashMap::new(),
+            next_id: 1,
+        }
+    }
+}
+
+/// Somewhere the whole feedback database can be saved to and loaded from.
+pub trait Storage {
+    /// Returns `FeedbackData::empty()` when nothing has been saved yet, and
+    /// `FeedbackError::CorruptData` when something was saved but cannot be read back.
+    fn load(&self) -> Result<FeedbackData, FeedbackError>;
+    fn save(&self, data: &FeedbackData) -> Result<(), FeedbackError>;
+    /// Loads the newest backup that can be read and makes it the current data.
+    fn restore_latest_backup(&self) -> Result<FeedbackData, FeedbackError>;
+    /// Moves unreadable stored data out of the way so the next save cannot overwrite it, and
+    /// returns where it went. Storage that cannot hold corrupt data has nothing to move.
+    fn set_aside_corrupt(&self) -> Result<Option<PathBuf>, FeedbackError> {
+        Ok(None)
+    }
+}
+
+/// A JSON file, replaced atomically on every save. The previous version is copied into a
+/// `backups` directory next to it first, keeping the newest `max_backups` copies.
+pub struct FileStorage {
+    path: PathBuf,
+    max_backups: usize,
+}
+
+impl FileStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_backups: DEFAULT_MAX_BACKUPS,
+        }
+    }
+
+    pub fn with_max_backups(mut self, max_backups: usize) -> Self {
+        self.max_backups = max_backups;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn backup_dir(&self) -> PathBuf {
+        self.path.parent().unwrap_or(Path::new("")).join("backups")
+    }
+
+    /// Backups of this data file, newest first. Their names start with the data file's stem and
+    /// end with a UTC timestamp, so they sort by age.
+    pub fn backups(&self) -> Result<Vec<PathBuf>, FeedbackError> {
+        let dir = self.backup_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let prefix = format!("{}-", self.file_stem());
+        let entries = fs::read_dir(&dir).map_err(|e| {
+            FeedbackError::PersistenceError(format!("Failed to read {}: {}", dir.display(), e))
+        })?;
+        let mut backups: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".json"))
+            })
+            .collect();
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    fn file_stem(&self) -> String {
+        self.path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("feedback_data")
+            .to_string()
+    }
+
+    /// Copies the current file into the backup directory, unless it is missing or unreadable:
+    /// a corrupt file must never push a good backup out of the rotation.
+    fn back_up_current(&self) -> Result<(), FeedbackError> {
+        if read_data(&self.path).is_err() {
+            return Ok(());
+        }
+        let dir = self.backup_dir();
+        fs::create_dir_all(&dir).map_err(|e| {
+            FeedbackError::PersistenceError(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.6fZ");
+        let mut backup = dir.join(format!("{}-{}.json", self.file_stem(), stamp));
+        let mut copy = 1;
+        while backup.exists() {
+            backup = dir.join(format!("{}-{}-{}.json", self.file_stem(), stamp, copy));
+            copy += 1;
+        }
+        fs::copy(&self.path, &backup).map_err(|e| {
+            FeedbackError::PersistenceError(format!("Failed to back up data: {}", e))
+        })?;
+
+        for old in self.backups()?.iter().skip(self.max_backups) {
+            // A backup that cannot be pruned now is retried on the next save
+            let _ = fs::remove_file(old);
+        }
+        Ok(())
+    }
+}
 
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(DATA_FILE)
-        .map_err(|e| FeedbackError::PersistenceError(format!("Failed to create file: {}", e)))?;
+impl Storage for FileStorage {
+    fn load(&self) -> Result<FeedbackData, FeedbackError> {
+        if !self.path.exists() {
+            return Ok(FeedbackData::empty());
+        }
+        read_data(&self.path)
+    }
+
+    fn save(&self, data: &FeedbackData) -> Result<(), FeedbackError> {
+        let json = serde_json::to_string_pretty(data).map_err(|e| {
+            FeedbackError::PersistenceError(format!("Failed to encode data: {}", e))
+        })?;
+        self.back_up_current()?;
+        write_atomically(&self.path, json.as_bytes())
+    }
+
+    /// The file being replaced is moved aside first, as `set_aside_corrupt` does, so it is kept.
+    fn restore_latest_backup(&self) -> Result<FeedbackData, FeedbackError> {
+        for backup in self.backups()? {
+            if let Ok(data) = read_data(&backup) {
+                let contents = fs::read(&backup).map_err(|e| {
+                    FeedbackError::PersistenceError(format!("Failed to read backup: {}", e))
+                })?;
+                self.set_aside_corrupt()?;
+                write_atomically(&self.path, &contents)?;
+                return Ok(data);
+            }
+        }
+        Err(FeedbackError::PersistenceError(
+            "No readable backup found".to_string(),
+        ))
+    }
+
+    /// Renames the data file to `<stem>.corrupt-<UTC timestamp>.json` beside it.
+    fn set_aside_corrupt(&self) -> Result<Option<PathBuf>, FeedbackError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.6fZ");
+        let aside =
+            self.path
+                .with_file_name(format!("{}.corrupt-{}.json", self.file_stem(), stamp));
+        fs::rename(&self.path, &aside).map_err(|e| {
+            FeedbackError::PersistenceError(format!(
+                "Failed to move {} aside: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+        Ok(Some(aside))
+    }
+}
+
+/// Keeps the last saved data in memory, for tests and for embedding the manager without a file.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    saved: RefCell<Option<FeedbackData>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_data(data: FeedbackData) -> Self {
+        Self {
+            saved: RefCell::new(Some(data)),
+        }
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self) -> Result<FeedbackData, FeedbackError> {
+        Ok(self
+            .saved
+            .borrow()
+            .clone()
+            .unwrap_or_else(FeedbackData::empty))
+    }
 
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, &data)
-        .map_err(|e| FeedbackError::PersistenceError(format!("Failed to write data: {}", e)))?;
+    fn save(&self, data: &FeedbackData) -> Result<(), FeedbackError> {
+        *self.saved.borrow_mut() = Some(data.clone());
+        Ok(())
+    }
+
+    fn restore_latest_backup(&self) -> Result<FeedbackData, FeedbackError> {
+        Err(FeedbackError::PersistenceError(
+            "Memory storage keeps no backups".to_string(),
+        ))
+    }
+}
 
-    println!("Data saved successfully to {}", DATA_FILE);
-    Ok(())
+/// A file that can be opened but is not UTF-8, such as one ending in junk bytes after a partial
+/// write, is corrupt data rather than a storage failure.
+fn read_data(path: &Path) -> Result<FeedbackData, FeedbackError> {
+    let bytes = fs::read(path).map_err(|e| {
+        FeedbackError::PersistenceError(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+    let contents = String::from_utf8(bytes).map_err(|e| {
+        FeedbackError::CorruptData(format!("{}: not valid UTF-8: {}", path.display(), e))
+    })?;
+    parse_data(&contents).map_err(|e| match e {
+        FeedbackError::CorruptData(reason) => {
+            FeedbackError::CorruptData(format!("{}: {}", path.display(), reason))
+        }
+        other => other,
+    })
 }
 
-pub fn load_from_file() -> Result<(HashMap<u32, Feedback>, u32), FeedbackError> {
-    if !Path::new(DATA_FILE).exists() {
-        // If file doesn't exist, return empty data
-        return Ok((HashMap::new(), 1));
+/// Reads saved JSON of any known version, upgrading it to `CURRENT_VERSION` in memory. The file
+/// itself is only rewritten, in the current format, on the next save.
+pub fn parse_data(json: &str) -> Result<FeedbackData, FeedbackError> {
+    let corrupt = |e: serde_json::Error| FeedbackError::CorruptData(e.to_string());
+    let mut value: Value = serde_json::from_str(json).map_err(corrupt)?;
+    let version = match value.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| FeedbackError::CorruptData(format!("invalid version {}", version)))?,
+    };
+    if version > CURRENT_VERSION {
+        return Err(FeedbackError::UnsupportedVersion(version));
+    }
+    if version < 2 {
+        migrate_v1_to_v2(&mut value);
     }
+    serde_json::from_value(value).map_err(corrupt)
+}
 
-    let file = File::open(DATA_FILE)
-        .map_err(|e| FeedbackError::PersistenceError(format!("Failed to open file: {}", e)))?;
+/// Version 2 added responses, tags, a follow-up status and a due date to every entry.
+fn migrate_v1_to_v2(value: &mut Value) {
+    if let Some(feedbacks) = value.get_mut("feedbacks").and_then(Value::as_object_mut) {
+        for feedback in feedbacks.values_mut().filter_map(Value::as_object_mut) {
+            feedback.entry("responses").or_insert_with(|| json!([]));
+            feedback.entry("tags").or_insert_with(|| json!([]));
+            feedback.entry("status").or_insert_with(|| json!("New"));
+            feedback.entry("due_date").or_insert(Value::Null);
+        }
+    }
+    if let Some(data) = value.as_object_mut() {
+        data.insert("version".to_string(), json!(2));
+    }
+}
 
-    let reader = BufReader::new(file);
-    let data: FeedbackData = serde_json::from_reader(reader)
-        .map_err(|e| FeedbackError::PersistenceError(format!("Failed to parse data: {}", e)))?;
+/// Writes to a temporary file beside `path` and renames it over `path`, so a crash part way
+/// through leaves the old file in place rather than a truncated one.
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), FeedbackError> {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp = PathBuf::from(temp_name);
 
-    println!("Data loaded successfully from {}", DATA_FILE);
-    Ok((data.feedbacks, data.next_id))
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        FeedbackError::PersistenceError(format!("Failed to write {}: {}", path.display(), e))
+    })
 }
 
-pub fn auto_save(feedbacks: &HashMap<u32, Feedback>, next_id: u32) {
-    if let Err(e) = save_to_file(feedbacks, next_id) {
-        eprintln!("Warning: Failed to auto-save data: {:?}", e);
+/// The data file named by `--data-file <path>` (or `--data-file=<path>`), then by the
+/// `FEEDBACK_DATA_FILE` environment variable, then `feedback_data.json`.
+pub fn resolve_data_path(args: &[String], env_value: Option<String>) -> PathBuf {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_FILE_FLAG {
+            if let Some(path) = args.next() {
+                return PathBuf::from(path);
+            }
+        } else if let Some(path) = arg.strip_prefix(&format!("{}=", DATA_FILE_FLAG)) {
+            return PathBuf::from(path);
+        }
     }
+    env_value
+        .filter(|value| !value.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_FILE))
 }
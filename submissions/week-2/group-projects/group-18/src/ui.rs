@@ -11,7 +11,10 @@ pub fn display_menu() {
     println!("3. Remove Feedback");
     println!("4. Edit Feedback");
     println!("5. Save Data");
-    println!("6. Exit");
+    println!("6. Analytics");
+    println!("7. Follow Up");
+    println!("8. Filter Feedback");
+    println!("9. Exit");
 }
 
 pub fn display_goodbye() {
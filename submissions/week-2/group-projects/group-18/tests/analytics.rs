@@ -0,0 +1,205 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use customer_feedback_logger::analytics::{
+    build_report, is_mismatch, rating_summary, report_to_json, score_sentiment, top_bigrams,
+    top_keywords, Sentiment, SentimentScore, TermCount, TimeWindow,
+};
+use customer_feedback_logger::models::{Feedback, FollowUpStatus};
+use std::collections::BTreeSet;
+
+/// Every test measures time from here, so window edges do not depend on the clock.
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()
+}
+
+fn feedback(id: u32, rating: u8, comment: &str, age: Duration) -> Feedback {
+    Feedback {
+        id,
+        customer_name: format!("Customer {}", id),
+        comment: comment.to_string(),
+        rating,
+        date_created: now() - age,
+        responses: Vec::new(),
+        tags: BTreeSet::new(),
+        status: FollowUpStatus::New,
+        due_date: None,
+    }
+}
+
+fn terms(counts: &[TermCount]) -> Vec<(&str, usize)> {
+    counts.iter().map(|t| (t.term.as_str(), t.count)).collect()
+}
+
+fn sentiment(sentiment: Sentiment) -> SentimentScore {
+    let score = match sentiment {
+        Sentiment::Positive => 1,
+        Sentiment::Neutral => 0,
+        Sentiment::Negative => -1,
+    };
+    SentimentScore { score, sentiment }
+}
+
+#[test]
+fn test_score_sentiment() {
+    let score = score_sentiment("Great service, very helpful!");
+    assert_eq!((score.score, score.sentiment), (2, Sentiment::Positive));
+
+    let score = score_sentiment("Slow delivery and a rude driver");
+    assert_eq!((score.score, score.sentiment), (-2, Sentiment::Negative));
+
+    let score = score_sentiment("It arrived on Tuesday");
+    assert_eq!((score.score, score.sentiment), (0, Sentiment::Neutral));
+
+    // Mixed comments cancel out
+    assert_eq!(
+        score_sentiment("Good food, bad parking").sentiment,
+        Sentiment::Neutral
+    );
+}
+
+#[test]
+fn test_negation_flips_the_next_word() {
+    assert_eq!(score_sentiment("not good").score, -1);
+    assert_eq!(score_sentiment("NOT GOOD").sentiment, Sentiment::Negative);
+    assert_eq!(score_sentiment("Delivery was not slow").score, 1);
+    // Apostrophes are dropped, so "don't" is a negator
+    assert_eq!(score_sentiment("I don't love it").score, -1);
+    // Only the word straight after the negator is flipped
+    assert_eq!(score_sentiment("Not the best, not bad").score, 2);
+    assert_eq!(
+        score_sentiment("No problem, great app").sentiment,
+        Sentiment::Positive
+    );
+}
+
+#[test]
+fn test_is_mismatch() {
+    let negative = sentiment(Sentiment::Negative);
+    let positive = sentiment(Sentiment::Positive);
+    let neutral = sentiment(Sentiment::Neutral);
+
+    assert!(is_mismatch(5, &negative));
+    assert!(is_mismatch(4, &negative));
+    assert!(!is_mismatch(3, &negative));
+    assert!(!is_mismatch(1, &negative));
+
+    assert!(is_mismatch(1, &positive));
+    assert!(is_mismatch(2, &positive));
+    assert!(!is_mismatch(3, &positive));
+    assert!(!is_mismatch(5, &positive));
+
+    assert!((1..=5).all(|rating| !is_mismatch(rating, &neutral)));
+}
+
+#[test]
+fn test_top_keywords() {
+    let comments = [
+        "The delivery was late",
+        "Late again, 2 days late",
+        "Friendly delivery driver",
+    ];
+    assert_eq!(
+        terms(&top_keywords(&comments, 3)),
+        vec![("late", 3), ("delivery", 2), ("again", 1)]
+    );
+    assert!(top_keywords(&[], 5).is_empty());
+}
+
+#[test]
+fn test_top_bigrams() {
+    let comments = [
+        "Delivery was slow. Support was great",
+        "delivery slow again",
+    ];
+    assert_eq!(
+        terms(&top_bigrams(&comments, 10)),
+        vec![
+            ("delivery slow", 2),
+            ("slow again", 1),
+            ("support great", 1)
+        ]
+    );
+    assert_eq!(
+        terms(&top_bigrams(&comments, 1)),
+        vec![("delivery slow", 2)]
+    );
+}
+
+#[test]
+fn test_bigrams_never_cross_sentences_or_comments() {
+    let bigrams = top_bigrams(&["Too slow! Refund please; app crashed?", "Terrible"], 10);
+    assert_eq!(
+        terms(&bigrams),
+        vec![("app crashed", 1), ("refund please", 1)]
+    );
+    // Sentences of a single word give no pairs
+    assert!(top_bigrams(&["Great. Thanks!", "Slow"], 10).is_empty());
+}
+
+#[test]
+fn test_rating_summary_window_edges() {
+    let feedbacks = [
+        feedback(1, 5, "Great", Duration::zero()),
+        feedback(2, 4, "Good", Duration::days(7)),
+        feedback(3, 1, "Bad", Duration::days(7) + Duration::seconds(1)),
+        feedback(4, 2, "Poor", Duration::days(30)),
+        feedback(5, 3, "Fine", Duration::days(90) + Duration::seconds(1)),
+    ];
+    let feedbacks: Vec<&Feedback> = feedbacks.iter().collect();
+
+    // Exactly seven days old is still inside the window; one second more is not
+    let week = rating_summary(&feedbacks, TimeWindow::Last7Days, now());
+    assert_eq!(week.count, 2);
+    assert_eq!(week.average, Some(4.5));
+    assert_eq!(week.distribution, [0, 0, 0, 1, 1]);
+
+    let month = rating_summary(&feedbacks, TimeWindow::Last30Days, now());
+    assert_eq!(month.count, 4);
+    assert_eq!(month.average, Some(3.0));
+    assert_eq!(month.distribution, [1, 1, 0, 1, 1]);
+
+    assert_eq!(
+        rating_summary(&feedbacks, TimeWindow::Last90Days, now()).count,
+        4
+    );
+    let all = rating_summary(&feedbacks, TimeWindow::AllTime, now());
+    assert_eq!(all.count, 5);
+    assert_eq!(all.distribution, [1, 1, 1, 1, 1]);
+
+    let empty = rating_summary(&[], TimeWindow::Last7Days, now());
+    assert_eq!((empty.count, empty.average), (0, None));
+    assert_eq!(empty.window, TimeWindow::Last7Days);
+}
+
+#[test]
+fn test_report_to_json() {
+    let feedbacks = [
+        feedback(2, 5, "Terrible app, it crashed", Duration::days(3)),
+        feedback(1, 4, "Great support", Duration::days(40)),
+    ];
+    let feedbacks: Vec<&Feedback> = feedbacks.iter().collect();
+    let report = build_report(&feedbacks, now());
+
+    let json: serde_json::Value = serde_json::from_str(&report_to_json(&report).unwrap()).unwrap();
+    assert_eq!(json["generated_at"], "2025-06-01T12:00:00Z");
+
+    let ratings = json["ratings"].as_array().unwrap();
+    assert_eq!(ratings.len(), TimeWindow::ALL.len());
+    assert_eq!(ratings[0]["window"], "Last7Days");
+    assert_eq!(ratings[0]["count"], 1);
+    assert_eq!(ratings[3]["average"], 4.5);
+    assert_eq!(
+        ratings[3]["distribution"],
+        serde_json::json!([0, 0, 0, 1, 1])
+    );
+
+    // Sentiments are listed by id whatever order the feedback came in
+    assert_eq!(json["sentiments"][0]["id"], 1);
+    assert_eq!(json["sentiments"][1]["sentiment"]["sentiment"], "Negative");
+    assert_eq!(json["sentiments"][1]["sentiment"]["score"], -2);
+
+    let mismatches = json["mismatches"].as_array().unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0]["id"], 2);
+    assert_eq!(mismatches[0]["customer_name"], "Customer 2");
+    assert_eq!(json["top_bigrams"][0]["term"], "app crashed");
+}
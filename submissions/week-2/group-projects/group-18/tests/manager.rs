@@ -0,0 +1,317 @@
+use chrono::{NaiveDate, Utc};
+use customer_feedback_logger::manager::FeedbackManager;
+use customer_feedback_logger::models::{
+    FeedbackError, FeedbackFilter, FeedbackPatch, FollowUpStatus,
+};
+use customer_feedback_logger::persistence::{FeedbackData, MemoryStorage, Storage};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Memory storage shared with the test, which can read what was saved and make saving fail.
+struct FlakyStorage {
+    inner: Rc<MemoryStorage>,
+    failing: Rc<Cell<bool>>,
+}
+
+impl Storage for FlakyStorage {
+    fn load(&self) -> Result<FeedbackData, FeedbackError> {
+        self.inner.load()
+    }
+
+    fn save(&self, data: &FeedbackData) -> Result<(), FeedbackError> {
+        if self.failing.get() {
+            return Err(FeedbackError::PersistenceError("disk full".to_string()));
+        }
+        self.inner.save(data)
+    }
+
+    fn restore_latest_backup(&self) -> Result<FeedbackData, FeedbackError> {
+        self.inner.restore_latest_backup()
+    }
+}
+
+fn flaky_manager() -> (FeedbackManager, Rc<MemoryStorage>, Rc<Cell<bool>>) {
+    let inner = Rc::new(MemoryStorage::new());
+    let failing = Rc::new(Cell::new(false));
+    let storage = FlakyStorage {
+        inner: Rc::clone(&inner),
+        failing: Rc::clone(&failing),
+    };
+    (FeedbackManager::empty(Box::new(storage)), inner, failing)
+}
+
+fn setup() -> FeedbackManager {
+    let mut manager = FeedbackManager::empty(Box::new(MemoryStorage::new()));
+    manager
+        .add_feedback("Ada".to_string(), "Great service".to_string(), 5)
+        .unwrap();
+    manager
+        .add_feedback("Bola".to_string(), "Too slow".to_string(), 2)
+        .unwrap();
+    manager
+}
+
+#[test]
+fn test_add_feedback() {
+    let manager = setup();
+    assert_eq!(manager.len(), 2);
+
+    let feedback = manager.get(2).unwrap();
+    assert_eq!(feedback.customer_name, "Bola");
+    assert_eq!(feedback.rating, 2);
+    let ids: Vec<u32> = manager.all_feedback().iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn test_add_rejects_invalid_rating() {
+    let mut manager = setup();
+    for rating in [0, 6, 255] {
+        assert_eq!(
+            manager.add_feedback("Chi".to_string(), "Okay".to_string(), rating),
+            Err(FeedbackError::InvalidRating)
+        );
+    }
+    assert_eq!(manager.len(), 2);
+    // Rejected entries do not use up an id
+    assert_eq!(
+        manager.add_feedback("Chi".to_string(), "Okay".to_string(), 3),
+        Ok(3)
+    );
+}
+
+#[test]
+fn test_add_rejects_blank_text() {
+    let mut manager = setup();
+    assert_eq!(
+        manager.add_feedback("  ".to_string(), "Okay".to_string(), 3),
+        Err(FeedbackError::InvalidInput)
+    );
+    assert_eq!(
+        manager.add_feedback("Chi".to_string(), "".to_string(), 3),
+        Err(FeedbackError::InvalidInput)
+    );
+    assert_eq!(manager.len(), 2);
+}
+
+#[test]
+fn test_edit_applies_patch() {
+    let mut manager = setup();
+    let patch = FeedbackPatch {
+        comment: Some(" Fixed now, thanks ".to_string()),
+        rating: Some(4),
+        ..FeedbackPatch::default()
+    };
+
+    let edited = manager.edit(2, patch).unwrap();
+    assert_eq!(edited.customer_name, "Bola");
+    assert_eq!(edited.comment, "Fixed now, thanks");
+    assert_eq!(edited.rating, 4);
+}
+
+#[test]
+fn test_edit_rejects_invalid_rating() {
+    let mut manager = setup();
+    let patch = FeedbackPatch {
+        customer_name: Some("Ada L.".to_string()),
+        rating: Some(9),
+        ..FeedbackPatch::default()
+    };
+
+    assert_eq!(manager.edit(1, patch), Err(FeedbackError::InvalidRating));
+    // No part of a rejected patch is applied
+    let feedback = manager.get(1).unwrap();
+    assert_eq!(feedback.customer_name, "Ada");
+    assert_eq!(feedback.rating, 5);
+}
+
+#[test]
+fn test_not_found() {
+    let mut manager = setup();
+    assert_eq!(manager.get(9), Err(FeedbackError::FeedbackNotFound));
+    assert_eq!(
+        manager.edit(9, FeedbackPatch::default()),
+        Err(FeedbackError::FeedbackNotFound)
+    );
+    assert_eq!(
+        manager.remove_feedback(9),
+        Err(FeedbackError::FeedbackNotFound)
+    );
+
+    manager.remove_feedback(1).unwrap();
+    assert_eq!(
+        manager.remove_feedback(1),
+        Err(FeedbackError::FeedbackNotFound)
+    );
+    assert_eq!(manager.len(), 1);
+}
+
+#[test]
+fn test_persistence_failure_undoes_changes() {
+    let (mut manager, saved, failing) = flaky_manager();
+    manager
+        .add_feedback("Ada".to_string(), "Great service".to_string(), 5)
+        .unwrap();
+    failing.set(true);
+    let disk_full = FeedbackError::PersistenceError("disk full".to_string());
+
+    let added = manager.add_feedback("Bola".to_string(), "Too slow".to_string(), 2);
+    assert_eq!(added, Err(disk_full.clone()));
+    assert_eq!(manager.len(), 1);
+
+    let patch = FeedbackPatch {
+        rating: Some(1),
+        ..FeedbackPatch::default()
+    };
+    assert_eq!(manager.edit(1, patch).unwrap_err(), disk_full);
+    assert_eq!(manager.get(1).unwrap().rating, 5);
+
+    assert_eq!(manager.remove_feedback(1).unwrap_err(), disk_full);
+    assert!(manager.get(1).is_ok());
+    assert_eq!(manager.save_data(), Err(disk_full));
+
+    // What was saved before the failures is untouched
+    assert_eq!(saved.load().unwrap().feedbacks.len(), 1);
+
+    // Once saving works again, the id the failed add would have used is handed out
+    failing.set(false);
+    assert_eq!(
+        manager.add_feedback("Bola".to_string(), "Too slow".to_string(), 2),
+        Ok(2)
+    );
+}
+
+#[test]
+fn test_changes_are_saved_and_loaded() {
+    let (mut manager, saved, _) = flaky_manager();
+    manager
+        .add_feedback("Ada".to_string(), "Great service".to_string(), 5)
+        .unwrap();
+    manager
+        .add_feedback("Bola".to_string(), "Too slow".to_string(), 2)
+        .unwrap();
+    manager.remove_feedback(1).unwrap();
+
+    let reloaded =
+        FeedbackManager::load(Box::new(MemoryStorage::with_data(saved.load().unwrap()))).unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded.get(2).unwrap().customer_name, "Bola");
+    assert!(reloaded.get(1).is_err());
+}
+
+#[test]
+fn test_respond_acknowledges_new_feedback() {
+    let mut manager = setup();
+    let feedback = manager
+        .respond(2, " Tolu ".to_string(), "Sorry, we are on it".to_string())
+        .unwrap();
+    assert_eq!(feedback.status, FollowUpStatus::Acknowledged);
+    assert_eq!(feedback.responses.len(), 1);
+    assert_eq!(feedback.responses[0].author, "Tolu");
+
+    let patch = FeedbackPatch {
+        status: Some(FollowUpStatus::Actioned),
+        ..FeedbackPatch::default()
+    };
+    manager.edit(2, patch).unwrap();
+    // A later reply does not move the status back
+    let feedback = manager
+        .respond(2, "Tolu".to_string(), "Fixed".to_string())
+        .unwrap();
+    assert_eq!(feedback.status, FollowUpStatus::Actioned);
+    assert_eq!(feedback.responses.len(), 2);
+
+    assert_eq!(
+        manager.respond(2, "Tolu".to_string(), " ".to_string()),
+        Err(FeedbackError::InvalidInput)
+    );
+    assert_eq!(manager.get(2).unwrap().responses.len(), 2);
+}
+
+#[test]
+fn test_edit_sets_tags_and_due_date() {
+    let mut manager = setup();
+    let due = NaiveDate::from_ymd_opt(2030, 1, 31).unwrap();
+    let patch = FeedbackPatch {
+        tags: Some(vec![
+            " Bug".to_string(),
+            "bug".to_string(),
+            "".to_string(),
+            "Pricing".to_string(),
+        ]),
+        due_date: Some(Some(due)),
+        ..FeedbackPatch::default()
+    };
+
+    let feedback = manager.edit(2, patch).unwrap();
+    let tags: Vec<&str> = feedback.tags.iter().map(String::as_str).collect();
+    assert_eq!(tags, vec!["bug", "pricing"]);
+    assert_eq!(feedback.due_date, Some(due));
+
+    let clear = FeedbackPatch {
+        due_date: Some(None),
+        ..FeedbackPatch::default()
+    };
+    let feedback = manager.edit(2, clear).unwrap();
+    assert_eq!(feedback.due_date, None);
+    assert_eq!(feedback.tags.len(), 2);
+}
+
+#[test]
+fn test_filter_feedback() {
+    let mut manager = setup();
+    manager
+        .add_feedback("Chi".to_string(), "Fine".to_string(), 3)
+        .unwrap();
+    let tag = |tags: &[&str]| FeedbackPatch {
+        tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+        ..FeedbackPatch::default()
+    };
+    manager.edit(1, tag(&["praise"])).unwrap();
+    manager.edit(2, tag(&["bug", "pricing"])).unwrap();
+    manager.edit(3, tag(&["pricing"])).unwrap();
+    manager
+        .respond(3, "Tolu".to_string(), "Thanks".to_string())
+        .unwrap();
+
+    let ids = |filter: FeedbackFilter| -> Vec<u32> {
+        manager.filter(&filter).iter().map(|f| f.id).collect()
+    };
+    assert_eq!(ids(FeedbackFilter::default()), vec![1, 2, 3]);
+    let pricing = FeedbackFilter {
+        tag: Some("Pricing".to_string()),
+        ..FeedbackFilter::default()
+    };
+    assert_eq!(ids(pricing.clone()), vec![2, 3]);
+    assert_eq!(
+        ids(FeedbackFilter {
+            status: Some(FollowUpStatus::New),
+            ..pricing
+        }),
+        vec![2]
+    );
+    assert_eq!(
+        ids(FeedbackFilter {
+            min_rating: Some(2),
+            max_rating: Some(3),
+            ..FeedbackFilter::default()
+        }),
+        vec![2, 3]
+    );
+
+    let today = Utc::now().date_naive();
+    assert_eq!(
+        ids(FeedbackFilter {
+            from: Some(today),
+            to: Some(today),
+            ..FeedbackFilter::default()
+        })
+        .len(),
+        3
+    );
+    assert!(ids(FeedbackFilter {
+        from: today.succ_opt(),
+        ..FeedbackFilter::default()
+    })
+    .is_empty());
+}
@@ -0,0 +1,264 @@
+use customer_feedback_logger::manager::FeedbackManager;
+use customer_feedback_logger::models::{FeedbackError, FollowUpStatus};
+use customer_feedback_logger::persistence::{
+    parse_data, resolve_data_path, FileStorage, Storage, CURRENT_VERSION,
+};
+use std::fs;
+use std::path::PathBuf;
+
+/// A fresh directory per test, so tests can run in parallel.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("feedback-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_file_round_trip_with_rotating_backups() {
+    let dir = temp_dir("round-trip");
+    let path = dir.join("data.json");
+    let mut manager = FeedbackManager::empty(Box::new(FileStorage::new(&path).with_max_backups(2)));
+    for rating in 1..=4 {
+        manager
+            .add_feedback("Ada".to_string(), "Fine".to_string(), rating)
+            .unwrap();
+    }
+
+    let storage = FileStorage::new(&path);
+    assert_eq!(storage.load().unwrap().feedbacks.len(), 4);
+    // Each save after the first backs up the previous version; only the newest two are kept
+    assert_eq!(storage.backups().unwrap().len(), 2);
+    assert!(!dir.join("data.json.tmp").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_corrupt_file_restores_latest_good_backup() {
+    let dir = temp_dir("corrupt");
+    let path = dir.join("data.json");
+    let mut manager = FeedbackManager::empty(Box::new(FileStorage::new(&path)));
+    manager
+        .add_feedback("Ada".to_string(), "Great".to_string(), 5)
+        .unwrap();
+    manager
+        .add_feedback("Bola".to_string(), "Slow".to_string(), 2)
+        .unwrap();
+    fs::write(&path, "{\"feedbacks\": {").unwrap();
+
+    let storage = FileStorage::new(&path);
+    assert!(matches!(
+        FeedbackManager::load(Box::new(FileStorage::new(&path))),
+        Err(FeedbackError::CorruptData(_))
+    ));
+
+    // The backup holds the data as it was before the last save
+    let restored = storage.restore_latest_backup().unwrap();
+    assert_eq!(restored.feedbacks.len(), 1);
+    assert_eq!(storage.load().unwrap().feedbacks.len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_non_utf8_file_is_corrupt_and_kept_on_restore() {
+    let dir = temp_dir("non-utf8");
+    let path = dir.join("data.json");
+    let mut manager = FeedbackManager::empty(Box::new(FileStorage::new(&path)));
+    manager
+        .add_feedback("Ada".to_string(), "Great".to_string(), 5)
+        .unwrap();
+    manager
+        .add_feedback("Bola".to_string(), "Slow".to_string(), 2)
+        .unwrap();
+    // A partial write can leave junk bytes at the end of the file
+    let mut junk = fs::read(&path).unwrap();
+    junk.extend_from_slice(&[0xff, 0xfe, 0x00]);
+    fs::write(&path, &junk).unwrap();
+
+    let storage = FileStorage::new(&path);
+    assert!(matches!(storage.load(), Err(FeedbackError::CorruptData(_))));
+
+    assert_eq!(storage.restore_latest_backup().unwrap().feedbacks.len(), 1);
+    assert_eq!(storage.load().unwrap().feedbacks.len(), 1);
+    let aside: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().contains("data.corrupt-"))
+        .collect();
+    assert_eq!(aside.len(), 1);
+    assert_eq!(fs::read(&aside[0]).unwrap(), junk);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_missing_file_loads_empty() {
+    let dir = temp_dir("missing");
+    let storage = FileStorage::new(dir.join("none.json"));
+    let data = storage.load().unwrap();
+    assert!(data.feedbacks.is_empty());
+    assert_eq!(data.next_id, 1);
+    assert!(storage.restore_latest_backup().is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_failed_save_leaves_previous_file_in_place() {
+    let dir = temp_dir("atomic");
+    let path = dir.join("data.json");
+    let mut manager = FeedbackManager::empty(Box::new(FileStorage::new(&path)));
+    manager
+        .add_feedback("Ada".to_string(), "Great".to_string(), 5)
+        .unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+
+    // A directory where the temporary file goes makes the next write fail part way
+    fs::create_dir(dir.join("data.json.tmp")).unwrap();
+    assert!(matches!(
+        manager.add_feedback("Bola".to_string(), "Slow".to_string(), 2),
+        Err(FeedbackError::PersistenceError(_))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    assert_eq!(FileStorage::new(&path).load().unwrap().feedbacks.len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_corrupt_file_is_not_rotated_into_backups() {
+    let dir = temp_dir("rotation");
+    let path = dir.join("data.json");
+    let storage = FileStorage::new(&path).with_max_backups(1);
+    let mut data = storage.load().unwrap();
+    storage.save(&data).unwrap();
+    data.next_id = 2;
+    storage.save(&data).unwrap();
+    let good_backup = storage.backups().unwrap();
+    assert_eq!(good_backup.len(), 1);
+
+    fs::write(&path, "not json").unwrap();
+    data.next_id = 3;
+    storage.save(&data).unwrap();
+    assert_eq!(storage.backups().unwrap(), good_backup);
+    assert_eq!(storage.load().unwrap().next_id, 3);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_restore_skips_unreadable_backups() {
+    let dir = temp_dir("restore");
+    let path = dir.join("data.json");
+    let storage = FileStorage::new(&path);
+    let mut data = storage.load().unwrap();
+    for next_id in 1..=3 {
+        data.next_id = next_id;
+        storage.save(&data).unwrap();
+    }
+    let backups = storage.backups().unwrap();
+    assert_eq!(backups.len(), 2);
+    fs::write(&backups[0], "{").unwrap();
+    fs::write(&path, "{").unwrap();
+
+    assert_eq!(storage.restore_latest_backup().unwrap().next_id, 1);
+    assert_eq!(storage.load().unwrap().next_id, 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_corrupt_file_is_set_aside() {
+    let dir = temp_dir("set-aside");
+    let path = dir.join("data.json");
+    fs::write(&path, "{\"feedbacks\": {").unwrap();
+
+    let storage = FileStorage::new(&path);
+    let aside = storage.set_aside_corrupt().unwrap().unwrap();
+    assert!(!path.exists());
+    assert_eq!(aside.parent(), Some(dir.as_path()));
+    let name = aside.file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("data.corrupt-") && name.ends_with(".json"));
+    assert_eq!(fs::read_to_string(&aside).unwrap(), "{\"feedbacks\": {");
+
+    // Starting over no longer touches the corrupt copy
+    storage.save(&storage.load().unwrap()).unwrap();
+    assert_eq!(fs::read_to_string(&aside).unwrap(), "{\"feedbacks\": {");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_resolve_data_path() {
+    let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    let env = Some("from-env.json".to_string());
+
+    assert_eq!(
+        resolve_data_path(&args(&["--data-file", "flag.json"]), env.clone()),
+        PathBuf::from("flag.json")
+    );
+    assert_eq!(
+        resolve_data_path(&args(&["--data-file=eq.json"]), env.clone()),
+        PathBuf::from("eq.json")
+    );
+    assert_eq!(
+        resolve_data_path(&args(&[]), env),
+        PathBuf::from("from-env.json")
+    );
+    assert_eq!(
+        resolve_data_path(&args(&[]), Some(" ".to_string())),
+        PathBuf::from("feedback_data.json")
+    );
+}
+
+/// A file as written before feedback had a version, responses, tags, status or due date.
+const VERSION_1_JSON: &str = r#"{
+  "feedbacks": {
+    "1": {
+      "id": 1,
+      "customer_name": "Ada",
+      "comment": "Great",
+      "rating": 5,
+      "date_created": "2024-05-01T10:00:00Z"
+    }
+  },
+  "next_id": 2
+}"#;
+
+#[test]
+fn test_version_1_file_is_migrated() {
+    let data = parse_data(VERSION_1_JSON).unwrap();
+    assert_eq!(data.version, CURRENT_VERSION);
+    assert_eq!(data.next_id, 2);
+    let feedback = &data.feedbacks[&1];
+    assert_eq!(feedback.customer_name, "Ada");
+    assert_eq!(feedback.status, FollowUpStatus::New);
+    assert!(feedback.responses.is_empty() && feedback.tags.is_empty());
+    assert_eq!(feedback.due_date, None);
+
+    // Saving writes the current format, which loads back unchanged
+    let dir = temp_dir("migrate");
+    let path = dir.join("data.json");
+    fs::write(&path, VERSION_1_JSON).unwrap();
+    let mut manager = FeedbackManager::load(Box::new(FileStorage::new(&path))).unwrap();
+    manager
+        .respond(1, "Tolu".to_string(), "Thank you!".to_string())
+        .unwrap();
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["version"], CURRENT_VERSION);
+    let reloaded = FileStorage::new(&path).load().unwrap();
+    assert_eq!(reloaded.feedbacks[&1].responses.len(), 1);
+    assert_eq!(reloaded.feedbacks[&1].status, FollowUpStatus::Acknowledged);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_newer_version_is_refused() {
+    let newer = format!(
+        "{{\"version\": {}, \"feedbacks\": {{}}, \"next_id\": 1}}",
+        CURRENT_VERSION + 1
+    );
+    assert_eq!(
+        parse_data(&newer).unwrap_err(),
+        FeedbackError::UnsupportedVersion(CURRENT_VERSION + 1)
+    );
+    assert!(matches!(
+        parse_data("{\"version\": \"two\", \"feedbacks\": {}, \"next_id\": 1}"),
+        Err(FeedbackError::CorruptData(_))
+    ));
+}
@@ -1,9 +1,99 @@
-use std::collections::HashMap;
+use chrono::{Duration, NaiveDateTime};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-#[derive(Clone, Debug, PartialEq)]
+/// How long an interview blocks the interviewer's calendar when no length is given.
+pub const DEFAULT_INTERVIEW_MINUTES: u32 = 60;
+
+/// Where a candidate is in the hiring pipeline. `INTERVIEW(n)` means the n-th interview round
+/// has been scheduled; `HIRED`, `REJECTED` and `WITHDRAWN` are final.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CandidateStatus {
-    ACCEPTED,
-    DECLINED,
+    APPLIED,
+    SCREENING,
+    INTERVIEW(u32),
+    OFFER,
+    HIRED,
+    REJECTED,
+    WITHDRAWN,
+}
+
+impl CandidateStatus {
+    /// Stage names in pipeline order, as used by `stage_name` and `pipeline_for_position`.
+    pub const STAGES: [&'static str; 7] = [
+        "Applied",
+        "Screening",
+        "Interview",
+        "Offer",
+        "Hired",
+        "Rejected",
+        "Withdrawn",
+    ];
+
+    /// The stage without the interview round, e.g. "Interview" for `INTERVIEW(2)`.
+    pub fn stage_name(&self) -> &'static str {
+        match self {
+            CandidateStatus::APPLIED => "Applied",
+            CandidateStatus::SCREENING => "Screening",
+            CandidateStatus::INTERVIEW(_) => "Interview",
+            CandidateStatus::OFFER => "Offer",
+            CandidateStatus::HIRED => "Hired",
+            CandidateStatus::REJECTED => "Rejected",
+            CandidateStatus::WITHDRAWN => "Withdrawn",
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            CandidateStatus::HIRED | CandidateStatus::REJECTED | CandidateStatus::WITHDRAWN
+        )
+    }
+
+    /// Whether `move_candidate` may take a candidate from this stage to `next`. Candidates can be
+    /// rejected or withdraw at any stage until a final one. Interview stages are only entered by
+    /// scheduling an interview.
+    pub fn can_move_to(&self, next: CandidateStatus) -> bool {
+        if self.is_final() {
+            return false;
+        }
+        match next {
+            CandidateStatus::REJECTED | CandidateStatus::WITHDRAWN => true,
+            CandidateStatus::SCREENING => *self == CandidateStatus::APPLIED,
+            CandidateStatus::OFFER => matches!(self, CandidateStatus::INTERVIEW(_)),
+            CandidateStatus::HIRED => *self == CandidateStatus::OFFER,
+            CandidateStatus::APPLIED | CandidateStatus::INTERVIEW(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for CandidateStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandidateStatus::INTERVIEW(round) => write!(f, "Interview {}", round),
+            other => write!(f, "{}", other.stage_name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interview {
+    pub round: u32,
+    pub interviewer: String,
+    pub scheduled_at: NaiveDateTime,
+    pub duration_minutes: u32,
+    /// From 1 to 5, once the interviewer has reported back.
+    pub feedback_score: Option<u8>,
+}
+
+impl Interview {
+    pub fn ends_at(&self) -> NaiveDateTime {
+        self.scheduled_at + Duration::minutes(i64::from(self.duration_minutes))
+    }
+
+    fn overlaps(&self, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        self.scheduled_at < end && start < self.ends_at()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -13,12 +103,139 @@ pub struct Candidates {
     pub position: String,
     pub contact: String,
     pub status: CandidateStatus,
+    /// Every interview round, in the order they were scheduled.
+    pub interviews: Vec<Interview>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PipelineError {
+    CandidateNotFound(u32),
+    InvalidTransition {
+        from: CandidateStatus,
+        to: CandidateStatus,
+    },
+    /// The interviewer already has an overlapping interview with this candidate and round.
+    InterviewerDoubleBooked {
+        interviewer: String,
+        candidate_id: u32,
+        round: u32,
+    },
+    InterviewNotFound {
+        candidate_id: u32,
+        round: u32,
+    },
+    InvalidScore(u8),
+    MissingInterviewer,
+    InvalidDuration,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::CandidateNotFound(id) => write!(f, "candidate with the ID {} not found", id),
+            PipelineError::InvalidTransition { from, to } => {
+                write!(f, "cannot move a candidate from {} to {}", from, to)
+            }
+            PipelineError::InterviewerDoubleBooked { interviewer, candidate_id, round } => write!(
+                f,
+                "{} is already interviewing candidate {} (round {}) at that time",
+                interviewer, candidate_id, round
+            ),
+            PipelineError::InterviewNotFound { candidate_id, round } => {
+                write!(f, "candidate {} has no interview round {}", candidate_id, round)
+            }
+            PipelineError::InvalidScore(score) => {
+                write!(f, "feedback score must be between 1 and 5, got {}", score)
+            }
+            PipelineError::MissingInterviewer => write!(f, "an interviewer is required"),
+            PipelineError::InvalidDuration => write!(f, "an interview must last at least a minute"),
+        }
+    }
+}
+
+/// One column of a position's pipeline: the candidates currently at a stage.
+#[derive(Debug, PartialEq)]
+pub struct PipelineStage<'a> {
+    pub stage: &'static str,
+    pub candidates: Vec<&'a Candidates>,
+}
+
+/// A candidate detail that can be changed through an edit session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CandidateField {
+    Name,
+    Position,
+    Contact,
+}
+
+impl CandidateField {
+    fn value<'a>(&self, candidate: &'a Candidates) -> &'a str {
+        match self {
+            CandidateField::Name => &candidate.name,
+            CandidateField::Position => &candidate.position,
+            CandidateField::Contact => &candidate.contact,
+        }
+    }
+
+    fn set(&self, candidate: &mut Candidates, value: String) {
+        match self {
+            CandidateField::Name => candidate.name = value,
+            CandidateField::Position => candidate.position = value,
+            CandidateField::Contact => candidate.contact = value,
+        }
+    }
+}
+
+/// What committing a session would change, or did change, in one field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDiff {
+    pub field: CandidateField,
+    pub before: String,
+    pub after: String,
+}
+
+/// Changes staged against one candidate. Nothing is applied until the session is committed.
+#[derive(Clone, Debug)]
+struct EditSession {
+    candidate_id: u32,
+    /// The candidate as it was when the session began, to detect edits committed since.
+    original: Candidates,
+    changes: BTreeMap<CandidateField, String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditError {
+    CandidateNotFound(u32),
+    SessionNotFound(u32),
+    EmptyValue(CandidateField),
+    /// Another session changed this field after this one began. The session stays open.
+    Conflict(CandidateField),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::CandidateNotFound(id) => write!(f, "candidate with the ID {} not found", id),
+            EditError::SessionNotFound(id) => write!(f, "no pending edit with the ID {}", id),
+            EditError::EmptyValue(field) => write!(f, "{:?} cannot be empty", field),
+            EditError::Conflict(field) => {
+                write!(f, "{:?} was changed by another edit since this one began", field)
+            }
+        }
+    }
 }
 
 pub struct CandidatesInfo {
     info: Vec<Candidates>,
     next_id: u32,
-    backup: HashMap<u32, Candidates>,
+    sessions: HashMap<u32, EditSession>,
+    next_session_id: u32,
+}
+
+impl Default for CandidatesInfo {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CandidatesInfo {
@@ -26,18 +243,21 @@ impl CandidatesInfo {
         Self {
             info: Vec::new(),
             next_id: 1,
-            backup: HashMap::new(),
+            sessions: HashMap::new(),
+            next_session_id: 1,
         }
     }
 
-    pub fn add_candidate(&mut self, name: String, contact: String, position: String, status: CandidateStatus) -> u32 {
+    /// Adds a candidate at the start of the pipeline, as `APPLIED`.
+    pub fn add_candidate(&mut self, name: String, contact: String, position: String) -> u32 {
         let present_id = self.next_id;
         let candidate = Candidates {
             id: present_id,
             name,
             position,
             contact,
-            status: status,
+            status: CandidateStatus::APPLIED,
+            interviews: Vec::new(),
         };
         self.next_id += 1;
         self.info.push(candidate);
@@ -48,32 +268,274 @@ impl CandidatesInfo {
         &self.info
     }
 
-    pub fn remove_candidates(&mut self, id: u32) {
-        self.info.retain(|candidate_id| candidate_id.id != id)
+    pub fn get_candidate(&self, id: u32) -> Option<&Candidates> {
+        self.info.iter().find(|candidate| candidate.id == id)
+    }
+
+    fn candidate_mut(&mut self, id: u32) -> Result<&mut Candidates, PipelineError> {
+        self.info
+            .iter_mut()
+            .find(|candidate| candidate.id == id)
+            .ok_or(PipelineError::CandidateNotFound(id))
+    }
+
+    /// Removes a candidate and any edits still pending for them. Returns false if there was no
+    /// such candidate.
+    pub fn remove_candidates(&mut self, id: u32) -> bool {
+        let before = self.info.len();
+        self.info.retain(|candidate_id| candidate_id.id != id);
+        self.sessions.retain(|_, session| session.candidate_id != id);
+        self.info.len() != before
     }
 
+    /// Replaces all three details at once, through an edit session that is committed straight
+    /// away. Returns false, changing nothing, if the candidate is missing or a value is empty.
     pub fn edit_candidates(&mut self, id: u32, new_name: String, new_position: String, new_contact: String) -> bool {
-        if let Some(candidate) = self.info.iter_mut().find(|candidate_id| candidate_id.id == id) {
-            if !self.backup.contains_key(&id) {
-                self.backup.insert(id, candidate.clone());
+        let Ok(session_id) = self.begin_edit(id) else {
+            return false;
+        };
+        let staged = self
+            .stage_change(session_id, CandidateField::Name, new_name)
+            .and_then(|_| self.stage_change(session_id, CandidateField::Position, new_position))
+            .and_then(|_| self.stage_change(session_id, CandidateField::Contact, new_contact));
+        if staged.and_then(|_| self.commit_edit(session_id)).is_err() {
+            let _ = self.rollback_edit(session_id);
+            return false;
+        }
+        true
+    }
+
+    /// Opens an edit session for a candidate and returns its ID. A candidate can have several
+    /// sessions pending at once; each is committed or rolled back on its own.
+    pub fn begin_edit(&mut self, candidate_id: u32) -> Result<u32, EditError> {
+        let original = self
+            .get_candidate(candidate_id)
+            .ok_or(EditError::CandidateNotFound(candidate_id))?
+            .clone();
+        let session_id = self.next_session_id;
+        self.next_session_id += 1;
+        self.sessions.insert(
+            session_id,
+            EditSession {
+                candidate_id,
+                original,
+                changes: BTreeMap::new(),
+            },
+        );
+        Ok(session_id)
+    }
+
+    /// Stages a new value for a field, replacing any value already staged for it in this session.
+    pub fn stage_change(&mut self, session_id: u32, field: CandidateField, value: String) -> Result<(), EditError> {
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(EditError::SessionNotFound(session_id))?;
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            return Err(EditError::EmptyValue(field));
+        }
+        session.changes.insert(field, value);
+        Ok(())
+    }
+
+    /// The fields committing the session would change, compared with the candidate as they are
+    /// now. Staged values equal to the current ones are left out.
+    pub fn preview_edit(&self, session_id: u32) -> Result<Vec<FieldDiff>, EditError> {
+        let session = self
+            .sessions
+            .get(&session_id)
+            .ok_or(EditError::SessionNotFound(session_id))?;
+        let candidate = self
+            .get_candidate(session.candidate_id)
+            .ok_or(EditError::CandidateNotFound(session.candidate_id))?;
+        Ok(session
+            .changes
+            .iter()
+            .filter(|(field, value)| field.value(candidate) != value.as_str())
+            .map(|(field, value)| FieldDiff {
+                field: *field,
+                before: field.value(candidate).to_string(),
+                after: value.clone(),
+            })
+            .collect())
+    }
+
+    /// Applies the staged changes and closes the session, returning what changed. If another
+    /// session has since committed a different value to one of the same fields, nothing is
+    /// applied and the session stays open to be re-staged or rolled back.
+    pub fn commit_edit(&mut self, session_id: u32) -> Result<Vec<FieldDiff>, EditError> {
+        let diff = self.preview_edit(session_id)?;
+        let session = &self.sessions[&session_id];
+        let candidate_id = session.candidate_id;
+        for change in &diff {
+            if change.field.value(&session.original) != change.before {
+                return Err(EditError::Conflict(change.field));
             }
-            candidate.name = new_name;
-            candidate.position = new_position;
-            candidate.contact = new_contact;
-            true
-        } else {
-            false
         }
+
+        self.sessions.remove(&session_id);
+        let candidate = self
+            .info
+            .iter_mut()
+            .find(|candidate| candidate.id == candidate_id)
+            .ok_or(EditError::CandidateNotFound(candidate_id))?;
+        for change in &diff {
+            change.field.set(candidate, change.after.clone());
+        }
+        Ok(diff)
+    }
+
+    /// Discards a session without changing the candidate.
+    pub fn rollback_edit(&mut self, session_id: u32) -> Result<(), EditError> {
+        self.sessions
+            .remove(&session_id)
+            .map(|_| ())
+            .ok_or(EditError::SessionNotFound(session_id))
+    }
+
+    /// IDs of the sessions still open for a candidate, oldest first.
+    pub fn pending_edits(&self, candidate_id: u32) -> Vec<u32> {
+        let mut sessions: Vec<u32> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.candidate_id == candidate_id)
+            .map(|(session_id, _)| *session_id)
+            .collect();
+        sessions.sort();
+        sessions
+    }
+
+    /// Moves a candidate to another stage, following `CandidateStatus::can_move_to`.
+    pub fn move_candidate(&mut self, id: u32, to: CandidateStatus) -> Result<(), PipelineError> {
+        let candidate = self.candidate_mut(id)?;
+        if !candidate.status.can_move_to(to) {
+            return Err(PipelineError::InvalidTransition { from: candidate.status, to });
+        }
+        candidate.status = to;
+        Ok(())
+    }
+
+    /// Books the candidate's next interview round and moves them to `INTERVIEW(round)`, returning
+    /// the round. Only candidates in screening or already interviewing can be booked, and the
+    /// interviewer must be free for the whole slot.
+    pub fn schedule_interview(
+        &mut self,
+        id: u32,
+        interviewer: String,
+        scheduled_at: NaiveDateTime,
+        duration_minutes: u32,
+    ) -> Result<u32, PipelineError> {
+        let interviewer = interviewer.trim().to_string();
+        if interviewer.is_empty() {
+            return Err(PipelineError::MissingInterviewer);
+        }
+        if duration_minutes == 0 {
+            return Err(PipelineError::InvalidDuration);
+        }
+        let candidate = self.get_candidate(id).ok_or(PipelineError::CandidateNotFound(id))?;
+        let round = candidate.interviews.len() as u32 + 1;
+        let from = candidate.status;
+        if !matches!(from, CandidateStatus::SCREENING | CandidateStatus::INTERVIEW(_)) {
+            return Err(PipelineError::InvalidTransition {
+                from,
+                to: CandidateStatus::INTERVIEW(round),
+            });
+        }
+        let ends_at = scheduled_at + Duration::minutes(i64::from(duration_minutes));
+        if let Some((other, interview)) = self.find_conflict(&interviewer, scheduled_at, ends_at) {
+            return Err(PipelineError::InterviewerDoubleBooked {
+                interviewer: interview.interviewer.clone(),
+                candidate_id: other.id,
+                round: interview.round,
+            });
+        }
+
+        let candidate = self.candidate_mut(id)?;
+        candidate.interviews.push(Interview {
+            round,
+            interviewer,
+            scheduled_at,
+            duration_minutes,
+            feedback_score: None,
+        });
+        candidate.status = CandidateStatus::INTERVIEW(round);
+        Ok(round)
+    }
+
+    /// The first interview the interviewer (matched ignoring case) already has between `start`
+    /// and `end`. Interviews of rejected or withdrawn candidates no longer take up time.
+    pub fn find_conflict(
+        &self,
+        interviewer: &str,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Option<(&Candidates, &Interview)> {
+        self.interviews_for(interviewer)
+            .into_iter()
+            .filter(|(candidate, _)| {
+                !matches!(candidate.status, CandidateStatus::REJECTED | CandidateStatus::WITHDRAWN)
+            })
+            .find(|(_, interview)| interview.overlaps(start, end))
+    }
+
+    /// An interviewer's interviews across all candidates, earliest first.
+    pub fn interviews_for(&self, interviewer: &str) -> Vec<(&Candidates, &Interview)> {
+        let interviewer = interviewer.trim().to_lowercase();
+        let mut interviews: Vec<(&Candidates, &Interview)> = self
+            .info
+            .iter()
+            .flat_map(|candidate| candidate.interviews.iter().map(move |interview| (candidate, interview)))
+            .filter(|(_, interview)| interview.interviewer.to_lowercase() == interviewer)
+            .collect();
+        interviews.sort_by_key(|(_, interview)| interview.scheduled_at);
+        interviews
+    }
+
+    /// Records how an interview went, on a scale of 1 to 5. A later score replaces an earlier one.
+    pub fn record_interview_feedback(&mut self, id: u32, round: u32, score: u8) -> Result<(), PipelineError> {
+        if !(1..=5).contains(&score) {
+            return Err(PipelineError::InvalidScore(score));
+        }
+        let candidate = self.candidate_mut(id)?;
+        let interview = candidate
+            .interviews
+            .iter_mut()
+            .find(|interview| interview.round == round)
+            .ok_or(PipelineError::InterviewNotFound { candidate_id: id, round })?;
+        interview.feedback_score = Some(score);
+        Ok(())
     }
 
-    pub fn cancel_edit(&mut self, id: u32) -> bool {
-        if let Some(original) = self.backup.remove(&id) {
-            if let Some(candidate) = self.info.iter_mut().find(|candidate_id| candidate_id.id == id) {
-                *candidate = original;
-                return true;
+    /// Every position with at least one candidate, sorted, as first written.
+    pub fn positions(&self) -> Vec<&str> {
+        let mut positions: Vec<&str> = Vec::new();
+        for candidate in &self.info {
+            let position = candidate.position.trim();
+            if !positions.iter().any(|known| known.eq_ignore_ascii_case(position)) {
+                positions.push(position);
             }
         }
-        false
+        positions.sort_by_key(|position| position.to_lowercase());
+        positions
+    }
+
+    /// The candidates for a position (matched ignoring case) grouped by stage, in pipeline order.
+    /// Every stage is listed, even when empty.
+    pub fn pipeline_for_position(&self, position: &str) -> Vec<PipelineStage<'_>> {
+        let position = position.trim();
+        CandidateStatus::STAGES
+            .iter()
+            .map(|stage| PipelineStage {
+                stage,
+                candidates: self
+                    .info
+                    .iter()
+                    .filter(|candidate| candidate.position.trim().eq_ignore_ascii_case(position))
+                    .filter(|candidate| candidate.status.stage_name() == *stage)
+                    .collect(),
+            })
+            .collect()
     }
 }
 
@@ -88,7 +550,6 @@ mod tests {
             "John Doe".to_string(),
             "080287653452".to_string(),
             "Developer".to_string(),
-            CandidateStatus::ACCEPTED,
         );
         assert_eq!(id, 1);
         assert_eq!(system.get_all_candidates().len(), 1);
@@ -101,10 +562,10 @@ mod tests {
             "Jane Smith".to_string(),
             "9289304097368".to_string(),
             "Designer".to_string(),
-            CandidateStatus::ACCEPTED,
         );
-        system.remove_candidates(id);
+        assert!(system.remove_candidates(id));
         assert_eq!(system.get_all_candidates().len(), 0);
+        assert!(!system.remove_candidates(id));
     }
 
     #[test]
@@ -114,7 +575,6 @@ mod tests {
             "Bob Wilson".to_string(),
             "123749787473".to_string(),
             "Manager".to_string(),
-            CandidateStatus::ACCEPTED,
         );
         
         let result = system.edit_candidates(id, "Robert Wilson".to_string(), "Senior Manager".to_string(), "93980907923827".to_string());
@@ -127,18 +587,19 @@ mod tests {
     }
 
     #[test]
-    fn test_cancel_edit() {
+    fn test_rollback_edit() {
         let mut system = CandidatesInfo::new();
         let id = system.add_candidate(
             "Alice Brown".to_string(),
             "0803126823797931".to_string(),
             "Analyst".to_string(),
-            CandidateStatus::ACCEPTED,
         );
         
-        system.edit_candidates(id, "Alice Johnson".to_string(), "Senior Analyst".to_string(), "0803126823797931".to_string());
-        let result = system.cancel_edit(id);
-        assert!(result);
+        let session = system.begin_edit(id).unwrap();
+        system.stage_change(session, CandidateField::Name, "Alice Johnson".to_string()).unwrap();
+        system.stage_change(session, CandidateField::Position, "Senior Analyst".to_string()).unwrap();
+        assert_eq!(system.rollback_edit(session), Ok(()));
+        assert!(system.pending_edits(id).is_empty());
         
         let candidate = &system.get_all_candidates()[0];
         assert_eq!(candidate.name, "Alice Brown");
@@ -154,9 +615,200 @@ mod tests {
     }
 
     #[test]
-    fn test_cancel_edit_without_backup() {
+    fn test_rollback_unknown_session() {
         let mut system = CandidatesInfo::new();
-        let result = system.cancel_edit(999);
-        assert!(!result);
+        assert_eq!(system.rollback_edit(999), Err(EditError::SessionNotFound(999)));
+        assert_eq!(system.begin_edit(999), Err(EditError::CandidateNotFound(999)));
+    }
+
+    #[test]
+    fn test_preview_and_commit_edit() {
+        let mut system = CandidatesInfo::new();
+        let id = system.add_candidate("Bob Wilson".to_string(), "0801".to_string(), "Manager".to_string());
+        let session = system.begin_edit(id).unwrap();
+        assert_eq!(
+            system.stage_change(session, CandidateField::Contact, "  ".to_string()),
+            Err(EditError::EmptyValue(CandidateField::Contact))
+        );
+        system.stage_change(session, CandidateField::Name, "Robert".to_string()).unwrap();
+        system.stage_change(session, CandidateField::Name, "Robert Wilson".to_string()).unwrap();
+        system.stage_change(session, CandidateField::Position, "Manager".to_string()).unwrap();
+
+        // Unchanged values are left out, and nothing is applied before the commit
+        let expected = vec![FieldDiff {
+            field: CandidateField::Name,
+            before: "Bob Wilson".to_string(),
+            after: "Robert Wilson".to_string(),
+        }];
+        assert_eq!(system.preview_edit(session), Ok(expected.clone()));
+        assert_eq!(system.get_candidate(id).unwrap().name, "Bob Wilson");
+
+        assert_eq!(system.commit_edit(session), Ok(expected));
+        assert_eq!(system.get_candidate(id).unwrap().name, "Robert Wilson");
+        assert_eq!(system.commit_edit(session), Err(EditError::SessionNotFound(session)));
+    }
+
+    #[test]
+    fn test_multiple_pending_edits() {
+        let mut system = CandidatesInfo::new();
+        let id = system.add_candidate("Alice Brown".to_string(), "0801".to_string(), "Analyst".to_string());
+        let first = system.begin_edit(id).unwrap();
+        let second = system.begin_edit(id).unwrap();
+        let third = system.begin_edit(id).unwrap();
+        system.stage_change(first, CandidateField::Position, "Senior Analyst".to_string()).unwrap();
+        system.stage_change(second, CandidateField::Contact, "0802".to_string()).unwrap();
+        system.stage_change(third, CandidateField::Position, "Lead Analyst".to_string()).unwrap();
+        assert_eq!(system.pending_edits(id), vec![first, second, third]);
+
+        // Sessions touching different fields commit independently
+        system.commit_edit(second).unwrap();
+        system.commit_edit(first).unwrap();
+        // The third began before the first changed the position, so it must not overwrite it
+        assert_eq!(system.commit_edit(third), Err(EditError::Conflict(CandidateField::Position)));
+        assert_eq!(system.pending_edits(id), vec![third]);
+
+        let candidate = system.get_candidate(id).unwrap();
+        assert_eq!(candidate.position, "Senior Analyst");
+        assert_eq!(candidate.contact, "0802");
+
+        // Removing the candidate closes their pending edits
+        assert!(system.remove_candidates(id));
+        assert_eq!(system.preview_edit(third), Err(EditError::SessionNotFound(third)));
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2025, 8, 11)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn screened(system: &mut CandidatesInfo, name: &str, position: &str) -> u32 {
+        let id = system.add_candidate(name.to_string(), "0801".to_string(), position.to_string());
+        system.move_candidate(id, CandidateStatus::SCREENING).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_pipeline_transitions() {
+        let mut system = CandidatesInfo::new();
+        let id = screened(&mut system, "John Doe", "Developer");
+        assert_eq!(
+            system.move_candidate(id, CandidateStatus::OFFER),
+            Err(PipelineError::InvalidTransition {
+                from: CandidateStatus::SCREENING,
+                to: CandidateStatus::OFFER,
+            })
+        );
+
+        system.schedule_interview(id, "Ada".to_string(), at(9, 0), 60).unwrap();
+        assert_eq!(system.schedule_interview(id, "Ada".to_string(), at(14, 0), 60), Ok(2));
+        assert_eq!(system.get_candidate(id).unwrap().status, CandidateStatus::INTERVIEW(2));
+
+        system.move_candidate(id, CandidateStatus::OFFER).unwrap();
+        system.move_candidate(id, CandidateStatus::HIRED).unwrap();
+        assert_eq!(
+            system.move_candidate(id, CandidateStatus::WITHDRAWN),
+            Err(PipelineError::InvalidTransition {
+                from: CandidateStatus::HIRED,
+                to: CandidateStatus::WITHDRAWN,
+            })
+        );
+        assert_eq!(
+            system.move_candidate(999, CandidateStatus::REJECTED),
+            Err(PipelineError::CandidateNotFound(999))
+        );
+    }
+
+    #[test]
+    fn test_schedule_needs_screening() {
+        let mut system = CandidatesInfo::new();
+        let id = system.add_candidate("Jane Smith".to_string(), "0801".to_string(), "Designer".to_string());
+        assert_eq!(
+            system.schedule_interview(id, "Ada".to_string(), at(9, 0), 60),
+            Err(PipelineError::InvalidTransition {
+                from: CandidateStatus::APPLIED,
+                to: CandidateStatus::INTERVIEW(1),
+            })
+        );
+        system.move_candidate(id, CandidateStatus::SCREENING).unwrap();
+        assert_eq!(
+            system.schedule_interview(id, " ".to_string(), at(9, 0), 60),
+            Err(PipelineError::MissingInterviewer)
+        );
+        assert!(system.get_candidate(id).unwrap().interviews.is_empty());
+    }
+
+    #[test]
+    fn test_double_booked_interviewer() {
+        let mut system = CandidatesInfo::new();
+        let first = screened(&mut system, "John Doe", "Developer");
+        let second = screened(&mut system, "Jane Smith", "Developer");
+        system.schedule_interview(first, "Ada".to_string(), at(9, 0), 60).unwrap();
+
+        assert_eq!(
+            system.schedule_interview(second, "ada".to_string(), at(9, 30), 60),
+            Err(PipelineError::InterviewerDoubleBooked {
+                interviewer: "Ada".to_string(),
+                candidate_id: first,
+                round: 1,
+            })
+        );
+        assert_eq!(system.get_candidate(second).unwrap().status, CandidateStatus::SCREENING);
+
+        // Back to back is fine, and so is another interviewer at the same time
+        assert_eq!(system.schedule_interview(second, "Ada".to_string(), at(10, 0), 30), Ok(1));
+        assert!(system.find_conflict("Bola", at(9, 0), at(10, 0)).is_none());
+
+        // A withdrawn candidate's interview frees the slot
+        system.move_candidate(first, CandidateStatus::WITHDRAWN).unwrap();
+        assert!(system.find_conflict("Ada", at(9, 0), at(10, 0)).is_none());
+        assert_eq!(system.interviews_for("ADA").len(), 2);
+    }
+
+    #[test]
+    fn test_record_interview_feedback() {
+        let mut system = CandidatesInfo::new();
+        let id = screened(&mut system, "Bob Wilson", "Manager");
+        system.schedule_interview(id, "Ada".to_string(), at(9, 0), 60).unwrap();
+
+        assert_eq!(system.record_interview_feedback(id, 1, 6), Err(PipelineError::InvalidScore(6)));
+        assert_eq!(
+            system.record_interview_feedback(id, 2, 4),
+            Err(PipelineError::InterviewNotFound { candidate_id: id, round: 2 })
+        );
+        system.record_interview_feedback(id, 1, 4).unwrap();
+        assert_eq!(system.get_candidate(id).unwrap().interviews[0].feedback_score, Some(4));
+    }
+
+    #[test]
+    fn test_pipeline_for_position() {
+        let mut system = CandidatesInfo::new();
+        let john = screened(&mut system, "John Doe", "Developer");
+        let jane = screened(&mut system, "Jane Smith", "developer ");
+        system.add_candidate("Alice Brown".to_string(), "0801".to_string(), "Developer".to_string());
+        system.add_candidate("Bob Wilson".to_string(), "0801".to_string(), "Manager".to_string());
+        system.schedule_interview(john, "Ada".to_string(), at(9, 0), 60).unwrap();
+        system.move_candidate(jane, CandidateStatus::REJECTED).unwrap();
+
+        assert_eq!(system.positions(), vec!["Developer", "Manager"]);
+        let pipeline = system.pipeline_for_position("DEVELOPER");
+        let counts: Vec<(&str, usize)> = pipeline
+            .iter()
+            .map(|stage| (stage.stage, stage.candidates.len()))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Applied", 1),
+                ("Screening", 0),
+                ("Interview", 1),
+                ("Offer", 0),
+                ("Hired", 0),
+                ("Rejected", 1),
+                ("Withdrawn", 0),
+            ]
+        );
+        assert_eq!(pipeline[2].candidates[0].id, john);
     }
 }
\ No newline at end of file
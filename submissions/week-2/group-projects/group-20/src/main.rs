@@ -1,6 +1,7 @@
 // use std::collections::HashMap;
 use std::io::{self, Write};
-use group_20::{CandidatesInfo, CandidateStatus};
+use chrono::NaiveDateTime;
+use group_20::{CandidateField, CandidatesInfo, CandidateStatus, DEFAULT_INTERVIEW_MINUTES};
 
 
 fn input(prompt: &str) -> String {
@@ -11,6 +12,48 @@ fn input(prompt: &str) -> String {
     user_input.trim().to_string()
 }
 
+fn parse_stage(stage: &str) -> Option<CandidateStatus> {
+    match stage.to_lowercase().as_str() {
+        "screening" => Some(CandidateStatus::SCREENING),
+        "offer" => Some(CandidateStatus::OFFER),
+        "hired" => Some(CandidateStatus::HIRED),
+        "rejected" => Some(CandidateStatus::REJECTED),
+        "withdrawn" => Some(CandidateStatus::WITHDRAWN),
+        _ => None,
+    }
+}
+
+/// Shows what an edit session would change, then commits it, rolls it back or leaves it pending.
+fn finish_edit(candidate_system: &mut CandidatesInfo, session: u32) {
+    let diff = match candidate_system.preview_edit(session) {
+        Ok(diff) => diff,
+        Err(e) => {
+            println!("Could not preview the edit: {}", e);
+            return;
+        }
+    };
+    if diff.is_empty() {
+        println!("Nothing would change");
+    }
+    for change in &diff {
+        println!("{:?}: {} -> {}", change.field, change.before, change.after);
+    }
+
+    // A blank or mistyped answer rolls back rather than leaving the edit pending
+    let choice = input("Commit (c), keep pending (k) or roll back (r, the default)? ");
+    match choice.to_lowercase().as_str() {
+        "c" => match candidate_system.commit_edit(session) {
+            Ok(_) => println!("Candidate updated"),
+            Err(e) => println!("Could not commit the edit: {}", e),
+        },
+        "k" => println!("Edit {} kept pending", session),
+        _ => match candidate_system.rollback_edit(session) {
+            Ok(_) => println!("Edit rolled back"),
+            Err(e) => println!("Could not roll back the edit: {}", e),
+        },
+    }
+}
+
 fn main() {
     let mut candidate_system = CandidatesInfo::new();
 
@@ -21,8 +64,12 @@ fn main() {
         println!("2. View all  Candidates in the System");
         println!("3. Remove  Candidates from the Hiring the system");
         println!("4. Edit Candidates in the System");
-        println!("5. Cancel the edit of Candidates in the system");
-        println!("6. Exit the Program Goodbye!");
+        println!("5. Review pending edits of Candidates in the system");
+        println!("6. Move a Candidate to another stage");
+        println!("7. Schedule an Interview");
+        println!("8. Record Interview feedback");
+        println!("9. View the pipeline for a position");
+        println!("10. Exit the Program Goodbye!");
 
 
         let choice = input("\n Enter your choice: ");
@@ -33,25 +80,29 @@ fn main() {
                 let name = input("Enter candidate name: ");
                 let position = input("Enter the candidate position: ");
                 let contact = input("Enter the candidate contact: ");
-                let id = candidate_system.add_candidate(name, contact, position, CandidateStatus::ACCEPTED);
+                let id = candidate_system.add_candidate(name, contact, position);
                 println!("Candidate added with the ID: {} has been added ", id);              
             }
 
             "2" => {
                 println!("\n View all the Candidates:");
                 for candidate in candidate_system.get_all_candidates() {
-                    println!("[{}], {}, {}, {}, {:?}", candidate.id, candidate.name, candidate.position, candidate.contact, candidate.status);
+                    println!("[{}], {}, {}, {}, {}", candidate.id, candidate.name, candidate.position, candidate.contact, candidate.status);
+                    for interview in &candidate.interviews {
+                        let score = match interview.feedback_score {
+                            Some(score) => format!("{}/5", score),
+                            None => "no feedback yet".to_string(),
+                        };
+                        println!("    Interview {}: {} at {} ({} min), {}", interview.round, interview.interviewer, interview.scheduled_at.format("%Y-%m-%d %H:%M"), interview.duration_minutes, score);
+                    }
                 }
             }
 
             "3" => {
-                let id_input = input("Enter the ID to edit");
+                let id_input = input("Enter ID to remove");
                 if let Ok(id) = id_input.parse::<u32>() {
-                    let name = input("Enter new name: ");
-                    let position = input("Enter new position: ");
-                    let contact = input("Enter new contact: ");
-                    if candidate_system.edit_candidates(id, name, position, contact) {
-                        println!("Candidate updated");
+                    if candidate_system.remove_candidates(id) {
+                        println!("Candidate removed");
                     } else {
                         println!("candidate with the ID {} not found.", id);
                     }
@@ -60,31 +111,126 @@ fn main() {
                 }
             }
 
-            "4"  => {
-                let id_input = input("Enter ID to remove");
-                if let Ok(id) = id_input.parse::<u32>() {
-                    candidate_system.remove_candidates(id);
-                        println!("candidate removed if existed");
-                } else {
-                        println!("Invalid ID");
+            "4" => {
+                let id_input = input("Enter the ID to edit");
+                let Ok(id) = id_input.parse::<u32>() else {
+                    println!("Invalid ID");
+                    continue;
+                };
+                let session = match candidate_system.begin_edit(id) {
+                    Ok(session) => session,
+                    Err(e) => {
+                        println!("Could not start the edit: {}", e);
+                        continue;
+                    }
+                };
+                println!("Leave a field blank to keep it");
+                for (field, prompt) in [
+                    (CandidateField::Name, "Enter new name: "),
+                    (CandidateField::Position, "Enter new position: "),
+                    (CandidateField::Contact, "Enter new contact: "),
+                ] {
+                    let value = input(prompt);
+                    if !value.is_empty() {
+                        candidate_system.stage_change(session, field, value).unwrap();
                     }
                 }
+                finish_edit(&mut candidate_system, session);
+            }
+
+            "5" => {
+                let id_input = input("Enter ID to review pending edits");
+                let Ok(id) = id_input.parse::<u32>() else {
+                    println!("Invalid ID");
+                    continue;
+                };
+                let pending = candidate_system.pending_edits(id);
+                if pending.is_empty() {
+                    println!("No pending edits for candidate {}", id);
+                    continue;
+                }
+                println!("Pending edits: {:?}", pending);
+                match input("Enter the edit ID to review").parse::<u32>() {
+                    Ok(session) if pending.contains(&session) => finish_edit(&mut candidate_system, session),
+                    _ => println!("Invalid edit ID"),
+                }
+            }
 
-            "5"  => {
-                let id_input = input("Enter ID to cancel edit");
+            "6" => {
+                let id_input = input("Enter ID to move");
                 if let Ok(id) = id_input.parse::<u32>() {
-                    candidate_system.cancel_edit(id); 
-                        println!("candidate edited");
-                } else {
-                        println!("Invalid ID");
+                    let stage = input("Enter the new stage (Screening, Offer, Hired, Rejected, Withdrawn): ");
+                    match parse_stage(&stage) {
+                        Some(stage) => match candidate_system.move_candidate(id, stage) {
+                            Ok(()) => println!("Candidate moved to {}", stage),
+                            Err(e) => println!("Could not move candidate: {}", e),
+                        },
+                        None => println!("Unknown stage. Interviews are entered by scheduling one."),
                     }
+                } else {
+                    println!("Invalid ID");
+                }
             }
-            "6"   =>  {
+
+            "7" => {
+                let id_input = input("Enter ID to schedule an interview for");
+                let Ok(id) = id_input.parse::<u32>() else {
+                    println!("Invalid ID");
+                    continue;
+                };
+                let interviewer = input("Enter the interviewer: ");
+                let when = input("Enter the date and time (YYYY-MM-DD HH:MM): ");
+                let Ok(scheduled_at) = NaiveDateTime::parse_from_str(&when, "%Y-%m-%d %H:%M") else {
+                    println!("Invalid date and time");
+                    continue;
+                };
+                let length = input(&format!("Enter the length in minutes (default {}): ", DEFAULT_INTERVIEW_MINUTES));
+                let duration = if length.is_empty() {
+                    DEFAULT_INTERVIEW_MINUTES
+                } else if let Ok(minutes) = length.parse::<u32>() {
+                    minutes
+                } else {
+                    println!("Invalid length");
+                    continue;
+                };
+                match candidate_system.schedule_interview(id, interviewer, scheduled_at, duration) {
+                    Ok(round) => println!("Interview round {} scheduled", round),
+                    Err(e) => println!("Could not schedule interview: {}", e),
+                }
+            }
+
+            "8" => {
+                let id_input = input("Enter ID of the candidate");
+                let round_input = input("Enter the interview round");
+                let score_input = input("Enter the feedback score (1-5)");
+                match (id_input.parse::<u32>(), round_input.parse::<u32>(), score_input.parse::<u8>()) {
+                    (Ok(id), Ok(round), Ok(score)) => match candidate_system.record_interview_feedback(id, round, score) {
+                        Ok(()) => println!("Feedback recorded"),
+                        Err(e) => println!("Could not record feedback: {}", e),
+                    },
+                    _ => println!("Invalid number"),
+                }
+            }
+
+            "9" => {
+                if candidate_system.get_all_candidates().is_empty() {
+                    println!("No candidates in the system");
+                    continue;
+                }
+                println!("Positions: {}", candidate_system.positions().join(", "));
+                let position = input("Enter the position: ");
+                for stage in candidate_system.pipeline_for_position(&position) {
+                    let names: Vec<String> = stage.candidates.iter().map(|candidate| format!("[{}] {}", candidate.id, candidate.name)).collect();
+                    println!("{:<10} ({}): {}", stage.stage, names.len(), names.join(", "));
+                }
+            }
+
+            "10" =>  {
                 println!("Exiting the Program Goodbye!");
                 break;
             }
             _ =>  {
-                println!("Invalid Option. Please enter 1 -6.");
+                println!("Invalid Option. Please enter 1 -10.");
             }
         }
     }
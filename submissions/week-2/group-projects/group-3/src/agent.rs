@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agent {
+    pub id: u32,
+    pub name: String,
+    /// Lowercase skill names, e.g. "billing" or "network".
+    pub skills: Vec<String>,
+    /// Most open tickets the agent can hold at once.
+    pub capacity: usize,
+}
+
+/// How `add_ticket_to_queue` picks an owner for a new ticket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssignmentStrategy {
+    /// New tickets stay unassigned until someone assigns them.
+    Manual,
+    /// Agents take turns in the order they were added, skipping any that are full or lack
+    /// the ticket's skill.
+    RoundRobin,
+    /// The eligible agent with the fewest open tickets; ties go to the earlier agent.
+    LeastLoaded,
+}
+
+/// One change of owner, kept on the ticket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub from: Option<u32>,
+    pub to: u32,
+    pub at: DateTime<Utc>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssignmentError {
+    TicketNotFound(u32),
+    AgentNotFound(u32),
+    /// Resolved and closed tickets are not assigned.
+    TicketNotActive(u32),
+    /// Use reassignment, with a reason, to move a ticket between agents.
+    AlreadyAssigned(u32),
+    NotAssigned(u32),
+    AtCapacity(u32),
+    MissingSkill {
+        agent_id: u32,
+        skill: String,
+    },
+    MissingReason,
+}
+
+impl fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignmentError::TicketNotFound(id) => write!(f, "Ticket #{} not found", id),
+            AssignmentError::AgentNotFound(id) => write!(f, "Agent #{} not found", id),
+            AssignmentError::TicketNotActive(id) => {
+                write!(f, "Ticket #{} is resolved or closed", id)
+            }
+            AssignmentError::AlreadyAssigned(id) => {
+                write!(f, "Ticket is already assigned to agent #{}", id)
+            }
+            AssignmentError::NotAssigned(id) => write!(f, "Ticket #{} has no agent yet", id),
+            AssignmentError::AtCapacity(id) => write!(f, "Agent #{} is at capacity", id),
+            AssignmentError::MissingSkill { agent_id, skill } => {
+                write!(f, "Agent #{} does not have the '{}' skill", agent_id, skill)
+            }
+            AssignmentError::MissingReason => write!(f, "A reason is required to reassign"),
+        }
+    }
+}
+
+pub fn create_agent(id: u32, name: String, skills: Vec<String>, capacity: usize) -> Agent {
+    let mut skills: Vec<String> = skills
+        .iter()
+        .map(|skill| skill.trim().to_lowercase())
+        .filter(|skill| !skill.is_empty())
+        .collect();
+    skills.sort();
+    skills.dedup();
+    Agent {
+        id,
+        name,
+        skills,
+        capacity,
+    }
+}
+
+/// Whether the agent can take a ticket needing `skill`; tickets without one suit anybody.
+pub fn agent_has_skill(agent: &Agent, skill: Option<&str>) -> bool {
+    match skill {
+        Some(skill) => agent.skills.iter().any(|s| s == skill),
+        None => true,
+    }
+}
+
+pub fn strategy_to_string(strategy: &AssignmentStrategy) -> &'static str {
+    match strategy {
+        AssignmentStrategy::Manual => "Manual",
+        AssignmentStrategy::RoundRobin => "Round-robin",
+        AssignmentStrategy::LeastLoaded => "Least-loaded",
+    }
+}
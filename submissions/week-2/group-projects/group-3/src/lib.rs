@@ -1,17 +1,36 @@
+pub mod agent;
+pub mod message;
+pub mod metrics;
 pub mod queue;
+pub mod sla;
+pub mod store;
 pub mod ticket;
 pub mod ui;
 
+pub use agent::{Agent, Assignment, AssignmentError, AssignmentStrategy};
+pub use message::{Author, Message, MessageError, Visibility};
+pub use metrics::{Metrics, compute_metrics, metrics_to_json};
 pub use queue::{
-    CustomerSupportQueue, add_ticket_to_queue, create_queue, get_ticket_from_queue,
-    remove_ticket_from_queue, switch_to_hashmap, update_ticket_in_queue,
+    CustomerSupportQueue, add_agent, add_message, add_ticket_to_queue, add_ticket_with_skill,
+    agent_load, agent_tickets, all_tickets, assign_ticket, breached_tickets, change_ticket_status,
+    create_queue, find_tickets_by_customer, find_tickets_by_priority, get_ticket_from_queue,
+    next_ticket, public_thread, reassign_ticket, remove_ticket_from_queue, resolve_ticket,
+    respond_to_ticket, switch_to_hashmap, tickets_by_priority, update_ticket_in_queue,
+};
+pub use sla::{SlaClock, SlaConfig, SlaStatus, SlaTarget, create_sla_config, sla_status};
+pub use store::TicketStore;
+pub use ticket::{
+    Priority, StatusChange, Ticket, TicketStatus, TransitionError, allowed_transitions,
+    create_ticket, priority_to_string, status_to_string, string_to_priority, string_to_status,
 };
-pub use ticket::{Priority, Ticket, create_ticket, priority_to_string, string_to_priority};
 pub use ui::{add_ticket, display_menu, edit_ticket, get_user_input, remove_ticket, view_tickets};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sla::{breached_clocks, format_duration, set_sla_target, sla_target};
+    use crate::store::{find_ticket, find_ticket_mut, ticket_count, tickets_in_order};
+    use chrono::{Duration, Utc};
 
     #[test]
     fn test_string_to_priority() {
@@ -43,16 +62,15 @@ mod tests {
         assert_eq!(ticket.customer_name, "John Doe");
         assert_eq!(ticket.issue_description, "Cannot login to system");
         assert_eq!(ticket.priority, Priority::High);
-        assert_eq!(ticket.status, "Open");
+        assert_eq!(ticket.status, TicketStatus::Open);
+        assert!(ticket.status_history.is_empty());
     }
 
     #[test]
     fn test_create_queue() {
         let queue = create_queue();
         assert_eq!(queue.next_id, 1);
-        assert_eq!(queue.use_hashmap, false);
-        assert_eq!(queue.tickets.len(), 0);
-        assert_eq!(queue.ticket_map.len(), 0);
+        assert_eq!(ticket_count(&queue.tickets), 0);
     }
 
     #[test]
@@ -68,11 +86,16 @@ mod tests {
 
         assert_eq!(ticket_id, 1);
         assert_eq!(queue.next_id, 2);
-        assert_eq!(queue.tickets.len(), 1);
-        assert_eq!(queue.ticket_map.len(), 0);
-        assert_eq!(queue.tickets[0].customer_name, "Alice");
-        assert_eq!(queue.tickets[0].issue_description, "Password reset needed");
-        assert_eq!(queue.tickets[0].priority, Priority::Medium);
+        assert_eq!(ticket_count(&queue.tickets), 1);
+        assert_eq!(tickets_in_order(&queue.tickets)[0].customer_name, "Alice");
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].issue_description,
+            "Password reset needed"
+        );
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].priority,
+            Priority::Medium
+        );
     }
 
     #[test]
@@ -92,7 +115,7 @@ mod tests {
             Priority::Critical,
         );
 
-        let tickets = queue.tickets.iter().collect::<Vec<_>>();
+        let tickets = tickets_in_order(&queue.tickets).iter().collect::<Vec<_>>();
         assert_eq!(tickets.len(), 2);
         assert_eq!(tickets[0].customer_name, "Bob");
         assert_eq!(tickets[1].customer_name, "Carol");
@@ -115,14 +138,17 @@ mod tests {
             Priority::High,
         );
 
-        assert_eq!(queue.tickets.len(), 2);
+        assert_eq!(ticket_count(&queue.tickets), 2);
 
+        // Only closed tickets can be removed
+        assert!(!remove_ticket_from_queue(&mut queue, 1));
+        close_ticket(&mut queue, 1);
         assert!(remove_ticket_from_queue(&mut queue, 1));
-        assert_eq!(queue.tickets.len(), 1);
-        assert_eq!(queue.tickets[0].id, 2);
+        assert_eq!(ticket_count(&queue.tickets), 1);
+        assert_eq!(tickets_in_order(&queue.tickets)[0].id, 2);
 
         assert!(!remove_ticket_from_queue(&mut queue, 999));
-        assert_eq!(queue.tickets.len(), 1);
+        assert_eq!(ticket_count(&queue.tickets), 1);
     }
 
     #[test]
@@ -142,17 +168,21 @@ mod tests {
             Priority::Low,
         );
 
-        assert_eq!(queue.tickets.len(), 2);
-        assert_eq!(queue.ticket_map.len(), 0);
-        assert_eq!(queue.use_hashmap, false);
+        assert_eq!(ticket_count(&queue.tickets), 2);
 
+        // The store is already indexed, so switching changes nothing
         switch_to_hashmap(&mut queue);
 
-        assert_eq!(queue.tickets.len(), 0);
-        assert_eq!(queue.ticket_map.len(), 2);
-        assert_eq!(queue.use_hashmap, true);
-        assert_eq!(queue.ticket_map.get(&1).unwrap().customer_name, "Frank");
-        assert_eq!(queue.ticket_map.get(&2).unwrap().customer_name, "Grace");
+        assert_eq!(ticket_count(&queue.tickets), 2);
+        assert_eq!(tickets_in_order(&queue.tickets)[0].customer_name, "Frank");
+        assert_eq!(
+            find_ticket(&queue.tickets, 1).unwrap().customer_name,
+            "Frank"
+        );
+        assert_eq!(
+            find_ticket(&queue.tickets, 2).unwrap().customer_name,
+            "Grace"
+        );
     }
 
     #[test]
@@ -168,9 +198,11 @@ mod tests {
         );
 
         assert_eq!(ticket_id, 1);
-        assert_eq!(queue.ticket_map.len(), 1);
-        assert_eq!(queue.tickets.len(), 0);
-        assert_eq!(queue.ticket_map.get(&1).unwrap().customer_name, "Henry");
+        assert_eq!(ticket_count(&queue.tickets), 1);
+        assert_eq!(
+            find_ticket(&queue.tickets, 1).unwrap().customer_name,
+            "Henry"
+        );
     }
 
     #[test]
@@ -191,15 +223,16 @@ mod tests {
             Priority::Medium,
         );
 
-        assert_eq!(queue.ticket_map.len(), 2);
+        assert_eq!(ticket_count(&queue.tickets), 2);
 
+        close_ticket(&mut queue, 1);
         assert!(remove_ticket_from_queue(&mut queue, 1));
-        assert_eq!(queue.ticket_map.len(), 1);
-        assert!(queue.ticket_map.get(&1).is_none());
-        assert!(queue.ticket_map.get(&2).is_some());
+        assert_eq!(ticket_count(&queue.tickets), 1);
+        assert!(find_ticket(&queue.tickets, 1).is_none());
+        assert!(find_ticket(&queue.tickets, 2).is_some());
 
         assert!(!remove_ticket_from_queue(&mut queue, 1));
-        assert_eq!(queue.ticket_map.len(), 1);
+        assert_eq!(ticket_count(&queue.tickets), 1);
     }
 
     #[test]
@@ -308,12 +341,10 @@ mod tests {
             Priority::High,
         );
 
-        assert_eq!(queue.tickets.len(), 3);
-        assert_eq!(queue.use_hashmap, false);
+        assert_eq!(ticket_count(&queue.tickets), 3);
 
         switch_to_hashmap(&mut queue);
-        assert_eq!(queue.ticket_map.len(), 3);
-        assert_eq!(queue.use_hashmap, true);
+        assert_eq!(ticket_count(&queue.tickets), 3);
 
         add_ticket_to_queue(
             &mut queue,
@@ -328,10 +359,11 @@ mod tests {
             Priority::Low,
         );
 
-        assert_eq!(queue.ticket_map.len(), 5);
+        assert_eq!(ticket_count(&queue.tickets), 5);
 
+        close_ticket(&mut queue, 1);
         assert!(remove_ticket_from_queue(&mut queue, 1));
-        assert_eq!(queue.ticket_map.len(), 4);
+        assert_eq!(ticket_count(&queue.tickets), 4);
 
         assert!(update_ticket_in_queue(
             &mut queue,
@@ -348,7 +380,7 @@ mod tests {
     fn test_empty_queue_operations() {
         let mut queue = create_queue();
 
-        let tickets = queue.tickets.iter().collect::<Vec<_>>();
+        let tickets = tickets_in_order(&queue.tickets).iter().collect::<Vec<_>>();
         assert_eq!(tickets.len(), 0);
 
         assert!(!remove_ticket_from_queue(&mut queue, 1));
@@ -357,4 +389,834 @@ mod tests {
 
         assert!(!update_ticket_in_queue(&mut queue, 1, None, None, None));
     }
+
+    fn close_ticket(queue: &mut CustomerSupportQueue, ticket_id: u32) {
+        resolve_ticket(queue, ticket_id, "Fixed".to_string()).unwrap();
+        change_ticket_status(queue, ticket_id, TicketStatus::Closed, None).unwrap();
+    }
+
+    fn ticket_ids(tickets: &[&Ticket]) -> Vec<u32> {
+        tickets.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_next_ticket_by_priority_then_age() {
+        let mut queue = create_queue();
+        assert!(next_ticket(&queue).is_none());
+
+        add_ticket_to_queue(
+            &mut queue,
+            "Uche".to_string(),
+            "Typo".to_string(),
+            Priority::Low,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Vera".to_string(),
+            "Outage".to_string(),
+            Priority::Critical,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Wale".to_string(),
+            "Slow".to_string(),
+            Priority::High,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Xena".to_string(),
+            "Outage too".to_string(),
+            Priority::Critical,
+        );
+
+        assert_eq!(next_ticket(&queue).unwrap().id, 2);
+        assert_eq!(ticket_ids(&tickets_by_priority(&queue)), vec![2, 4, 3, 1]);
+
+        // The order is the same once tickets live in the HashMap
+        switch_to_hashmap(&mut queue);
+        assert_eq!(ticket_ids(&tickets_by_priority(&queue)), vec![2, 4, 3, 1]);
+    }
+
+    #[test]
+    fn test_priority_change_reorders_queue() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Yemi".to_string(),
+            "A".to_string(),
+            Priority::Medium,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Zara".to_string(),
+            "B".to_string(),
+            Priority::High,
+        );
+
+        assert!(update_ticket_in_queue(
+            &mut queue,
+            1,
+            None,
+            None,
+            Some(Priority::Critical)
+        ));
+        assert_eq!(next_ticket(&queue).unwrap().id, 1);
+        assert_eq!(queue.work_order.len(), 2);
+    }
+
+    #[test]
+    fn test_resolved_and_removed_tickets_leave_work_order() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Ada".to_string(),
+            "A".to_string(),
+            Priority::High,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Bayo".to_string(),
+            "B".to_string(),
+            Priority::Low,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Chi".to_string(),
+            "C".to_string(),
+            Priority::Medium,
+        );
+
+        resolve_ticket(&mut queue, 1, "Restarted the service".to_string()).unwrap();
+        let resolved = get_ticket_from_queue(&queue, 1).unwrap();
+        assert_eq!(resolved.status, TicketStatus::Resolved);
+        assert!(resolved.resolved_at.is_some() && resolved.first_response_at.is_some());
+        assert_eq!(next_ticket(&queue).unwrap().id, 3);
+
+        // Resolved tickets are listed after the open ones
+        assert_eq!(ticket_ids(&tickets_by_priority(&queue)), vec![3, 2, 1]);
+
+        close_ticket(&mut queue, 3);
+        assert!(remove_ticket_from_queue(&mut queue, 3));
+        assert_eq!(next_ticket(&queue).unwrap().id, 2);
+        assert_eq!(
+            resolve_ticket(&mut queue, 99, "Note".to_string()),
+            Err(TransitionError::TicketNotFound(99))
+        );
+    }
+
+    #[test]
+    fn test_sla_clocks() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Dayo".to_string(),
+            "Down".to_string(),
+            Priority::Critical,
+        );
+        let now = Utc::now();
+        find_ticket_mut(&mut queue.tickets, 1).unwrap().created_at = now - Duration::minutes(10);
+
+        let status = sla_status(&queue.sla, &tickets_in_order(&queue.tickets)[0], now).unwrap();
+        assert_eq!(status.clock, SlaClock::Response);
+        assert_eq!(status.remaining, Duration::minutes(5));
+
+        assert!(respond_to_ticket(&mut queue, 1));
+        let responded_at = tickets_in_order(&queue.tickets)[0].first_response_at;
+        assert!(respond_to_ticket(&mut queue, 1));
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].first_response_at,
+            responded_at
+        );
+
+        let status = sla_status(&queue.sla, &tickets_in_order(&queue.tickets)[0], now).unwrap();
+        assert_eq!(status.clock, SlaClock::Resolution);
+        assert_eq!(status.remaining, Duration::minutes(230));
+
+        resolve_ticket(&mut queue, 1, "Failed over".to_string()).unwrap();
+        assert!(sla_status(&queue.sla, &tickets_in_order(&queue.tickets)[0], now).is_none());
+    }
+
+    #[test]
+    fn test_breached_tickets() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Efe".to_string(),
+            "Late".to_string(),
+            Priority::High,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Femi".to_string(),
+            "Fine".to_string(),
+            Priority::High,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Gbenga".to_string(),
+            "Answered late".to_string(),
+            Priority::High,
+        );
+        let now = Utc::now();
+        find_ticket_mut(&mut queue.tickets, 1).unwrap().created_at = now - Duration::hours(2);
+        find_ticket_mut(&mut queue.tickets, 3).unwrap().created_at = now - Duration::hours(3);
+        find_ticket_mut(&mut queue.tickets, 3)
+            .unwrap()
+            .first_response_at = Some(now - Duration::minutes(90));
+
+        assert_eq!(ticket_ids(&breached_tickets(&queue, now)), vec![1, 3]);
+        assert_eq!(
+            breached_clocks(&queue.sla, &tickets_in_order(&queue.tickets)[0], now),
+            vec![SlaClock::Response]
+        );
+
+        // A missed response stays a breach after the ticket is answered
+        let later = now + Duration::hours(6);
+        assert_eq!(
+            breached_clocks(&queue.sla, &tickets_in_order(&queue.tickets)[2], later),
+            vec![SlaClock::Response, SlaClock::Resolution]
+        );
+    }
+
+    #[test]
+    fn test_configurable_sla() {
+        let mut queue = create_queue();
+        let target = SlaTarget {
+            response: Duration::minutes(30),
+            resolution: Duration::hours(2),
+        };
+        set_sla_target(&mut queue.sla, &Priority::Low, target);
+        assert_eq!(sla_target(&queue.sla, &Priority::Low), target);
+        assert_eq!(
+            sla_target(&queue.sla, &Priority::Critical).response,
+            Duration::minutes(15)
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(12)), "12m");
+        assert_eq!(format_duration(Duration::minutes(65)), "1h 05m");
+        assert_eq!(format_duration(-Duration::hours(51)), "2d 3h");
+    }
+
+    #[test]
+    fn test_status_parsing() {
+        assert_eq!(
+            string_to_status("in progress"),
+            Some(TicketStatus::InProgress)
+        );
+        assert_eq!(
+            string_to_status("Waiting-On-Customer"),
+            Some(TicketStatus::WaitingOnCustomer)
+        );
+        assert_eq!(string_to_status("CLOSED"), Some(TicketStatus::Closed));
+        assert_eq!(string_to_status("done"), None);
+        assert_eq!(
+            status_to_string(&TicketStatus::WaitingOnCustomer),
+            "Waiting on Customer"
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_transitions() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Hauwa".to_string(),
+            "VPN".to_string(),
+            Priority::Medium,
+        );
+
+        assert_eq!(
+            change_ticket_status(&mut queue, 1, TicketStatus::Closed, None),
+            Err(TransitionError::NotAllowed {
+                from: TicketStatus::Open,
+                to: TicketStatus::Closed
+            })
+        );
+        change_ticket_status(&mut queue, 1, TicketStatus::InProgress, None).unwrap();
+        assert!(
+            tickets_in_order(&queue.tickets)[0]
+                .first_response_at
+                .is_some()
+        );
+        change_ticket_status(
+            &mut queue,
+            1,
+            TicketStatus::WaitingOnCustomer,
+            Some("Asked for logs".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            change_ticket_status(&mut queue, 1, TicketStatus::Open, None),
+            Err(TransitionError::NotAllowed {
+                from: TicketStatus::WaitingOnCustomer,
+                to: TicketStatus::Open
+            })
+        );
+
+        let history = &tickets_in_order(&queue.tickets)[0].status_history;
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].from, history[0].to),
+            (TicketStatus::Open, TicketStatus::InProgress)
+        );
+        assert_eq!(history[1].note.as_deref(), Some("Asked for logs"));
+        assert!(history[0].at <= history[1].at);
+    }
+
+    #[test]
+    fn test_resolving_requires_note() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Ifeoma".to_string(),
+            "Bug".to_string(),
+            Priority::High,
+        );
+
+        assert_eq!(
+            change_ticket_status(&mut queue, 1, TicketStatus::Resolved, None),
+            Err(TransitionError::MissingResolutionNote)
+        );
+        assert_eq!(
+            resolve_ticket(&mut queue, 1, "   ".to_string()),
+            Err(TransitionError::MissingResolutionNote)
+        );
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].status,
+            TicketStatus::Open
+        );
+        assert!(
+            tickets_in_order(&queue.tickets)[0]
+                .status_history
+                .is_empty()
+        );
+
+        resolve_ticket(&mut queue, 1, " Patched in 2.1 ".to_string()).unwrap();
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0]
+                .resolution_note
+                .as_deref(),
+            Some("Patched in 2.1")
+        );
+        assert!(next_ticket(&queue).is_none());
+    }
+
+    #[test]
+    fn test_reopen_returns_to_work_order() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Jide".to_string(),
+            "Crash".to_string(),
+            Priority::Low,
+        );
+        close_ticket(&mut queue, 1);
+        assert!(next_ticket(&queue).is_none());
+
+        change_ticket_status(
+            &mut queue,
+            1,
+            TicketStatus::Reopened,
+            Some("Crashed again".to_string()),
+        )
+        .unwrap();
+        let ticket = &tickets_in_order(&queue.tickets)[0];
+        assert_eq!(ticket.status, TicketStatus::Reopened);
+        assert!(ticket.resolved_at.is_none() && ticket.resolution_note.is_none());
+        assert_eq!(ticket.status_history.len(), 3);
+        assert_eq!(next_ticket(&queue).unwrap().id, 1);
+
+        // A reopened ticket is no longer closed, so it cannot be purged
+        assert!(!remove_ticket_from_queue(&mut queue, 1));
+    }
+
+    fn add_simple_ticket(queue: &mut CustomerSupportQueue, skill: Option<&str>) -> u32 {
+        add_ticket_with_skill(
+            queue,
+            "Customer".to_string(),
+            "Issue".to_string(),
+            Priority::Medium,
+            skill.map(|s| s.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_manual_strategy_leaves_tickets_unassigned() {
+        let mut queue = create_queue();
+        add_agent(&mut queue, "Ada".to_string(), vec![], 5);
+        add_simple_ticket(&mut queue, None);
+
+        assert_eq!(tickets_in_order(&queue.tickets)[0].assigned_agent, None);
+        assert_eq!(
+            reassign_ticket(&mut queue, 1, 1, "Cover".to_string()),
+            Err(AssignmentError::NotAssigned(1))
+        );
+
+        assert_eq!(
+            assign_ticket(&mut queue, 1, 9),
+            Err(AssignmentError::AgentNotFound(9))
+        );
+        assign_ticket(&mut queue, 1, 1).unwrap();
+        assert_eq!(tickets_in_order(&queue.tickets)[0].assigned_agent, Some(1));
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].assignment_history[0].from,
+            None
+        );
+        assert_eq!(
+            assign_ticket(&mut queue, 1, 1),
+            Err(AssignmentError::AlreadyAssigned(1))
+        );
+        assert_eq!(
+            assign_ticket(&mut queue, 7, 1),
+            Err(AssignmentError::TicketNotFound(7))
+        );
+    }
+
+    #[test]
+    fn test_round_robin_assignment() {
+        let mut queue = create_queue();
+        queue.assignment_strategy = AssignmentStrategy::RoundRobin;
+        add_agent(&mut queue, "Ada".to_string(), vec![], 5);
+        add_agent(
+            &mut queue,
+            "Bayo".to_string(),
+            vec!["Billing".to_string()],
+            5,
+        );
+        add_agent(&mut queue, "Chi".to_string(), vec![], 5);
+
+        for _ in 0..4 {
+            add_simple_ticket(&mut queue, None);
+        }
+        // Only Bayo can take billing tickets, so the turn passes over the others
+        add_simple_ticket(&mut queue, Some(" billing "));
+        add_simple_ticket(&mut queue, None);
+
+        let owners: Vec<Option<u32>> = tickets_in_order(&queue.tickets)
+            .iter()
+            .map(|t| t.assigned_agent)
+            .collect();
+        assert_eq!(
+            owners,
+            vec![Some(1), Some(2), Some(3), Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[4]
+                .required_skill
+                .as_deref(),
+            Some("billing")
+        );
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].assignment_history[0].reason,
+            "Round-robin assignment"
+        );
+    }
+
+    #[test]
+    fn test_least_loaded_assignment_respects_capacity() {
+        let mut queue = create_queue();
+        queue.assignment_strategy = AssignmentStrategy::LeastLoaded;
+        add_agent(&mut queue, "Ada".to_string(), vec![], 1);
+        add_agent(&mut queue, "Bayo".to_string(), vec![], 2);
+
+        for _ in 0..4 {
+            add_simple_ticket(&mut queue, None);
+        }
+        let owners: Vec<Option<u32>> = tickets_in_order(&queue.tickets)
+            .iter()
+            .map(|t| t.assigned_agent)
+            .collect();
+        // Ties go to the earlier agent; once everyone is full tickets wait unassigned
+        assert_eq!(owners, vec![Some(1), Some(2), Some(2), None]);
+        assert_eq!(agent_load(&queue, 1), 1);
+        assert_eq!(agent_load(&queue, 2), 2);
+        assert_eq!(
+            assign_ticket(&mut queue, 4, 1),
+            Err(AssignmentError::AtCapacity(1))
+        );
+
+        // Resolving frees up capacity
+        resolve_ticket(&mut queue, 1, "Done".to_string()).unwrap();
+        assert_eq!(agent_load(&queue, 1), 0);
+        assign_ticket(&mut queue, 4, 1).unwrap();
+        assert_eq!(
+            reassign_ticket(&mut queue, 1, 2, "Follow-up".to_string()),
+            Err(AssignmentError::TicketNotActive(1))
+        );
+    }
+
+    #[test]
+    fn test_skill_required_for_assignment() {
+        let mut queue = create_queue();
+        queue.assignment_strategy = AssignmentStrategy::LeastLoaded;
+        add_agent(
+            &mut queue,
+            "Ada".to_string(),
+            vec!["network".to_string()],
+            3,
+        );
+        add_simple_ticket(&mut queue, Some("billing"));
+
+        assert_eq!(tickets_in_order(&queue.tickets)[0].assigned_agent, None);
+        assert_eq!(
+            assign_ticket(&mut queue, 1, 1),
+            Err(AssignmentError::MissingSkill {
+                agent_id: 1,
+                skill: "billing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_reassignment_records_reason() {
+        let mut queue = create_queue();
+        queue.assignment_strategy = AssignmentStrategy::RoundRobin;
+        add_agent(&mut queue, "Ada".to_string(), vec![], 3);
+        add_agent(&mut queue, "Bayo".to_string(), vec![], 3);
+        add_ticket_to_queue(
+            &mut queue,
+            "Kemi".to_string(),
+            "Refund".to_string(),
+            Priority::High,
+        );
+        add_simple_ticket(&mut queue, None);
+        assert_eq!(agent_tickets(&queue, 1)[0].id, 1);
+
+        assert_eq!(
+            reassign_ticket(&mut queue, 1, 2, "  ".to_string()),
+            Err(AssignmentError::MissingReason)
+        );
+        assert_eq!(
+            reassign_ticket(&mut queue, 1, 1, "Same".to_string()),
+            Err(AssignmentError::AlreadyAssigned(1))
+        );
+        reassign_ticket(&mut queue, 1, 2, "Ada is on leave".to_string()).unwrap();
+
+        let last = tickets_in_order(&queue.tickets)[0]
+            .assignment_history
+            .last()
+            .unwrap();
+        assert_eq!((last.from, last.to), (Some(1), 2));
+        assert_eq!(last.reason, "Ada is on leave");
+        assert!(agent_tickets(&queue, 1).is_empty());
+        // Agent views follow work order: the High ticket comes before the Medium one
+        let ids: Vec<u32> = agent_tickets(&queue, 2).iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_thread_starts_with_description() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Lola".to_string(),
+            "Cannot log in".to_string(),
+            Priority::Medium,
+        );
+
+        let ticket = &tickets_in_order(&queue.tickets)[0];
+        assert_eq!(ticket.thread.len(), 1);
+        assert_eq!(
+            ticket.thread[0].author,
+            Author::Customer("Lola".to_string())
+        );
+        assert_eq!(ticket.thread[0].body, "Cannot log in");
+        assert_eq!(
+            message::last_changed_at(&ticket.thread[0]),
+            ticket.created_at
+        );
+    }
+
+    #[test]
+    fn test_editing_description_keeps_earlier_text() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Lola".to_string(),
+            "Cannot log in".to_string(),
+            Priority::Medium,
+        );
+        update_ticket_in_queue(
+            &mut queue,
+            1,
+            None,
+            Some("Cannot log in on mobile".to_string()),
+            None,
+        );
+
+        let ticket = &tickets_in_order(&queue.tickets)[0];
+        assert_eq!(ticket.issue_description, "Cannot log in on mobile");
+        assert_eq!(ticket.thread.len(), 1);
+        assert_eq!(ticket.thread[0].body, "Cannot log in on mobile");
+        assert_eq!(ticket.thread[0].revisions.len(), 1);
+        assert_eq!(ticket.thread[0].revisions[0].body, "Cannot log in");
+
+        // Saving the same text again is not a revision
+        update_ticket_in_queue(
+            &mut queue,
+            1,
+            None,
+            Some("Cannot log in on mobile".to_string()),
+            None,
+        );
+        assert_eq!(
+            tickets_in_order(&queue.tickets)[0].thread[0]
+                .revisions
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_messages_and_internal_notes() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Musa".to_string(),
+            "Invoice is wrong".to_string(),
+            Priority::High,
+        );
+
+        add_message(
+            &mut queue,
+            1,
+            Author::Agent("Ada".to_string()),
+            Visibility::Internal,
+            "Check the tax rate".to_string(),
+        )
+        .unwrap();
+        // An internal note is not a response to the customer
+        assert!(
+            tickets_in_order(&queue.tickets)[0]
+                .first_response_at
+                .is_none()
+        );
+
+        add_message(
+            &mut queue,
+            1,
+            Author::Agent("Ada".to_string()),
+            Visibility::Public,
+            " Looking into it ".to_string(),
+        )
+        .unwrap();
+        add_message(
+            &mut queue,
+            1,
+            Author::Customer("Musa".to_string()),
+            Visibility::Public,
+            "Thanks".to_string(),
+        )
+        .unwrap();
+
+        let ticket = &tickets_in_order(&queue.tickets)[0];
+        assert!(ticket.first_response_at.is_some());
+        let bodies: Vec<&str> = ticket.thread.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(
+            bodies,
+            vec![
+                "Invoice is wrong",
+                "Check the tax rate",
+                "Looking into it",
+                "Thanks"
+            ]
+        );
+        assert_eq!(public_thread(ticket).len(), 3);
+        assert!(ticket::last_activity(ticket) >= ticket.thread[3].at);
+    }
+
+    #[test]
+    fn test_message_errors() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Musa".to_string(),
+            "Invoice is wrong".to_string(),
+            Priority::High,
+        );
+
+        assert_eq!(
+            add_message(
+                &mut queue,
+                1,
+                Author::Agent("Ada".to_string()),
+                Visibility::Public,
+                "  ".to_string()
+            ),
+            Err(MessageError::EmptyMessage)
+        );
+        assert_eq!(
+            add_message(
+                &mut queue,
+                1,
+                Author::Customer("Musa".to_string()),
+                Visibility::Internal,
+                "Hello".to_string()
+            ),
+            Err(MessageError::CustomerInternalNote)
+        );
+        assert_eq!(
+            add_message(
+                &mut queue,
+                2,
+                Author::Customer("Musa".to_string()),
+                Visibility::Public,
+                "Hello".to_string()
+            ),
+            Err(MessageError::TicketNotFound(2))
+        );
+        assert_eq!(tickets_in_order(&queue.tickets)[0].thread.len(), 1);
+    }
+
+    #[test]
+    fn test_store_keeps_insertion_order_after_removal() {
+        let mut queue = create_queue();
+        for name in ["Uche", "Vera", "Wale", "Xena"] {
+            add_ticket_to_queue(
+                &mut queue,
+                name.to_string(),
+                "Issue".to_string(),
+                Priority::Low,
+            );
+        }
+        close_ticket(&mut queue, 2);
+        assert!(remove_ticket_from_queue(&mut queue, 2));
+
+        let ids: Vec<u32> = all_tickets(&queue).iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        // Lookups still find tickets that moved up
+        assert_eq!(
+            get_ticket_from_queue(&queue, 4).unwrap().customer_name,
+            "Xena"
+        );
+        assert!(get_ticket_from_queue(&queue, 2).is_none());
+        assert!(find_tickets_by_customer(&queue, "Vera").is_empty());
+    }
+
+    #[test]
+    fn test_store_indexes_follow_updates() {
+        let mut queue = create_queue();
+        add_ticket_to_queue(
+            &mut queue,
+            "Yemi".to_string(),
+            "Issue 1".to_string(),
+            Priority::Low,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "Zara".to_string(),
+            "Issue 2".to_string(),
+            Priority::High,
+        );
+        add_ticket_to_queue(
+            &mut queue,
+            "yemi ".to_string(),
+            "Issue 3".to_string(),
+            Priority::Low,
+        );
+
+        let ids = |tickets: Vec<&Ticket>| tickets.iter().map(|t| t.id).collect::<Vec<u32>>();
+        assert_eq!(ids(find_tickets_by_customer(&queue, "YEMI")), vec![1, 3]);
+        assert_eq!(
+            ids(find_tickets_by_priority(&queue, Priority::Low)),
+            vec![1, 3]
+        );
+
+        update_ticket_in_queue(
+            &mut queue,
+            3,
+            Some("Zara".to_string()),
+            None,
+            Some(Priority::High),
+        );
+        assert_eq!(ids(find_tickets_by_customer(&queue, "yemi")), vec![1]);
+        assert_eq!(ids(find_tickets_by_customer(&queue, "Zara")), vec![2, 3]);
+        assert_eq!(
+            ids(find_tickets_by_priority(&queue, Priority::Low)),
+            vec![1]
+        );
+        assert_eq!(
+            ids(find_tickets_by_priority(&queue, Priority::High)),
+            vec![2, 3]
+        );
+        assert!(find_tickets_by_priority(&queue, Priority::Critical).is_empty());
+    }
+
+    #[test]
+    fn test_duration_stats() {
+        let minutes = |m: &[i64]| m.iter().map(|m| Duration::minutes(*m)).collect();
+        let stats = metrics::duration_stats(minutes(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]));
+        assert_eq!(stats.count, 10);
+        assert_eq!(stats.mean_seconds, Some(55 * 60));
+        assert_eq!(stats.p90_seconds, Some(90 * 60));
+
+        let stats = metrics::duration_stats(minutes(&[5]));
+        assert_eq!(
+            (stats.mean_seconds, stats.p90_seconds),
+            (Some(300), Some(300))
+        );
+        assert_eq!(metrics::duration_stats(Vec::new()).mean_seconds, None);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let mut queue = create_queue();
+        let now = Utc::now();
+        for (name, priority) in [
+            ("Ada", Priority::High),
+            ("Bayo", Priority::High),
+            ("ada", Priority::Low),
+        ] {
+            add_ticket_to_queue(&mut queue, name.to_string(), "Issue".to_string(), priority);
+        }
+        for (id, age) in [
+            (1, Duration::days(2)),
+            (2, Duration::hours(2)),
+            (3, Duration::days(9)),
+        ] {
+            find_ticket_mut(&mut queue.tickets, id).unwrap().created_at = now - age;
+        }
+        close_ticket(&mut queue, 1);
+        respond_to_ticket(&mut queue, 2);
+
+        let metrics = compute_metrics(&queue, now);
+
+        let today = now.date_naive();
+        let today_count = metrics.daily.iter().find(|d| d.date == today).unwrap();
+        assert_eq!(today_count.closed, 1);
+        assert_eq!(metrics.daily.iter().map(|d| d.opened).sum::<usize>(), 3);
+
+        let high = &metrics.by_priority[1];
+        assert_eq!(high.priority, "High");
+        assert_eq!(high.time_to_first_response.count, 2);
+        assert_eq!(high.time_to_resolve.count, 1);
+        assert!(high.time_to_resolve.mean_seconds.unwrap() >= 2 * 24 * 3600);
+        assert_eq!(metrics.by_priority[0].time_to_resolve.p90_seconds, None);
+
+        let ages: Vec<usize> = metrics.backlog_age.iter().map(|b| b.count).collect();
+        assert_eq!(ages, vec![0, 1, 0, 0, 0, 1]);
+
+        assert_eq!(metrics.customers[0].customer, "Ada");
+        assert_eq!(
+            (metrics.customers[0].total, metrics.customers[0].open),
+            (2, 1)
+        );
+        assert_eq!(metrics.customers[1].customer, "Bayo");
+
+        // Purging a closed ticket leaves the figures as they were
+        assert!(remove_ticket_from_queue(&mut queue, 1));
+        assert_eq!(queue.purged.len(), 1);
+        assert_eq!(compute_metrics(&queue, now), metrics);
+
+        let json: serde_json::Value = serde_json::from_str(&metrics_to_json(&metrics)).unwrap();
+        assert_eq!(json["customers"][0]["total"], 2);
+        assert_eq!(json["backlog_age"][5]["label"], "> 7d");
+        assert_eq!(
+            json["daily"][0]["date"],
+            (now - Duration::days(9)).date_naive().to_string()
+        );
+    }
 }
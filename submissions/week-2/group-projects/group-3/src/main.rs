@@ -1,10 +1,8 @@
-mod queue;
-mod ticket;
-mod ui;
-
-use crate::queue::{create_queue, switch_to_hashmap};
-use crate::ui::{
-    add_ticket, display_menu, edit_ticket, get_user_input, remove_ticket, view_tickets,
+use group_3::queue::create_queue;
+use group_3::ui::{
+    add_ticket, change_status, display_menu, edit_ticket, get_user_input, manage_agents,
+    remove_ticket, view_breached_tickets, view_dashboard, view_thread, view_tickets,
+    work_next_ticket,
 };
 
 fn main() {
@@ -20,21 +18,15 @@ fn main() {
 
         match choice.as_str() {
             "1" => add_ticket(&mut queue),
-            "2" => view_tickets(&mut queue),
-            "3" => {
-                if stage >= 2 {
-                    remove_ticket(&mut queue);
-                } else {
-                    println!("Invalid choice. Please enter a valid option.");
-                }
-            }
-            "4" => {
-                if stage >= 3 {
-                    edit_ticket(&mut queue);
-                } else {
-                    println!("Invalid choice. Please enter a valid option.");
-                }
-            }
+            "2" => view_tickets(&queue),
+            "3" if stage >= 2 => remove_ticket(&mut queue),
+            "4" if stage >= 3 => edit_ticket(&mut queue),
+            "5" => work_next_ticket(&mut queue),
+            "6" => view_breached_tickets(&queue),
+            "7" => change_status(&mut queue),
+            "8" => manage_agents(&mut queue),
+            "9" => view_thread(&mut queue),
+            "10" => view_dashboard(&queue),
             "0" => {
                 println!("Thank you for using Customer Support Queue System!");
                 break;
@@ -46,7 +38,6 @@ fn main() {
 
         if stage == 1 {
             stage = 2;
-            switch_to_hashmap(&mut queue);
             println!("Advanced to Stage 2 (Remove functionality enabled)!");
         }
 
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Who wrote a message, with the name they wrote it under.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Author {
+    Customer(String),
+    Agent(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    /// Part of the conversation with the customer.
+    Public,
+    /// Seen by support staff only.
+    Internal,
+}
+
+/// Text a message had before it was edited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub body: String,
+    pub replaced_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub author: Author,
+    pub visibility: Visibility,
+    pub body: String,
+    pub at: DateTime<Utc>,
+    /// Earlier versions of `body`, oldest first.
+    pub revisions: Vec<Revision>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageError {
+    TicketNotFound(u32),
+    EmptyMessage,
+    /// Only agents can write internal notes.
+    CustomerInternalNote,
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::TicketNotFound(id) => write!(f, "Ticket #{} not found", id),
+            MessageError::EmptyMessage => write!(f, "A message cannot be empty"),
+            MessageError::CustomerInternalNote => {
+                write!(f, "Customers cannot write internal notes")
+            }
+        }
+    }
+}
+
+pub fn create_message(
+    author: Author,
+    visibility: Visibility,
+    body: String,
+    at: DateTime<Utc>,
+) -> Message {
+    Message {
+        author,
+        visibility,
+        body,
+        at,
+        revisions: Vec::new(),
+    }
+}
+
+/// Replaces the text, keeping the old version in `revisions`. Returns false if nothing changed.
+pub fn edit_message(message: &mut Message, body: String, at: DateTime<Utc>) -> bool {
+    if message.body == body {
+        return false;
+    }
+    let previous = std::mem::replace(&mut message.body, body);
+    message.revisions.push(Revision {
+        body: previous,
+        replaced_at: at,
+    });
+    true
+}
+
+/// When the message was written or last edited.
+pub fn last_changed_at(message: &Message) -> DateTime<Utc> {
+    message
+        .revisions
+        .last()
+        .map_or(message.at, |revision| revision.replaced_at)
+}
+
+/// `Customer Ada` or `Agent Bayo`.
+pub fn author_to_string(author: &Author) -> String {
+    match author {
+        Author::Customer(name) => format!("Customer {}", name),
+        Author::Agent(name) => format!("Agent {}", name),
+    }
+}
+
+pub fn visibility_to_string(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "Public reply",
+        Visibility::Internal => "Internal note",
+    }
+}
@@ -0,0 +1,196 @@
+use crate::queue::{CustomerSupportQueue, all_tickets};
+use crate::ticket::{Priority, Ticket, TicketStatus, is_active, priority_to_string};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Upper bounds of the backlog age buckets; anything older goes in a final open-ended bucket.
+const AGE_BUCKETS: [(&str, i64); 5] = [
+    ("< 1h", 1),
+    ("1h - 4h", 4),
+    ("4h - 1d", 24),
+    ("1d - 3d", 72),
+    ("3d - 7d", 168),
+];
+const OLDEST_BUCKET: &str = "> 7d";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub opened: usize,
+    /// Moves into Closed on that day, so a ticket closed twice counts on both days.
+    pub closed: usize,
+}
+
+/// Mean and 90th percentile over the tickets that have reached a milestone, in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationStats {
+    pub count: usize,
+    pub mean_seconds: Option<i64>,
+    pub p90_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriorityMetrics {
+    pub priority: String,
+    pub time_to_first_response: DurationStats,
+    pub time_to_resolve: DurationStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgeBucket {
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CustomerCount {
+    pub customer: String,
+    pub open: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metrics {
+    pub generated_at: DateTime<Utc>,
+    /// Only days with activity, oldest first.
+    pub daily: Vec<DailyCount>,
+    /// Most urgent priority first.
+    pub by_priority: Vec<PriorityMetrics>,
+    /// How long unresolved tickets have been waiting.
+    pub backlog_age: Vec<AgeBucket>,
+    /// Most tickets first, then by name.
+    pub customers: Vec<CustomerCount>,
+}
+
+/// Counts purged tickets as well as the ones still in the queue, in the order they were added,
+/// so removing closed tickets does not change past figures.
+pub fn compute_metrics(queue: &CustomerSupportQueue, now: DateTime<Utc>) -> Metrics {
+    let mut tickets = all_tickets(queue);
+    tickets.extend(&queue.purged);
+    tickets.sort_by_key(|t| t.id);
+    Metrics {
+        generated_at: now,
+        daily: daily_counts(&tickets),
+        by_priority: [
+            Priority::Critical,
+            Priority::High,
+            Priority::Medium,
+            Priority::Low,
+        ]
+        .iter()
+        .map(|priority| priority_metrics(&tickets, *priority))
+        .collect(),
+        backlog_age: backlog_age(&tickets, now),
+        customers: customer_counts(&tickets),
+    }
+}
+
+pub fn metrics_to_json(metrics: &Metrics) -> String {
+    serde_json::to_string_pretty(metrics).expect("metrics always serialize")
+}
+
+fn daily_counts(tickets: &[&Ticket]) -> Vec<DailyCount> {
+    let mut days: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    for ticket in tickets {
+        days.entry(ticket.created_at.date_naive()).or_default().0 += 1;
+        for change in &ticket.status_history {
+            if change.to == TicketStatus::Closed {
+                days.entry(change.at.date_naive()).or_default().1 += 1;
+            }
+        }
+    }
+    days.into_iter()
+        .map(|(date, (opened, closed))| DailyCount {
+            date,
+            opened,
+            closed,
+        })
+        .collect()
+}
+
+fn priority_metrics(tickets: &[&Ticket], priority: Priority) -> PriorityMetrics {
+    let tickets: Vec<&&Ticket> = tickets.iter().filter(|t| t.priority == priority).collect();
+    let responses = tickets
+        .iter()
+        .filter_map(|t| Some(t.first_response_at? - t.created_at))
+        .collect();
+    let resolutions = tickets
+        .iter()
+        .filter_map(|t| Some(t.resolved_at? - t.created_at))
+        .collect();
+    PriorityMetrics {
+        priority: priority_to_string(&priority).to_string(),
+        time_to_first_response: duration_stats(responses),
+        time_to_resolve: duration_stats(resolutions),
+    }
+}
+
+/// The p90 uses the nearest-rank method: the smallest value at least 90% of samples do not exceed.
+pub fn duration_stats(mut durations: Vec<Duration>) -> DurationStats {
+    if durations.is_empty() {
+        return DurationStats {
+            count: 0,
+            mean_seconds: None,
+            p90_seconds: None,
+        };
+    }
+    durations.sort();
+    let count = durations.len();
+    let total: i64 = durations.iter().map(|d| d.num_seconds()).sum();
+    let rank = (count * 9).div_ceil(10);
+    DurationStats {
+        count,
+        mean_seconds: Some(total / count as i64),
+        p90_seconds: Some(durations[rank - 1].num_seconds()),
+    }
+}
+
+fn backlog_age(tickets: &[&Ticket], now: DateTime<Utc>) -> Vec<AgeBucket> {
+    let mut counts = vec![0; AGE_BUCKETS.len() + 1];
+    for ticket in tickets.iter().filter(|t| is_active(&t.status)) {
+        let age = now - ticket.created_at;
+        let index = AGE_BUCKETS
+            .iter()
+            .position(|(_, hours)| age < Duration::hours(*hours))
+            .unwrap_or(AGE_BUCKETS.len());
+        counts[index] += 1;
+    }
+    AGE_BUCKETS
+        .iter()
+        .map(|(label, _)| *label)
+        .chain([OLDEST_BUCKET])
+        .zip(counts)
+        .map(|(label, count)| AgeBucket {
+            label: label.to_string(),
+            count,
+        })
+        .collect()
+}
+
+/// Names are grouped ignoring case, shown as first entered.
+fn customer_counts(tickets: &[&Ticket]) -> Vec<CustomerCount> {
+    let mut customers: Vec<CustomerCount> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for ticket in tickets {
+        let key = ticket.customer_name.trim().to_lowercase();
+        let index = *positions.entry(key).or_insert_with(|| {
+            customers.push(CustomerCount {
+                customer: ticket.customer_name.trim().to_string(),
+                open: 0,
+                total: 0,
+            });
+            customers.len() - 1
+        });
+        customers[index].total += 1;
+        if is_active(&ticket.status) {
+            customers[index].open += 1;
+        }
+    }
+    customers.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then_with(|| a.customer.cmp(&b.customer))
+    });
+    customers
+}
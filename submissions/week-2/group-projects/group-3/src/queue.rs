@@ -1,19 +1,54 @@
-use crate::ticket::{Priority, Ticket, create_ticket, priority_to_string};
-use std::collections::HashMap;
+use crate::agent::{
+    Agent, Assignment, AssignmentError, AssignmentStrategy, agent_has_skill, create_agent,
+    strategy_to_string,
+};
+use crate::message::{Author, Message, MessageError, Visibility, create_message};
+use crate::sla::{
+    SlaConfig, breached_clocks, clock_to_string, create_sla_config, format_duration, sla_status,
+};
+use crate::store::{
+    TicketStore, create_store, find_ticket, find_ticket_mut, insert_ticket, set_ticket_customer,
+    set_ticket_priority, take_ticket, ticket_count, tickets_for_customer, tickets_in_order,
+    tickets_with_priority,
+};
+use crate::ticket::{
+    Priority, StatusChange, Ticket, TicketStatus, TransitionError, allowed_transitions,
+    create_ticket, is_active, last_activity, priority_to_string, revise_description,
+    status_to_string,
+};
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
 
 pub struct CustomerSupportQueue {
-    pub tickets: Vec<Ticket>,
-    pub ticket_map: HashMap<u32, Ticket>,
+    pub tickets: TicketStore,
     pub next_id: u32,
-    pub use_hashmap: bool,
+    pub sla: SlaConfig,
+    /// Unresolved tickets, most urgent first. Ids are handed out in creation order, so within a
+    /// priority the lower id is the older ticket.
+    pub work_order: BTreeSet<(Reverse<Priority>, u32)>,
+    /// In the order they were added, which is the round-robin order.
+    pub agents: Vec<Agent>,
+    pub next_agent_id: u32,
+    pub assignment_strategy: AssignmentStrategy,
+    /// Position in `agents` where the next round-robin search starts.
+    pub round_robin_cursor: usize,
+    /// Closed tickets removed from the queue, oldest removal first. Kept so metrics still count
+    /// them.
+    pub purged: Vec<Ticket>,
 }
 
 pub fn create_queue() -> CustomerSupportQueue {
     CustomerSupportQueue {
-        tickets: Vec::new(),
-        ticket_map: HashMap::new(),
+        tickets: create_store(),
         next_id: 1,
-        use_hashmap: false,
+        sla: create_sla_config(),
+        work_order: BTreeSet::new(),
+        agents: Vec::new(),
+        next_agent_id: 1,
+        assignment_strategy: AssignmentStrategy::Manual,
+        round_robin_cursor: 0,
+        purged: Vec::new(),
     }
 }
 
@@ -23,85 +58,439 @@ pub fn add_ticket_to_queue(
     issue_description: String,
     priority: Priority,
 ) -> u32 {
-    let ticket = create_ticket(queue.next_id, customer_name, issue_description, priority);
-    let id = queue.next_id;
-
-    if queue.use_hashmap {
-        queue.ticket_map.insert(id, ticket);
-    } else {
-        queue.tickets.push(ticket);
-    }
+    add_ticket_with_skill(queue, customer_name, issue_description, priority, None)
+}
 
+/// Adds a ticket that only agents with `required_skill` can take, and assigns it using the
+/// queue's assignment strategy. The ticket stays unassigned if no agent is eligible.
+pub fn add_ticket_with_skill(
+    queue: &mut CustomerSupportQueue,
+    customer_name: String,
+    issue_description: String,
+    priority: Priority,
+    required_skill: Option<String>,
+) -> u32 {
+    let mut ticket = create_ticket(queue.next_id, customer_name, issue_description, priority);
+    ticket.required_skill = required_skill
+        .map(|skill| skill.trim().to_lowercase())
+        .filter(|skill| !skill.is_empty());
+    let id = queue.next_id;
+    queue.work_order.insert((Reverse(ticket.priority), id));
+    insert_ticket(&mut queue.tickets, ticket);
     queue.next_id += 1;
+    auto_assign(queue, id);
     id
 }
 
+/// Open tickets in the order they should be worked, then resolved ones by id.
 pub fn display_tickets(queue: &CustomerSupportQueue) {
-    if queue.tickets.is_empty() && queue.ticket_map.is_empty() {
+    if ticket_count(&queue.tickets) == 0 {
         println!("No tickets found.");
         return;
     }
 
     println!("\n=== CUSTOMER SUPPORT TICKETS ===");
+    print_ticket_header();
+    let now = Utc::now();
+    for ticket in tickets_by_priority(queue) {
+        print_ticket_row(queue, ticket, now);
+    }
+    println!("{:-<136}", "");
+}
+
+pub fn print_ticket_header() {
     println!(
-        "{:<5} {:<20} {:<50} {:<10} {:<10}",
-        "ID", "Customer", "Description", "Priority", "Status"
+        "{:<5} {:<20} {:<30} {:<10} {:<10} {:<12} {:<5} {:<16} {:<20}",
+        "ID",
+        "Customer",
+        "Description",
+        "Priority",
+        "Status",
+        "Agent",
+        "Msgs",
+        "Last Activity",
+        "SLA"
     );
-    println!("{:-<100}", "");
-
-    if queue.use_hashmap {
-        for ticket in queue.ticket_map.values() {
-            println!(
-                "{:<5} {:<20} {:<50} {:<10} {:<10}",
-                ticket.id,
-                ticket.customer_name,
-                if ticket.issue_description.len() > 47 {
-                    format!("{}...", &ticket.issue_description[..47])
-                } else {
-                    ticket.issue_description.clone()
-                },
-                priority_to_string(&ticket.priority),
-                ticket.status
-            );
+    println!("{:-<136}", "");
+}
+
+pub fn print_ticket_row(queue: &CustomerSupportQueue, ticket: &Ticket, now: DateTime<Utc>) {
+    println!(
+        "{:<5} {:<20} {:<30} {:<10} {:<10} {:<12} {:<5} {:<16} {:<20}",
+        ticket.id,
+        ticket.customer_name,
+        truncate(&ticket.issue_description, 27),
+        priority_to_string(&ticket.priority),
+        status_to_string(&ticket.status),
+        agent_summary(queue, ticket),
+        ticket.thread.len(),
+        last_activity(ticket).format("%Y-%m-%d %H:%M").to_string(),
+        sla_summary(queue, ticket, now)
+    );
+}
+
+/// The assigned agent's name, shortened to fit the column, or `-`.
+pub fn agent_summary(queue: &CustomerSupportQueue, ticket: &Ticket) -> String {
+    ticket
+        .assigned_agent
+        .and_then(|id| get_agent(queue, id))
+        .map(|agent| truncate(&agent.name, 9))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Time left on the current SLA clock, e.g. `Response 0h 12m` or `BREACHED Resolution 2h 05m`.
+pub fn sla_summary(queue: &CustomerSupportQueue, ticket: &Ticket, now: DateTime<Utc>) -> String {
+    match sla_status(&queue.sla, ticket, now) {
+        Some(status) if status.remaining < chrono::Duration::zero() => format!(
+            "BREACHED {} {}",
+            clock_to_string(&status.clock),
+            format_duration(status.remaining)
+        ),
+        Some(status) => format!(
+            "{} {}",
+            clock_to_string(&status.clock),
+            format_duration(status.remaining)
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Every ticket in the order it was added.
+pub fn all_tickets(queue: &CustomerSupportQueue) -> Vec<&Ticket> {
+    tickets_in_order(&queue.tickets).iter().collect()
+}
+
+fn get_ticket_mut(queue: &mut CustomerSupportQueue, ticket_id: u32) -> Option<&mut Ticket> {
+    find_ticket_mut(&mut queue.tickets, ticket_id)
+}
+
+/// Tickets of one priority in any status, oldest first.
+pub fn find_tickets_by_priority(queue: &CustomerSupportQueue, priority: Priority) -> Vec<&Ticket> {
+    tickets_with_priority(&queue.tickets, priority)
+}
+
+/// A customer's tickets in any status, oldest first, matching the name ignoring case.
+pub fn find_tickets_by_customer<'a>(
+    queue: &'a CustomerSupportQueue,
+    customer_name: &str,
+) -> Vec<&'a Ticket> {
+    tickets_for_customer(&queue.tickets, customer_name)
+}
+
+/// The unresolved ticket to work on next: highest priority first, oldest first within a priority.
+pub fn next_ticket(queue: &CustomerSupportQueue) -> Option<&Ticket> {
+    let (_, id) = queue.work_order.first()?;
+    get_ticket_from_queue(queue, *id)
+}
+
+/// Unresolved tickets in work order, followed by resolved and closed tickets by id.
+pub fn tickets_by_priority(queue: &CustomerSupportQueue) -> Vec<&Ticket> {
+    let mut tickets: Vec<&Ticket> = queue
+        .work_order
+        .iter()
+        .filter_map(|(_, id)| get_ticket_from_queue(queue, *id))
+        .collect();
+    let mut resolved: Vec<&Ticket> = all_tickets(queue)
+        .into_iter()
+        .filter(|t| !is_active(&t.status))
+        .collect();
+    resolved.sort_by_key(|t| t.id);
+    tickets.extend(resolved);
+    tickets
+}
+
+/// Tickets that have missed a response or resolution deadline, in work order.
+pub fn breached_tickets(queue: &CustomerSupportQueue, now: DateTime<Utc>) -> Vec<&Ticket> {
+    tickets_by_priority(queue)
+        .into_iter()
+        .filter(|t| !breached_clocks(&queue.sla, t, now).is_empty())
+        .collect()
+}
+
+/// Records the first response to a ticket, stopping its response clock. Later calls keep the
+/// original time.
+pub fn respond_to_ticket(queue: &mut CustomerSupportQueue, ticket_id: u32) -> bool {
+    match get_ticket_mut(queue, ticket_id) {
+        Some(ticket) => {
+            ticket.first_response_at.get_or_insert_with(Utc::now);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Moves a ticket to `to` if its lifecycle allows it, recording when and why. Resolving needs
+/// a note. Leaving `Open` counts as the first response; resolving or closing takes the ticket
+/// out of the work order and reopening puts it back.
+pub fn change_ticket_status(
+    queue: &mut CustomerSupportQueue,
+    ticket_id: u32,
+    to: TicketStatus,
+    note: Option<String>,
+) -> Result<(), TransitionError> {
+    let ticket =
+        get_ticket_mut(queue, ticket_id).ok_or(TransitionError::TicketNotFound(ticket_id))?;
+    let from = ticket.status;
+    if !allowed_transitions(&from).contains(&to) {
+        return Err(TransitionError::NotAllowed { from, to });
+    }
+    let note = note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+    if to == TicketStatus::Resolved && note.is_none() {
+        return Err(TransitionError::MissingResolutionNote);
+    }
+
+    let now = Utc::now();
+    match to {
+        TicketStatus::InProgress | TicketStatus::WaitingOnCustomer => {
+            ticket.first_response_at.get_or_insert(now);
+        }
+        TicketStatus::Resolved => {
+            ticket.first_response_at.get_or_insert(now);
+            ticket.resolved_at = Some(now);
+            ticket.resolution_note = note.clone();
         }
+        TicketStatus::Reopened => {
+            ticket.resolved_at = None;
+            ticket.resolution_note = None;
+        }
+        TicketStatus::Open | TicketStatus::Closed => {}
+    }
+    ticket.status = to;
+    ticket.status_history.push(StatusChange {
+        from,
+        to,
+        at: now,
+        note,
+    });
+
+    let key = (Reverse(ticket.priority), ticket_id);
+    if is_active(&to) {
+        queue.work_order.insert(key);
     } else {
-        for ticket in &queue.tickets {
-            println!(
-                "{:<5} {:<20} {:<50} {:<10} {:<10}",
-                ticket.id,
-                ticket.customer_name,
-                if ticket.issue_description.len() > 47 {
-                    format!("{}...", &ticket.issue_description[..47])
-                } else {
-                    ticket.issue_description.clone()
-                },
-                priority_to_string(&ticket.priority),
-                ticket.status
-            );
+        queue.work_order.remove(&key);
+    }
+    Ok(())
+}
+
+pub fn resolve_ticket(
+    queue: &mut CustomerSupportQueue,
+    ticket_id: u32,
+    note: String,
+) -> Result<(), TransitionError> {
+    change_ticket_status(queue, ticket_id, TicketStatus::Resolved, Some(note))
+}
+
+pub fn add_agent(
+    queue: &mut CustomerSupportQueue,
+    name: String,
+    skills: Vec<String>,
+    capacity: usize,
+) -> u32 {
+    let id = queue.next_agent_id;
+    queue.agents.push(create_agent(id, name, skills, capacity));
+    queue.next_agent_id += 1;
+    id
+}
+
+pub fn get_agent(queue: &CustomerSupportQueue, agent_id: u32) -> Option<&Agent> {
+    queue.agents.iter().find(|a| a.id == agent_id)
+}
+
+/// Number of unresolved tickets assigned to the agent.
+pub fn agent_load(queue: &CustomerSupportQueue, agent_id: u32) -> usize {
+    all_tickets(queue)
+        .into_iter()
+        .filter(|t| t.assigned_agent == Some(agent_id) && is_active(&t.status))
+        .count()
+}
+
+/// The agent's unresolved tickets, in work order.
+pub fn agent_tickets(queue: &CustomerSupportQueue, agent_id: u32) -> Vec<&Ticket> {
+    queue
+        .work_order
+        .iter()
+        .filter_map(|(_, id)| get_ticket_from_queue(queue, *id))
+        .filter(|t| t.assigned_agent == Some(agent_id))
+        .collect()
+}
+
+/// Gives an unassigned ticket to an agent who has the skill and room for it.
+pub fn assign_ticket(
+    queue: &mut CustomerSupportQueue,
+    ticket_id: u32,
+    agent_id: u32,
+) -> Result<(), AssignmentError> {
+    let ticket = get_ticket_from_queue(queue, ticket_id)
+        .ok_or(AssignmentError::TicketNotFound(ticket_id))?;
+    if let Some(current) = ticket.assigned_agent {
+        return Err(AssignmentError::AlreadyAssigned(current));
+    }
+    check_agent_can_take(queue, ticket, agent_id)?;
+    record_assignment(queue, ticket_id, agent_id, "Manual assignment".to_string());
+    Ok(())
+}
+
+/// Moves an assigned ticket to another agent, keeping the reason in its assignment history.
+pub fn reassign_ticket(
+    queue: &mut CustomerSupportQueue,
+    ticket_id: u32,
+    agent_id: u32,
+    reason: String,
+) -> Result<(), AssignmentError> {
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(AssignmentError::MissingReason);
+    }
+    let ticket = get_ticket_from_queue(queue, ticket_id)
+        .ok_or(AssignmentError::TicketNotFound(ticket_id))?;
+    match ticket.assigned_agent {
+        None => return Err(AssignmentError::NotAssigned(ticket_id)),
+        Some(current) if current == agent_id => {
+            return Err(AssignmentError::AlreadyAssigned(current));
+        }
+        Some(_) => {}
+    }
+    check_agent_can_take(queue, ticket, agent_id)?;
+    record_assignment(queue, ticket_id, agent_id, reason);
+    Ok(())
+}
+
+fn check_agent_can_take(
+    queue: &CustomerSupportQueue,
+    ticket: &Ticket,
+    agent_id: u32,
+) -> Result<(), AssignmentError> {
+    let agent = get_agent(queue, agent_id).ok_or(AssignmentError::AgentNotFound(agent_id))?;
+    if !is_active(&ticket.status) {
+        return Err(AssignmentError::TicketNotActive(ticket.id));
+    }
+    if let Some(skill) = &ticket.required_skill
+        && !agent_has_skill(agent, Some(skill))
+    {
+        return Err(AssignmentError::MissingSkill {
+            agent_id,
+            skill: skill.clone(),
+        });
+    }
+    if agent_load(queue, agent_id) >= agent.capacity {
+        return Err(AssignmentError::AtCapacity(agent_id));
+    }
+    Ok(())
+}
+
+fn record_assignment(queue: &mut CustomerSupportQueue, ticket_id: u32, to: u32, reason: String) {
+    if let Some(ticket) = get_ticket_mut(queue, ticket_id) {
+        ticket.assignment_history.push(Assignment {
+            from: ticket.assigned_agent,
+            to,
+            at: Utc::now(),
+            reason,
+        });
+        ticket.assigned_agent = Some(to);
+    }
+}
+
+/// Picks an owner for a new ticket according to the queue's strategy.
+fn auto_assign(queue: &mut CustomerSupportQueue, ticket_id: u32) {
+    let Some(ticket) = get_ticket_from_queue(queue, ticket_id) else {
+        return;
+    };
+    let eligible: Vec<usize> = (0..queue.agents.len())
+        .filter(|&index| check_agent_can_take(queue, ticket, queue.agents[index].id).is_ok())
+        .collect();
+
+    let chosen = match queue.assignment_strategy {
+        AssignmentStrategy::Manual => None,
+        AssignmentStrategy::RoundRobin => {
+            let cursor = queue.round_robin_cursor;
+            eligible
+                .iter()
+                .copied()
+                .find(|&index| index >= cursor)
+                .or_else(|| eligible.first().copied())
         }
+        AssignmentStrategy::LeastLoaded => eligible
+            .iter()
+            .copied()
+            .min_by_key(|&index| agent_load(queue, queue.agents[index].id)),
+    };
+
+    if let Some(index) = chosen {
+        let reason = format!(
+            "{} assignment",
+            strategy_to_string(&queue.assignment_strategy)
+        );
+        queue.round_robin_cursor = index + 1;
+        record_assignment(queue, ticket_id, queue.agents[index].id, reason);
+    }
+}
+
+/// Adds a message to the end of the ticket's thread. A public reply from an agent counts as the
+/// first response.
+pub fn add_message(
+    queue: &mut CustomerSupportQueue,
+    ticket_id: u32,
+    author: Author,
+    visibility: Visibility,
+    body: String,
+) -> Result<(), MessageError> {
+    let body = body.trim().to_string();
+    if body.is_empty() {
+        return Err(MessageError::EmptyMessage);
+    }
+    if matches!(author, Author::Customer(_)) && visibility == Visibility::Internal {
+        return Err(MessageError::CustomerInternalNote);
+    }
+    let ticket = get_ticket_mut(queue, ticket_id).ok_or(MessageError::TicketNotFound(ticket_id))?;
+
+    let now = Utc::now();
+    if matches!(author, Author::Agent(_)) && visibility == Visibility::Public {
+        ticket.first_response_at.get_or_insert(now);
     }
-    println!("{:-<100}", "");
+    ticket
+        .thread
+        .push(create_message(author, visibility, body, now));
+    Ok(())
 }
 
+/// The messages the customer can see.
+pub fn public_thread(ticket: &Ticket) -> Vec<&Message> {
+    ticket
+        .thread
+        .iter()
+        .filter(|message| message.visibility == Visibility::Public)
+        .collect()
+}
+
+/// Takes a ticket out of the queue. Only closed tickets can be purged, so every other ticket
+/// keeps its history; returns false for missing or unclosed tickets. The ticket moves to
+/// `purged`, so it no longer shows up in listings or lookups but metrics still count it.
 pub fn remove_ticket_from_queue(queue: &mut CustomerSupportQueue, ticket_id: u32) -> bool {
-    if queue.use_hashmap {
-        queue.ticket_map.remove(&ticket_id).is_some()
-    } else {
-        if let Some(index) = queue.tickets.iter().position(|t| t.id == ticket_id) {
-            queue.tickets.remove(index);
+    match get_ticket_from_queue(queue, ticket_id) {
+        Some(ticket) if ticket.status == TicketStatus::Closed => {}
+        _ => return false,
+    }
+    match take_ticket(&mut queue.tickets, ticket_id) {
+        Some(ticket) => {
+            queue.purged.push(ticket);
             true
-        } else {
-            false
         }
+        None => false,
     }
 }
 
 pub fn get_ticket_from_queue(queue: &CustomerSupportQueue, ticket_id: u32) -> Option<&Ticket> {
-    if queue.use_hashmap {
-        queue.ticket_map.get(&ticket_id)
-    } else {
-        queue.tickets.iter().find(|t| t.id == ticket_id)
-    }
+    find_ticket(&queue.tickets, ticket_id)
 }
 
 pub fn update_ticket_in_queue(
@@ -111,45 +500,31 @@ pub fn update_ticket_in_queue(
     issue_description: Option<String>,
     priority: Option<Priority>,
 ) -> bool {
-    if queue.use_hashmap {
-        if let Some(ticket) = queue.ticket_map.get_mut(&ticket_id) {
-            if let Some(name) = customer_name {
-                ticket.customer_name = name;
-            }
-            if let Some(desc) = issue_description {
-                ticket.issue_description = desc;
-            }
-            if let Some(pri) = priority {
-                ticket.priority = pri;
-            }
-            true
-        } else {
-            false
-        }
-    } else {
-        if let Some(ticket) = queue.tickets.iter_mut().find(|t| t.id == ticket_id) {
-            if let Some(name) = customer_name {
-                ticket.customer_name = name;
-            }
-            if let Some(desc) = issue_description {
-                ticket.issue_description = desc;
-            }
-            if let Some(pri) = priority {
-                ticket.priority = pri;
-            }
-            true
-        } else {
-            false
+    let Some(ticket) = get_ticket_from_queue(queue, ticket_id) else {
+        return false;
+    };
+    // A new priority moves an unresolved ticket to its place in the work order
+    if let Some(new_priority) = priority {
+        let old_key = (Reverse(ticket.priority), ticket_id);
+        if queue.work_order.remove(&old_key) {
+            queue.work_order.insert((Reverse(new_priority), ticket_id));
         }
     }
-}
 
-pub fn switch_to_hashmap(queue: &mut CustomerSupportQueue) {
-    if !queue.use_hashmap {
-        for ticket in queue.tickets.drain(..) {
-            queue.ticket_map.insert(ticket.id, ticket);
-        }
-        queue.use_hashmap = true;
-        println!("Switched to HashMap for efficient lookups!");
+    if let Some(name) = customer_name {
+        set_ticket_customer(&mut queue.tickets, ticket_id, name);
     }
+    if let Some(pri) = priority {
+        set_ticket_priority(&mut queue.tickets, ticket_id, pri);
+    }
+    if let Some(desc) = issue_description
+        && let Some(ticket) = get_ticket_mut(queue, ticket_id)
+    {
+        revise_description(ticket, desc);
+    }
+    true
 }
+
+/// Tickets used to move from a `Vec` to a `HashMap` here once the queue grew. The store now
+/// gives fast lookups from the start, so this does nothing and is kept for existing callers.
+pub fn switch_to_hashmap(_queue: &mut CustomerSupportQueue) {}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::ticket::{Priority, Ticket};
+
+/// How long a ticket of one priority may wait for a first response and for a resolution,
+/// both counted from when it was created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlaTarget {
+    pub response: Duration,
+    pub resolution: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlaConfig {
+    pub low: SlaTarget,
+    pub medium: SlaTarget,
+    pub high: SlaTarget,
+    pub critical: SlaTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlaClock {
+    Response,
+    Resolution,
+}
+
+/// The deadline a ticket is currently working towards. `remaining` is negative once it has passed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlaStatus {
+    pub clock: SlaClock,
+    pub deadline: DateTime<Utc>,
+    pub remaining: Duration,
+}
+
+pub fn create_sla_config() -> SlaConfig {
+    SlaConfig {
+        low: SlaTarget {
+            response: Duration::hours(8),
+            resolution: Duration::days(3),
+        },
+        medium: SlaTarget {
+            response: Duration::hours(4),
+            resolution: Duration::days(1),
+        },
+        high: SlaTarget {
+            response: Duration::hours(1),
+            resolution: Duration::hours(8),
+        },
+        critical: SlaTarget {
+            response: Duration::minutes(15),
+            resolution: Duration::hours(4),
+        },
+    }
+}
+
+pub fn sla_target(config: &SlaConfig, priority: &Priority) -> SlaTarget {
+    match priority {
+        Priority::Low => config.low,
+        Priority::Medium => config.medium,
+        Priority::High => config.high,
+        Priority::Critical => config.critical,
+    }
+}
+
+pub fn set_sla_target(config: &mut SlaConfig, priority: &Priority, target: SlaTarget) {
+    match priority {
+        Priority::Low => config.low = target,
+        Priority::Medium => config.medium = target,
+        Priority::High => config.high = target,
+        Priority::Critical => config.critical = target,
+    }
+}
+
+pub fn response_deadline(config: &SlaConfig, ticket: &Ticket) -> DateTime<Utc> {
+    ticket.created_at + sla_target(config, &ticket.priority).response
+}
+
+pub fn resolution_deadline(config: &SlaConfig, ticket: &Ticket) -> DateTime<Utc> {
+    ticket.created_at + sla_target(config, &ticket.priority).resolution
+}
+
+/// The response deadline until the ticket has had a response, then the resolution deadline.
+/// `None` once the ticket is resolved.
+pub fn sla_status(config: &SlaConfig, ticket: &Ticket, now: DateTime<Utc>) -> Option<SlaStatus> {
+    if ticket.resolved_at.is_some() {
+        return None;
+    }
+    let (clock, deadline) = match ticket.first_response_at {
+        None => (SlaClock::Response, response_deadline(config, ticket)),
+        Some(_) => (SlaClock::Resolution, resolution_deadline(config, ticket)),
+    };
+    Some(SlaStatus {
+        clock,
+        deadline,
+        remaining: deadline - now,
+    })
+}
+
+/// Every deadline the ticket has missed, including a response or resolution that came late.
+pub fn breached_clocks(config: &SlaConfig, ticket: &Ticket, now: DateTime<Utc>) -> Vec<SlaClock> {
+    let mut breached = Vec::new();
+    if ticket.first_response_at.unwrap_or(now) > response_deadline(config, ticket) {
+        breached.push(SlaClock::Response);
+    }
+    if ticket.resolved_at.unwrap_or(now) > resolution_deadline(config, ticket) {
+        breached.push(SlaClock::Resolution);
+    }
+    breached
+}
+
+pub fn clock_to_string(clock: &SlaClock) -> &'static str {
+    match clock {
+        SlaClock::Response => "Response",
+        SlaClock::Resolution => "Resolution",
+    }
+}
+
+/// Short form such as `2d 3h`, `1h 05m` or `12m`, ignoring the sign.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().abs();
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
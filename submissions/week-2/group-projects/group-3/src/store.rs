@@ -0,0 +1,146 @@
+use crate::ticket::{Priority, Ticket};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Tickets in the order they were added, with lookups by id, priority and customer.
+///
+/// The fields are private so the indexes always match the tickets: priority and customer name
+/// are changed through `set_ticket_priority` and `set_ticket_customer`, never in place.
+#[derive(Debug, Clone, Default)]
+pub struct TicketStore {
+    tickets: Vec<Ticket>,
+    /// Ticket id to its index in `tickets`.
+    positions: HashMap<u32, usize>,
+    by_priority: BTreeMap<Priority, BTreeSet<u32>>,
+    /// Keyed by the lowercased, trimmed customer name.
+    by_customer: HashMap<String, BTreeSet<u32>>,
+}
+
+pub fn create_store() -> TicketStore {
+    TicketStore::default()
+}
+
+fn customer_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn index_ticket(store: &mut TicketStore, ticket_id: u32, priority: Priority, customer: &str) {
+    store
+        .by_priority
+        .entry(priority)
+        .or_default()
+        .insert(ticket_id);
+    store
+        .by_customer
+        .entry(customer_key(customer))
+        .or_default()
+        .insert(ticket_id);
+}
+
+fn unindex_ticket(store: &mut TicketStore, ticket_id: u32, priority: Priority, customer: &str) {
+    if let Some(ids) = store.by_priority.get_mut(&priority) {
+        ids.remove(&ticket_id);
+        if ids.is_empty() {
+            store.by_priority.remove(&priority);
+        }
+    }
+    let key = customer_key(customer);
+    if let Some(ids) = store.by_customer.get_mut(&key) {
+        ids.remove(&ticket_id);
+        if ids.is_empty() {
+            store.by_customer.remove(&key);
+        }
+    }
+}
+
+/// Adds a ticket at the end. Returns false, leaving the store unchanged, if the id is taken.
+pub fn insert_ticket(store: &mut TicketStore, ticket: Ticket) -> bool {
+    if store.positions.contains_key(&ticket.id) {
+        return false;
+    }
+    index_ticket(store, ticket.id, ticket.priority, &ticket.customer_name);
+    store.positions.insert(ticket.id, store.tickets.len());
+    store.tickets.push(ticket);
+    true
+}
+
+pub fn find_ticket(store: &TicketStore, ticket_id: u32) -> Option<&Ticket> {
+    let index = *store.positions.get(&ticket_id)?;
+    store.tickets.get(index)
+}
+
+/// Mutable access for the queue. Callers must not change `priority` or `customer_name` here.
+pub(crate) fn find_ticket_mut(store: &mut TicketStore, ticket_id: u32) -> Option<&mut Ticket> {
+    let index = *store.positions.get(&ticket_id)?;
+    store.tickets.get_mut(index)
+}
+
+/// Takes a ticket out, keeping the others in order. Later tickets shift down one place, so this
+/// is linear in the number of tickets after it.
+pub fn take_ticket(store: &mut TicketStore, ticket_id: u32) -> Option<Ticket> {
+    let index = store.positions.remove(&ticket_id)?;
+    let ticket = store.tickets.remove(index);
+    unindex_ticket(store, ticket_id, ticket.priority, &ticket.customer_name);
+    for later in &store.tickets[index..] {
+        if let Some(position) = store.positions.get_mut(&later.id) {
+            *position -= 1;
+        }
+    }
+    Some(ticket)
+}
+
+pub fn ticket_count(store: &TicketStore) -> usize {
+    store.tickets.len()
+}
+
+/// Every ticket in the order it was added.
+pub fn tickets_in_order(store: &TicketStore) -> &[Ticket] {
+    &store.tickets
+}
+
+/// Tickets of one priority, oldest first.
+pub fn tickets_with_priority(store: &TicketStore, priority: Priority) -> Vec<&Ticket> {
+    store
+        .by_priority
+        .get(&priority)
+        .into_iter()
+        .flatten()
+        .filter_map(|id| find_ticket(store, *id))
+        .collect()
+}
+
+/// A customer's tickets, oldest first. The name is matched ignoring case and surrounding spaces.
+pub fn tickets_for_customer<'a>(store: &'a TicketStore, customer_name: &str) -> Vec<&'a Ticket> {
+    store
+        .by_customer
+        .get(&customer_key(customer_name))
+        .into_iter()
+        .flatten()
+        .filter_map(|id| find_ticket(store, *id))
+        .collect()
+}
+
+pub fn set_ticket_priority(store: &mut TicketStore, ticket_id: u32, priority: Priority) -> bool {
+    let Some(ticket) = find_ticket(store, ticket_id) else {
+        return false;
+    };
+    let (old, customer) = (ticket.priority, ticket.customer_name.clone());
+    unindex_ticket(store, ticket_id, old, &customer);
+    index_ticket(store, ticket_id, priority, &customer);
+    if let Some(ticket) = find_ticket_mut(store, ticket_id) {
+        ticket.priority = priority;
+    }
+    true
+}
+
+pub fn set_ticket_customer(store: &mut TicketStore, ticket_id: u32, customer_name: String) -> bool {
+    let Some(ticket) = find_ticket(store, ticket_id) else {
+        return false;
+    };
+    let (priority, old) = (ticket.priority, ticket.customer_name.clone());
+    unindex_ticket(store, ticket_id, priority, &old);
+    index_ticket(store, ticket_id, priority, &customer_name);
+    if let Some(ticket) = find_ticket_mut(store, ticket_id) {
+        ticket.customer_name = customer_name;
+    }
+    true
+}
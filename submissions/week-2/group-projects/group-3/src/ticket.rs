@@ -1,4 +1,10 @@
-#[derive(Debug, Clone, PartialEq)]
+use crate::agent::Assignment;
+use crate::message::{Author, Message, Visibility, create_message, edit_message, last_changed_at};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Declared from least to most urgent, so `Priority::Critical` compares greatest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Priority {
     Low,
     Medium,
@@ -6,13 +12,72 @@ pub enum Priority {
     Critical,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TicketStatus {
+    Open,
+    InProgress,
+    WaitingOnCustomer,
+    Resolved,
+    Closed,
+    Reopened,
+}
+
+/// One move through the lifecycle, kept on the ticket so its history survives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub from: TicketStatus,
+    pub to: TicketStatus,
+    pub at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionError {
+    TicketNotFound(u32),
+    NotAllowed {
+        from: TicketStatus,
+        to: TicketStatus,
+    },
+    MissingResolutionNote,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransitionError::TicketNotFound(id) => write!(f, "Ticket #{} not found", id),
+            TransitionError::NotAllowed { from, to } => write!(
+                f,
+                "A ticket cannot go from {} to {}",
+                status_to_string(from),
+                status_to_string(to)
+            ),
+            TransitionError::MissingResolutionNote => {
+                write!(f, "A resolution note is required to resolve a ticket")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Ticket {
     pub id: u32,
     pub customer_name: String,
+    /// The current text of the first message in `thread`.
     pub issue_description: String,
     pub priority: Priority,
-    pub status: String,
+    pub status: TicketStatus,
+    pub created_at: DateTime<Utc>,
+    pub first_response_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// The note given when the ticket was last resolved; cleared when it is reopened.
+    pub resolution_note: Option<String>,
+    pub status_history: Vec<StatusChange>,
+    /// Lowercase skill an agent needs to take the ticket, if any.
+    pub required_skill: Option<String>,
+    pub assigned_agent: Option<u32>,
+    pub assignment_history: Vec<Assignment>,
+    /// The conversation in the order it happened, starting with the customer's description.
+    pub thread: Vec<Message>,
 }
 
 pub fn string_to_priority(s: &str) -> Option<Priority> {
@@ -34,17 +99,98 @@ pub fn priority_to_string(priority: &Priority) -> &str {
     }
 }
 
+pub fn status_to_string(status: &TicketStatus) -> &'static str {
+    match status {
+        TicketStatus::Open => "Open",
+        TicketStatus::InProgress => "In Progress",
+        TicketStatus::WaitingOnCustomer => "Waiting on Customer",
+        TicketStatus::Resolved => "Resolved",
+        TicketStatus::Closed => "Closed",
+        TicketStatus::Reopened => "Reopened",
+    }
+}
+
+/// Accepts the display names, ignoring case, spaces, dashes and underscores.
+pub fn string_to_status(s: &str) -> Option<TicketStatus> {
+    let key: String = s
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+    match key.as_str() {
+        "open" => Some(TicketStatus::Open),
+        "inprogress" => Some(TicketStatus::InProgress),
+        "waitingoncustomer" | "waiting" => Some(TicketStatus::WaitingOnCustomer),
+        "resolved" => Some(TicketStatus::Resolved),
+        "closed" => Some(TicketStatus::Closed),
+        "reopened" => Some(TicketStatus::Reopened),
+        _ => None,
+    }
+}
+
+/// Statuses a ticket may move to next. A ticket must be resolved before it can be closed, and
+/// only resolved or closed tickets can be reopened.
+pub fn allowed_transitions(status: &TicketStatus) -> &'static [TicketStatus] {
+    match status {
+        TicketStatus::Open | TicketStatus::Reopened => &[
+            TicketStatus::InProgress,
+            TicketStatus::WaitingOnCustomer,
+            TicketStatus::Resolved,
+        ],
+        TicketStatus::InProgress => &[TicketStatus::WaitingOnCustomer, TicketStatus::Resolved],
+        TicketStatus::WaitingOnCustomer => &[TicketStatus::InProgress, TicketStatus::Resolved],
+        TicketStatus::Resolved => &[TicketStatus::Closed, TicketStatus::Reopened],
+        TicketStatus::Closed => &[TicketStatus::Reopened],
+    }
+}
+
+/// Whether a ticket in this status still needs work.
+pub fn is_active(status: &TicketStatus) -> bool {
+    !matches!(status, TicketStatus::Resolved | TicketStatus::Closed)
+}
+
 pub fn create_ticket(
     id: u32,
     customer_name: String,
     issue_description: String,
     priority: Priority,
 ) -> Ticket {
+    let created_at = Utc::now();
+    let description = create_message(
+        Author::Customer(customer_name.clone()),
+        Visibility::Public,
+        issue_description.clone(),
+        created_at,
+    );
     Ticket {
         id,
         customer_name,
         issue_description,
         priority,
-        status: "Open".to_string(),
+        status: TicketStatus::Open,
+        created_at,
+        first_response_at: None,
+        resolved_at: None,
+        resolution_note: None,
+        status_history: Vec::new(),
+        required_skill: None,
+        assigned_agent: None,
+        assignment_history: Vec::new(),
+        thread: vec![description],
     }
 }
+
+/// Changes the description, keeping the earlier text as a revision of the first message.
+pub fn revise_description(ticket: &mut Ticket, description: String) {
+    if let Some(first) = ticket.thread.first_mut() {
+        edit_message(first, description.clone(), Utc::now());
+    }
+    ticket.issue_description = description;
+}
+
+/// The latest message, edit or status change on the ticket.
+pub fn last_activity(ticket: &Ticket) -> DateTime<Utc> {
+    let messages = ticket.thread.iter().map(last_changed_at);
+    let changes = ticket.status_history.iter().map(|change| change.at);
+    messages.chain(changes).max().unwrap_or(ticket.created_at)
+}
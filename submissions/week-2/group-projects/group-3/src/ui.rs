@@ -1,8 +1,20 @@
+use crate::agent::{AssignmentStrategy, strategy_to_string};
+use crate::message::{
+    Author, Message, Visibility, author_to_string, last_changed_at, visibility_to_string,
+};
+use crate::metrics::{DurationStats, compute_metrics, metrics_to_json};
 use crate::queue::{
-    CustomerSupportQueue, add_ticket_to_queue, display_tickets, get_ticket_from_queue,
-    remove_ticket_from_queue, update_ticket_in_queue,
+    CustomerSupportQueue, add_agent, add_message, add_ticket_with_skill, agent_load, agent_tickets,
+    assign_ticket, breached_tickets, change_ticket_status, display_tickets, get_agent,
+    get_ticket_from_queue, next_ticket, print_ticket_header, print_ticket_row, reassign_ticket,
+    remove_ticket_from_queue, respond_to_ticket, sla_summary, update_ticket_in_queue,
+};
+use crate::sla::{breached_clocks, clock_to_string, format_duration};
+use crate::ticket::{
+    Priority, Ticket, TicketStatus, allowed_transitions, priority_to_string, status_to_string,
+    string_to_priority,
 };
-use crate::ticket::{Priority, priority_to_string, string_to_priority};
+use chrono::{Duration, Utc};
 use std::io::{self, Write};
 
 pub fn get_user_input(prompt: &str) -> String {
@@ -42,8 +54,17 @@ pub fn add_ticket(queue: &mut CustomerSupportQueue) {
         }
     };
 
-    let ticket_id = add_ticket_to_queue(queue, customer_name, issue_description, priority);
+    let skill = get_user_input("Required skill (optional): ");
+    let skill = (!skill.is_empty()).then_some(skill);
+
+    let ticket_id = add_ticket_with_skill(queue, customer_name, issue_description, priority, skill);
     println!("Ticket #{} added successfully!", ticket_id);
+    if let Some(agent) = get_ticket_from_queue(queue, ticket_id)
+        .and_then(|ticket| ticket.assigned_agent)
+        .and_then(|id| get_agent(queue, id))
+    {
+        println!("Assigned to {} (agent #{}).", agent.name, agent.id);
+    }
 }
 
 pub fn view_tickets(queue: &CustomerSupportQueue) {
@@ -62,10 +83,18 @@ pub fn remove_ticket(queue: &mut CustomerSupportQueue) {
         }
     };
 
-    if remove_ticket_from_queue(queue, ticket_id) {
-        println!("Ticket #{} removed successfully!", ticket_id);
-    } else {
-        println!("Error: Ticket #{} not found.", ticket_id);
+    match get_ticket_from_queue(queue, ticket_id) {
+        None => println!("Error: Ticket #{} not found.", ticket_id),
+        Some(ticket) if ticket.status != TicketStatus::Closed => println!(
+            "Error: Ticket #{} is {}. Only closed tickets can be removed.",
+            ticket_id,
+            status_to_string(&ticket.status)
+        ),
+        Some(_) => {
+            if remove_ticket_from_queue(queue, ticket_id) {
+                println!("Ticket #{} removed successfully!", ticket_id);
+            }
+        }
     }
 }
 
@@ -127,6 +156,409 @@ pub fn edit_ticket(queue: &mut CustomerSupportQueue) {
     }
 }
 
+pub fn work_next_ticket(queue: &mut CustomerSupportQueue) {
+    println!("\n=== NEXT TICKET ===");
+
+    let Some(ticket) = next_ticket(queue) else {
+        println!("No open tickets. Nice work!");
+        return;
+    };
+    let ticket_id = ticket.id;
+    println!("Ticket #{} from {}", ticket.id, ticket.customer_name);
+    println!("Priority: {}", priority_to_string(&ticket.priority));
+    println!("Description: {}", ticket.issue_description);
+    println!("SLA: {}", sla_summary(queue, ticket, Utc::now()));
+
+    println!("\n1. Record first response");
+    println!("2. Change status");
+    println!("0. Back");
+    match get_user_input("Enter your choice: ").as_str() {
+        "1" if respond_to_ticket(queue, ticket_id) => {
+            println!("Response recorded for ticket #{}.", ticket_id);
+        }
+        "2" => change_status_of(queue, ticket_id),
+        _ => {}
+    }
+}
+
+pub fn print_status_history(ticket: &Ticket) {
+    println!(
+        "Created {} as Open",
+        ticket.created_at.format("%Y-%m-%d %H:%M")
+    );
+    for change in &ticket.status_history {
+        print!(
+            "{} {} -> {}",
+            change.at.format("%Y-%m-%d %H:%M"),
+            status_to_string(&change.from),
+            status_to_string(&change.to)
+        );
+        match &change.note {
+            Some(note) => println!(": {}", note),
+            None => println!(),
+        }
+    }
+}
+
+pub fn change_status(queue: &mut CustomerSupportQueue) {
+    println!("\n=== CHANGE TICKET STATUS ===");
+
+    let ticket_id: u32 = match get_user_input("Enter ticket ID: ").parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Error: Invalid ticket ID.");
+            return;
+        }
+    };
+    change_status_of(queue, ticket_id);
+}
+
+fn change_status_of(queue: &mut CustomerSupportQueue, ticket_id: u32) {
+    let Some(ticket) = get_ticket_from_queue(queue, ticket_id) else {
+        println!("Error: Ticket #{} not found.", ticket_id);
+        return;
+    };
+    print_status_history(ticket);
+
+    let choices = allowed_transitions(&ticket.status);
+    println!(
+        "\nTicket #{} is {}. Move it to:",
+        ticket_id,
+        status_to_string(&ticket.status)
+    );
+    for (index, status) in choices.iter().enumerate() {
+        println!("{}. {}", index + 1, status_to_string(status));
+    }
+    let Some(to) = get_user_input("Enter your choice: ")
+        .parse::<usize>()
+        .ok()
+        .and_then(|choice| choice.checked_sub(1))
+        .and_then(|index| choices.get(index))
+        .copied()
+    else {
+        println!("Error: Invalid choice.");
+        return;
+    };
+
+    let note = if to == TicketStatus::Resolved {
+        get_user_input("Resolution note (required): ")
+    } else {
+        get_user_input("Note (optional): ")
+    };
+    match change_ticket_status(queue, ticket_id, to, Some(note)) {
+        Ok(()) => println!("Ticket #{} is now {}.", ticket_id, status_to_string(&to)),
+        Err(e) => println!("Error: {}.", e),
+    }
+}
+
+pub fn view_breached_tickets(queue: &CustomerSupportQueue) {
+    let now = Utc::now();
+    let breached = breached_tickets(queue, now);
+    if breached.is_empty() {
+        println!("No tickets have breached their SLA.");
+        return;
+    }
+
+    println!("\n=== SLA BREACHES ===");
+    print_ticket_header();
+    for ticket in breached {
+        print_ticket_row(queue, ticket, now);
+        let missed: Vec<&str> = breached_clocks(&queue.sla, ticket, now)
+            .iter()
+            .map(clock_to_string)
+            .collect();
+        println!("      Missed: {}", missed.join(", "));
+    }
+    println!("{:-<136}", "");
+}
+
+pub fn print_message(message: &Message) {
+    let label = match message.visibility {
+        Visibility::Public => String::new(),
+        Visibility::Internal => " [internal note]".to_string(),
+    };
+    println!(
+        "{} {}{}:",
+        message.at.format("%Y-%m-%d %H:%M"),
+        author_to_string(&message.author),
+        label
+    );
+    for line in message.body.lines() {
+        println!("    {}", line);
+    }
+    if !message.revisions.is_empty() {
+        println!(
+            "    (edited {} time(s), last at {})",
+            message.revisions.len(),
+            last_changed_at(message).format("%Y-%m-%d %H:%M")
+        );
+        for revision in &message.revisions {
+            println!(
+                "    was, until {}: {}",
+                revision.replaced_at.format("%Y-%m-%d %H:%M"),
+                revision.body
+            );
+        }
+    }
+}
+
+/// Staff view of a ticket's conversation, internal notes included, with options to add to it.
+pub fn view_thread(queue: &mut CustomerSupportQueue) {
+    println!("\n=== TICKET THREAD ===");
+
+    let ticket_id: u32 = match get_user_input("Enter ticket ID: ").parse() {
+        Ok(id) => id,
+        Err(_) => {
+            println!("Error: Invalid ticket ID.");
+            return;
+        }
+    };
+
+    loop {
+        let Some(ticket) = get_ticket_from_queue(queue, ticket_id) else {
+            println!("Error: Ticket #{} not found.", ticket_id);
+            return;
+        };
+        println!(
+            "\nTicket #{} from {} ({}, {})",
+            ticket.id,
+            ticket.customer_name,
+            priority_to_string(&ticket.priority),
+            status_to_string(&ticket.status)
+        );
+        println!("{:-<60}", "");
+        for message in &ticket.thread {
+            print_message(message);
+        }
+        println!("{:-<60}", "");
+        let customer_name = ticket.customer_name.clone();
+
+        println!("1. Add Public Reply");
+        println!("2. Add Internal Note");
+        println!("3. Add Customer Message");
+        println!("0. Back");
+        let (author, visibility) = match get_user_input("Enter your choice: ").as_str() {
+            "1" => (
+                Author::Agent(get_user_input("Your name: ")),
+                Visibility::Public,
+            ),
+            "2" => (
+                Author::Agent(get_user_input("Your name: ")),
+                Visibility::Internal,
+            ),
+            "3" => (Author::Customer(customer_name), Visibility::Public),
+            "0" => return,
+            _ => {
+                println!("Invalid choice.");
+                continue;
+            }
+        };
+        let body = get_user_input(&format!("{}: ", visibility_to_string(&visibility)));
+        if let Err(e) = add_message(queue, ticket_id, author, visibility, body) {
+            println!("Error: {}.", e);
+        }
+    }
+}
+
+fn stats_to_string(stats: &DurationStats) -> String {
+    match (stats.mean_seconds, stats.p90_seconds) {
+        (Some(mean), Some(p90)) => format!(
+            "mean {}, p90 {} ({} tickets)",
+            format_duration(Duration::seconds(mean)),
+            format_duration(Duration::seconds(p90)),
+            stats.count
+        ),
+        _ => "no data".to_string(),
+    }
+}
+
+pub fn view_dashboard(queue: &CustomerSupportQueue) {
+    let metrics = compute_metrics(queue, Utc::now());
+
+    println!("\n=== SUPPORT DASHBOARD ===");
+    println!("\nTickets per day:");
+    if metrics.daily.is_empty() {
+        println!("  No tickets yet.");
+    }
+    for day in &metrics.daily {
+        println!(
+            "  {}  opened {:>3}  closed {:>3}",
+            day.date, day.opened, day.closed
+        );
+    }
+
+    println!("\nResponse and resolution times:");
+    for priority in &metrics.by_priority {
+        println!("  {}", priority.priority);
+        println!(
+            "    First response: {}",
+            stats_to_string(&priority.time_to_first_response)
+        );
+        println!(
+            "    Resolution:     {}",
+            stats_to_string(&priority.time_to_resolve)
+        );
+    }
+
+    println!("\nBacklog age (unresolved tickets):");
+    for bucket in &metrics.backlog_age {
+        println!(
+            "  {:<8} {:>3} {}",
+            bucket.label,
+            bucket.count,
+            "#".repeat(bucket.count)
+        );
+    }
+
+    println!("\nTickets per customer:");
+    for customer in &metrics.customers {
+        println!(
+            "  {:<20} {:>3} total, {:>3} open",
+            customer.customer, customer.total, customer.open
+        );
+    }
+
+    println!("\n1. Show as JSON");
+    println!("2. Save JSON to file");
+    println!("0. Back");
+    match get_user_input("Enter your choice: ").as_str() {
+        "1" => println!("{}", metrics_to_json(&metrics)),
+        "2" => {
+            let path = get_user_input("File path: ");
+            match std::fs::write(&path, metrics_to_json(&metrics)) {
+                Ok(()) => println!("Metrics saved to {}.", path),
+                Err(e) => println!("Error: Could not write {}: {}.", path, e),
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn manage_agents(queue: &mut CustomerSupportQueue) {
+    loop {
+        println!("\n=== AGENTS ===");
+        println!(
+            "Assignment of new tickets: {}",
+            strategy_to_string(&queue.assignment_strategy)
+        );
+        if queue.agents.is_empty() {
+            println!("No agents yet.");
+        } else {
+            println!("{:<5} {:<20} {:<10} Skills", "ID", "Name", "Load");
+            for agent in &queue.agents {
+                println!(
+                    "{:<5} {:<20} {:<10} {}",
+                    agent.id,
+                    agent.name,
+                    format!("{}/{}", agent_load(queue, agent.id), agent.capacity),
+                    agent.skills.join(", ")
+                );
+            }
+        }
+
+        println!("\n1. Add Agent");
+        println!("2. View Agent's Tickets");
+        println!("3. Assign Ticket");
+        println!("4. Reassign Ticket");
+        println!("5. Change Assignment Strategy");
+        println!("0. Back");
+        match get_user_input("Enter your choice: ").as_str() {
+            "1" => add_agent_from_user(queue),
+            "2" => view_agent_tickets(queue),
+            "3" => assign_from_user(queue, false),
+            "4" => assign_from_user(queue, true),
+            "5" => change_strategy(queue),
+            "0" => return,
+            _ => println!("Invalid choice."),
+        }
+    }
+}
+
+fn add_agent_from_user(queue: &mut CustomerSupportQueue) {
+    let name = get_user_input("Agent name: ");
+    if name.is_empty() {
+        println!("Error: Agent name cannot be empty.");
+        return;
+    }
+    let skills = get_user_input("Skills (comma separated): ")
+        .split(',')
+        .map(|skill| skill.to_string())
+        .collect();
+    let capacity: usize = match get_user_input("Capacity (open tickets): ").parse() {
+        Ok(capacity) if capacity > 0 => capacity,
+        _ => {
+            println!("Error: Capacity must be a positive number.");
+            return;
+        }
+    };
+    let agent_id = add_agent(queue, name, skills, capacity);
+    println!("Agent #{} added.", agent_id);
+}
+
+fn view_agent_tickets(queue: &CustomerSupportQueue) {
+    let Some(agent) = get_user_input("Agent ID: ")
+        .parse()
+        .ok()
+        .and_then(|id| get_agent(queue, id))
+    else {
+        println!("Error: Agent not found.");
+        return;
+    };
+    let tickets = agent_tickets(queue, agent.id);
+    if tickets.is_empty() {
+        println!("{} has no open tickets.", agent.name);
+        return;
+    }
+
+    println!("\n=== TICKETS FOR {} ===", agent.name.to_uppercase());
+    print_ticket_header();
+    let now = Utc::now();
+    for ticket in tickets {
+        print_ticket_row(queue, ticket, now);
+    }
+    println!("{:-<136}", "");
+}
+
+fn assign_from_user(queue: &mut CustomerSupportQueue, reassign: bool) {
+    let (Ok(ticket_id), Ok(agent_id)) = (
+        get_user_input("Ticket ID: ").parse::<u32>(),
+        get_user_input("Agent ID: ").parse::<u32>(),
+    ) else {
+        println!("Error: Invalid ID.");
+        return;
+    };
+
+    let result = if reassign {
+        let reason = get_user_input("Reason for reassignment: ");
+        reassign_ticket(queue, ticket_id, agent_id, reason)
+    } else {
+        assign_ticket(queue, ticket_id, agent_id)
+    };
+    match result {
+        Ok(()) => println!("Ticket #{} assigned to agent #{}.", ticket_id, agent_id),
+        Err(e) => println!("Error: {}.", e),
+    }
+}
+
+fn change_strategy(queue: &mut CustomerSupportQueue) {
+    println!("1. Manual");
+    println!("2. Round-robin");
+    println!("3. Least-loaded");
+    queue.assignment_strategy = match get_user_input("Enter your choice: ").as_str() {
+        "1" => AssignmentStrategy::Manual,
+        "2" => AssignmentStrategy::RoundRobin,
+        "3" => AssignmentStrategy::LeastLoaded,
+        _ => {
+            println!("Invalid choice.");
+            return;
+        }
+    };
+    println!(
+        "New tickets now use {} assignment.",
+        strategy_to_string(&queue.assignment_strategy)
+    );
+}
+
 pub fn display_menu(stage: u32) {
     println!("\n=== CUSTOMER SUPPORT QUEUE SYSTEM ===");
     println!("Stage {} - Current Features:", stage);
@@ -141,6 +573,13 @@ pub fn display_menu(stage: u32) {
         println!("4. Edit Ticket");
     }
 
+    println!("5. Work Next Ticket");
+    println!("6. View SLA Breaches");
+    println!("7. Change Ticket Status");
+    println!("8. Manage Agents");
+    println!("9. View Ticket Thread");
+    println!("10. Dashboard");
+
     println!("0. Exit");
     println!("Enter your choice: ");
 }